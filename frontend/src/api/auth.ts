@@ -23,6 +23,7 @@ export interface LoginRequest {
 
 export interface LoginResponse {
     token: string;
+    refresh_token: string;
     pid: string;
     name: string;
     email: string;
@@ -35,6 +36,17 @@ export interface ResendOtpRequest {
 // Backend returns empty JSON on success
 export type ResendOtpResponse = Record<string, never>;
 
+export interface MagicLinkRequest {
+    email: string;
+}
+
+// Backend returns empty JSON on success
+export type MagicLinkResponse = Record<string, never>;
+
+export interface VerifyMagicLinkRequest {
+    token: string;
+}
+
 export interface CurrentUserResponse {
     pid: string;
     name: string;
@@ -98,6 +110,7 @@ async function login(data: LoginRequest): Promise<LoginResponse> {
             const token = createMockJwt(data.email);
             return {
                 token,
+                refresh_token: "mock-refresh-token",
                 pid: `mock-pid-${Date.now()}`,
                 name: data.email.split("@")[0],
                 email: data.email,
@@ -125,6 +138,39 @@ async function resendOtp(data: ResendOtpRequest): Promise<ResendOtpResponse> {
     return apiFetch("/auth/send-otp", { data });
 }
 
+/**
+ * Email a sign-in link instead of an OTP
+ * POST /api/auth/magic-link
+ */
+async function requestMagicLink(
+    data: MagicLinkRequest,
+): Promise<MagicLinkResponse> {
+    return apiFetch("/auth/magic-link", { data });
+}
+
+/**
+ * Login with the token of a sign-in link, which can only be used once
+ * POST /api/auth/magic-link/verify
+ */
+async function verifyMagicLink(
+    data: VerifyMagicLinkRequest,
+): Promise<LoginResponse> {
+    return apiFetch("/auth/magic-link/verify", { data });
+}
+
+/**
+ * Revoke the refresh token, ending the session
+ * POST /api/auth/logout
+ */
+export async function logout(refreshToken: string): Promise<void> {
+    if (CONFIG.useMock) return;
+
+    await apiFetch("/auth/logout", {
+        data: { refresh_token: refreshToken },
+        token: null,
+    });
+}
+
 /**
  * Get current authenticated user
  * GET /api/auth/current
@@ -154,6 +200,7 @@ export async function getCurrentUser(
 export const authApiKeys = {
     all: ["auth"] as const,
     otp: () => [...authApiKeys.all, "otp"] as const,
+    magicLink: () => [...authApiKeys.all, "magic-link"] as const,
 };
 
 // --- Hooks ---
@@ -177,3 +224,17 @@ export function useResendOtp() {
         mutationKey: authApiKeys.otp(),
     });
 }
+
+export function useRequestMagicLink() {
+    return useMutation({
+        mutationFn: requestMagicLink,
+        mutationKey: authApiKeys.magicLink(),
+    });
+}
+
+export function useVerifyMagicLink() {
+    return useMutation({
+        mutationFn: verifyMagicLink,
+        mutationKey: authApiKeys.magicLink(),
+    });
+}
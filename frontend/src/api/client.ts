@@ -5,6 +5,7 @@ const CONFIG = {
     // Check for Rsbuild/Vite env vars
     apiBaseUrl: import.meta.env.PUBLIC_API_URL || "",
     authTokenKey: "auth_token",
+    refreshTokenKey: "refresh_token",
 } as const;
 
 // --- Types ---
@@ -15,6 +16,8 @@ interface ApiOptions {
     headers?: Record<string, string>;
     /** HTTP status codes for which error toasts should be suppressed */
     suppressToastOn?: number[];
+    /** Whether the request was already retried after refreshing the session */
+    retried?: boolean;
 }
 
 // --- Utilities ---
@@ -23,6 +26,54 @@ export const getStoredToken = (): string | null => {
     return localStorage.getItem(CONFIG.authTokenKey);
 };
 
+export const getStoredRefreshToken = (): string | null => {
+    if (typeof window === "undefined") return null;
+    return localStorage.getItem(CONFIG.refreshTokenKey);
+};
+
+export const storeTokens = (token: string, refreshToken: string): void => {
+    localStorage.setItem(CONFIG.authTokenKey, token);
+    localStorage.setItem(CONFIG.refreshTokenKey, refreshToken);
+};
+
+export const clearStoredTokens = (): void => {
+    localStorage.removeItem(CONFIG.authTokenKey);
+    localStorage.removeItem(CONFIG.refreshTokenKey);
+};
+
+// Shared so concurrent requests failing with 401 refresh the session only once,
+// a refresh token can't be used twice
+let pendingRefresh: Promise<string | null> | null = null;
+
+/**
+ * Exchanges the stored refresh token for a new access token and refresh token
+ * POST /api/auth/refresh
+ */
+export function refreshSession(): Promise<string | null> {
+    const refreshToken = getStoredRefreshToken();
+    if (!refreshToken) return Promise.resolve(null);
+
+    pendingRefresh ??= fetch(`${CONFIG.apiBaseUrl}/api/auth/refresh`, {
+        method: "POST",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify({ refresh_token: refreshToken }),
+    })
+        .then(async (response) => {
+            if (!response.ok) {
+                clearStoredTokens();
+                return null;
+            }
+            const session = await response.json();
+            storeTokens(session.token, session.refresh_token);
+            return session.token as string;
+        })
+        .catch(() => null)
+        .finally(() => {
+            pendingRefresh = null;
+        });
+    return pendingRefresh;
+}
+
 export async function apiFetch<T>(
     endpoint: string,
     options: ApiOptions = {},
@@ -33,6 +84,7 @@ export async function apiFetch<T>(
         token = getStoredToken(),
         headers = {},
         suppressToastOn = [],
+        retried = false,
     } = options;
 
     const requestHeaders: Record<string, string> = {
@@ -50,6 +102,23 @@ export async function apiFetch<T>(
             headers: requestHeaders,
             body: data ? JSON.stringify(data) : undefined,
         });
+        // The access token expired, get a new one and try once more
+        if (
+            response.status === 401 &&
+            !retried &&
+            token &&
+            token === getStoredToken()
+        ) {
+            const refreshed = await refreshSession();
+            if (refreshed) {
+                return apiFetch(endpoint, {
+                    ...options,
+                    token: refreshed,
+                    retried: true,
+                });
+            }
+        }
+
         if (!response.ok) {
             const status = response.status;
             const errorData = await response.json().catch(() => ({}));
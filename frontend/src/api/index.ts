@@ -4,13 +4,18 @@ export {
     getCurrentUser,
     type LoginRequest,
     type LoginResponse,
+    type MagicLinkRequest,
+    type MagicLinkResponse,
     type ResendOtpRequest,
     type ResendOtpResponse,
     type SendOtpRequest,
     type SendOtpResponse,
+    useRequestMagicLink,
     useRequestOtp,
     useResendOtp,
+    useVerifyMagicLink,
     useVerifyOtp,
+    type VerifyMagicLinkRequest,
 } from "./auth";
 
 export {
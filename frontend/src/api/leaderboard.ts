@@ -6,13 +6,14 @@ import type { SubmissionResponse } from "./submissions";
 export interface LeaderboardEntry {
     id: number;
     submission_id: number;
-    problem_fit_score: number;
-    visual_clarity_score: number;
-    style_interpretation_score: number;
-    originality_score: number;
-    overall_quality_score: number;
+    /** Per-criterion scores (0-1000) keyed by rubric criterion */
+    criteria: Record<string, number>;
     final_score: number;
+    /** Names of all authors, comma separated */
     user_name: string;
+    /** Set for submissions made by a team */
+    team_name: string | null;
+    member_names: string[];
     created_at: string;
     updated_at: string;
 }
@@ -16,15 +16,12 @@ export interface VoteAssignment {
 }
 
 /**
- * Vote scores for a submission (all 0-5)
+ * Vote scores for a submission, keyed by rubric criterion
+ * (e.g. "problem_fit", "clarity"), each within the criterion's range
  */
 export interface VoteParams {
     submission_id: number;
-    problem_fit_score: number;
-    clarity_score: number;
-    style_interpretation_score: number;
-    originality_score: number;
-    overall_quality_score: number;
+    scores: Record<string, number>;
 }
 
 /**
@@ -34,11 +31,7 @@ export interface VoteResponse {
     id: number;
     user_id: number;
     submission_id: number;
-    problem_fit_score: number;
-    clarity_score: number;
-    style_interpretation_score: number;
-    originality_score: number;
-    overall_quality_score: number;
+    scores: Record<string, number>;
     created_at: string;
     updated_at: string;
 }
@@ -221,7 +221,9 @@ function LeaderboardItem({ rank, entry, onClick }: LeaderboardItemProps) {
 				<span
 					className={`font-['Figtree',sans-serif] font-medium text-[20px] ${styles.text}`}
 				>
-					{entry.user_name}
+					{entry.team_name
+						? `${entry.team_name} · ${entry.user_name}`
+						: entry.user_name}
 				</span>
 			</div>
 			<span
@@ -436,31 +438,31 @@ function SubmissionPreviewModal({
 								<div className="flex justify-between">
 									<span className="text-[#717171]">Problem Fit:</span>
 									<span className="font-medium text-black">
-										{score.problem_fit_score}
+										{score.criteria.problem_fit}
 									</span>
 								</div>
 								<div className="flex justify-between">
 									<span className="text-[#717171]">Visual Clarity:</span>
 									<span className="font-medium text-black">
-										{score.visual_clarity_score}
+										{score.criteria.clarity}
 									</span>
 								</div>
 								<div className="flex justify-between">
 									<span className="text-[#717171]">Style Interpretation:</span>
 									<span className="font-medium text-black">
-										{score.style_interpretation_score}
+										{score.criteria.style_interpretation}
 									</span>
 								</div>
 								<div className="flex justify-between">
 									<span className="text-[#717171]">Originality:</span>
 									<span className="font-medium text-black">
-										{score.originality_score}
+										{score.criteria.originality}
 									</span>
 								</div>
 								<div className="flex justify-between col-span-2">
 									<span className="text-[#717171]">Overall Quality:</span>
 									<span className="font-medium text-black">
-										{score.overall_quality_score}
+										{score.criteria.overall_quality}
 									</span>
 								</div>
 							</div>
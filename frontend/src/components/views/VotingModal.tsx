@@ -28,11 +28,11 @@ const initialVoteScores: VoteScores = {
  */
 function apiVoteToScores(vote: VoteResponse): VoteScores {
 	return {
-		problemFitScore: vote.problem_fit_score,
-		clarityScore: vote.clarity_score,
-		styleInterpretationScore: vote.style_interpretation_score,
-		originalityScore: vote.originality_score,
-		overallQualityScore: vote.overall_quality_score,
+		problemFitScore: vote.scores.problem_fit ?? 0,
+		clarityScore: vote.scores.clarity ?? 0,
+		styleInterpretationScore: vote.scores.style_interpretation ?? 0,
+		originalityScore: vote.scores.originality ?? 0,
+		overallQualityScore: vote.scores.overall_quality ?? 0,
 	};
 }
 
@@ -1,5 +1,11 @@
 import { useMutation, useQuery, useQueryClient } from "@tanstack/react-query";
-import { getCurrentUser } from "../api/auth";
+import { getCurrentUser, logout } from "../api/auth";
+import {
+    clearStoredTokens,
+    getStoredRefreshToken,
+    getStoredToken,
+    storeTokens,
+} from "../api/client";
 
 export interface User {
     pid: string;
@@ -12,24 +18,6 @@ interface AuthState {
     token: string | null;
 }
 
-const TOKEN_KEY = "auth_token";
-
-// Get token from localStorage
-function getStoredToken(): string | null {
-    if (typeof window === "undefined") return null;
-    return localStorage.getItem(TOKEN_KEY);
-}
-
-// Store token in localStorage
-function setStoredToken(token: string): void {
-    localStorage.setItem(TOKEN_KEY, token);
-}
-
-// Remove token from localStorage
-function removeStoredToken(): void {
-    localStorage.removeItem(TOKEN_KEY);
-}
-
 // Fetch current user from token using the API
 async function fetchCurrentUser(): Promise<AuthState> {
     const token = getStoredToken();
@@ -46,18 +34,23 @@ async function fetchCurrentUser(): Promise<AuthState> {
                 email: userData.email,
                 name: userData.name,
             },
-            token,
+            // the request may have refreshed the session
+            token: getStoredToken(),
         };
     } catch {
-        // Token is invalid or expired
-        removeStoredToken();
+        // Session is invalid or could not be refreshed
+        clearStoredTokens();
         return { user: null, token: null };
     }
 }
 
-// Logout function (just removes token, no server endpoint needed)
+// Revokes the refresh token, the access token expires on its own
 async function logoutUser(): Promise<void> {
-    removeStoredToken();
+    const refreshToken = getStoredRefreshToken();
+    clearStoredTokens();
+    if (refreshToken) {
+        await logout(refreshToken).catch(() => {});
+    }
 }
 
 export const authKeys = {
@@ -85,8 +78,8 @@ export function useAuth() {
     });
 
     // Function to set auth state after login (called from login flow)
-    const setAuth = (token: string, user: User) => {
-        setStoredToken(token);
+    const setAuth = (token: string, refreshToken: string, user: User) => {
+        storeTokens(token, refreshToken);
         queryClient.setQueryData(authKeys.user, { user, token });
     };
 
@@ -57,11 +57,13 @@ function Index() {
 		submitVoteMutation.mutate(
 			{
 				submission_id: submissionId,
-				problem_fit_score: scores.problemFitScore,
-				clarity_score: scores.clarityScore,
-				style_interpretation_score: scores.styleInterpretationScore,
-				originality_score: scores.originalityScore,
-				overall_quality_score: scores.overallQualityScore,
+				scores: {
+					problem_fit: scores.problemFitScore,
+					clarity: scores.clarityScore,
+					style_interpretation: scores.styleInterpretationScore,
+					originality: scores.originalityScore,
+					overall_quality: scores.overallQualityScore,
+				},
 			},
 			{
 				onSuccess: () => {
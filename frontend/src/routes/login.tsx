@@ -11,32 +11,48 @@ import {
 	AuthLayout,
 } from "../components";
 import { useLoginFlow, useLoginAnimations, useAuth } from "../hooks";
+import { type LoginResponse, useVerifyMagicLink } from "../api";
 
 const DESCRIPTIONS = {
 	email: "Use your registered email address from unstop.",
 	otp: "Enter the One Time Password that you just received on your email.",
+	invalidLink:
+		"This sign-in link is invalid or has expired. Enter your email to get an OTP instead.",
 } as const;
 
+interface LoginSearch {
+	token?: string;
+}
+
 const RESEND_SUCCESS_TIMEOUT = 3000;
 
 function LoginPage() {
 	const navigate = useNavigate();
 	const [shake, setShake] = useState(false);
 	const { setAuth } = useAuth();
+	const { token } = Route.useSearch();
 
 	const animations = useLoginAnimations();
-	const login = useLoginFlow({
-		onSuccess: (response) => {
-			setAuth(response.token, {
-				pid: response.pid,
-				email: response.email,
-				name: response.name,
-			});
-			animations.animateSuccessExit(() => {
-				navigate({ to: "/" });
-			});
-		},
-	});
+	const onSuccess = (response: LoginResponse) => {
+		setAuth(response.token, response.refresh_token, {
+			pid: response.pid,
+			email: response.email,
+			name: response.name,
+		});
+		animations.animateSuccessExit(() => {
+			navigate({ to: "/" });
+		});
+	};
+	const login = useLoginFlow({ onSuccess });
+	const magicLink = useVerifyMagicLink();
+
+	// Sign in with the token of an emailed link, which only works once
+	// biome-ignore lint/correctness/useExhaustiveDependencies: verify each token once
+	useEffect(() => {
+		if (token) {
+			magicLink.mutate({ token }, { onSuccess });
+		}
+	}, [token]);
 
 	// Register GSAP context
 	useGSAP(() => {}, { scope: animations.containerRef });
@@ -100,7 +116,9 @@ function LoginPage() {
 
 					<div ref={animations.formContainerRef}>
 						<CardDescription className="mt-2 mb-6">
-							{DESCRIPTIONS[login.step]}
+							{magicLink.isError && login.step === "email"
+								? DESCRIPTIONS.invalidLink
+								: DESCRIPTIONS[login.step]}
 						</CardDescription>
 
 						{login.step === "email" ? (
@@ -108,7 +126,7 @@ function LoginPage() {
 								email={login.email}
 								onEmailChange={login.updateEmail}
 								onSubmit={handleRequestOtp}
-								isLoading={login.isLoading}
+								isLoading={login.isLoading || magicLink.isPending}
 							/>
 						) : (
 							<OtpForm
@@ -274,5 +292,8 @@ function FormMessage({ variant, children }: FormMessageProps) {
 }
 
 export const Route = createFileRoute("/login")({
+	validateSearch: (search: Record<string, unknown>): LoginSearch => ({
+		token: typeof search.token === "string" ? search.token : undefined,
+	}),
 	component: LoginPage,
 });
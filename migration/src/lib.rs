@@ -14,6 +14,30 @@ mod m20260123_172521_votes;
 mod m20260123_214643_scores;
 mod m20260124_122842_add_show_leaderboard_to_configs;
 mod m20260124_223823_add_assigned_and_created_scores_to_configs;
+mod m20261017_101500_competitions;
+mod m20261017_102000_add_competition_to_rounds_tables;
+mod m20261017_110000_criteria;
+mod m20261017_110500_per_criterion_scores;
+mod m20261017_120000_add_aggregator_to_competitions;
+mod m20261017_123000_add_calibrate_voters_to_competitions;
+mod m20261017_130000_add_assignment_settings_to_competitions;
+mod m20261017_130500_conflicts;
+mod m20261017_133000_add_reassign_after_hours_to_competitions;
+mod m20261017_143000_audit_events;
+mod m20261017_150000_submission_revisions;
+mod m20261017_160000_add_previews_to_submissions;
+mod m20261017_163000_add_figma_parts_to_submissions;
+mod m20261017_170000_add_status_to_submissions;
+mod m20261017_173000_add_removal_to_submissions;
+mod m20261017_180000_teams;
+mod m20261017_183000_deadline_extensions;
+mod m20261017_190000_harden_otp;
+mod m20261017_193000_rate_limit_buckets;
+mod m20261017_200000_invite_codes;
+mod m20261017_203000_add_magic_link_to_users;
+mod m20261017_210000_refresh_tokens;
+mod m20261017_213000_store_competition_phase;
+mod m20261017_220000_rate_limit_full_at;
 pub struct Migrator;
 
 #[async_trait::async_trait]
@@ -32,7 +56,31 @@ impl MigratorTrait for Migrator {
             Box::new(m20260123_214643_scores::Migration),
             Box::new(m20260124_122842_add_show_leaderboard_to_configs::Migration),
             Box::new(m20260124_223823_add_assigned_and_created_scores_to_configs::Migration),
+            Box::new(m20261017_101500_competitions::Migration),
+            Box::new(m20261017_102000_add_competition_to_rounds_tables::Migration),
+            Box::new(m20261017_110000_criteria::Migration),
+            Box::new(m20261017_110500_per_criterion_scores::Migration),
+            Box::new(m20261017_120000_add_aggregator_to_competitions::Migration),
+            Box::new(m20261017_123000_add_calibrate_voters_to_competitions::Migration),
+            Box::new(m20261017_130000_add_assignment_settings_to_competitions::Migration),
+            Box::new(m20261017_130500_conflicts::Migration),
+            Box::new(m20261017_133000_add_reassign_after_hours_to_competitions::Migration),
+            Box::new(m20261017_143000_audit_events::Migration),
+            Box::new(m20261017_150000_submission_revisions::Migration),
+            Box::new(m20261017_160000_add_previews_to_submissions::Migration),
+            Box::new(m20261017_163000_add_figma_parts_to_submissions::Migration),
+            Box::new(m20261017_170000_add_status_to_submissions::Migration),
+            Box::new(m20261017_173000_add_removal_to_submissions::Migration),
+            Box::new(m20261017_180000_teams::Migration),
+            Box::new(m20261017_183000_deadline_extensions::Migration),
+            Box::new(m20261017_190000_harden_otp::Migration),
+            Box::new(m20261017_193000_rate_limit_buckets::Migration),
+            Box::new(m20261017_200000_invite_codes::Migration),
+            Box::new(m20261017_203000_add_magic_link_to_users::Migration),
+            Box::new(m20261017_210000_refresh_tokens::Migration),
+            Box::new(m20261017_213000_store_competition_phase::Migration),
+            Box::new(m20261017_220000_rate_limit_full_at::Migration),
             // inject-above (do not remove this comment)
         ]
     }
-}
\ No newline at end of file
+}
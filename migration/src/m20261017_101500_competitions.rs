@@ -0,0 +1,81 @@
+use loco_rs::schema::*;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        create_table(
+            m,
+            "competitions",
+            &[
+                ("id", ColType::PkAuto),
+                ("name", ColType::String),
+                ("slug", ColType::StringUniq),
+                ("submission_start", ColType::TimestampWithTimeZoneNull),
+                ("submission_end", ColType::TimestampWithTimeZoneNull),
+                ("voting_start", ColType::TimestampWithTimeZoneNull),
+                ("voting_end", ColType::TimestampWithTimeZoneNull),
+                ("show_leaderboard", ColType::BooleanWithDefault(false)),
+                ("assigned", ColType::BooleanWithDefault(false)),
+                ("created_scores", ColType::BooleanWithDefault(false)),
+            ],
+            &[],
+        )
+        .await?;
+
+        // Carry the round configured in the old single-row `configs` table over
+        // as the first competition so that existing submissions can be attached to it.
+        let db = m.get_connection();
+        db.execute_unprepared(
+            "INSERT INTO competitions (name, slug, submission_start, submission_end, \
+             voting_start, voting_end, show_leaderboard, assigned, created_scores) \
+             SELECT 'Bit by Design', 'bit-by-design', submission_start, submission_end, \
+             voting_start, voting_end, COALESCE(show_leaderboard, false), \
+             COALESCE(assigned, false), COALESCE(created_scores, false) \
+             FROM configs ORDER BY id LIMIT 1",
+        )
+        .await?;
+        db.execute_unprepared(
+            "INSERT INTO competitions (name, slug) SELECT 'Bit by Design', 'bit-by-design' \
+             WHERE NOT EXISTS (SELECT 1 FROM competitions) \
+             AND EXISTS (SELECT 1 FROM submissions)",
+        )
+        .await?;
+
+        drop_table(m, "configs").await
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        create_table(
+            m,
+            "configs",
+            &[
+                ("id", ColType::PkAuto),
+                ("submission_start", ColType::TimestampWithTimeZoneNull),
+                ("submission_end", ColType::TimestampWithTimeZoneNull),
+                ("voting_start", ColType::TimestampWithTimeZoneNull),
+                ("voting_end", ColType::TimestampWithTimeZoneNull),
+                ("show_leaderboard", ColType::Boolean),
+                ("assigned", ColType::Boolean),
+                ("created_scores", ColType::Boolean),
+            ],
+            &[],
+        )
+        .await?;
+
+        m.get_connection()
+            .execute_unprepared(
+                "INSERT INTO configs (submission_start, submission_end, voting_start, \
+                 voting_end, show_leaderboard, assigned, created_scores) \
+                 SELECT submission_start, submission_end, voting_start, voting_end, \
+                 show_leaderboard, assigned, created_scores \
+                 FROM competitions ORDER BY id DESC LIMIT 1",
+            )
+            .await?;
+
+        drop_table(m, "competitions").await
+    }
+}
@@ -0,0 +1,59 @@
+use loco_rs::schema::*;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+const TABLES: [&str; 4] = ["submissions", "vote_assignments", "votes", "scores"];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        for table in TABLES {
+            // Added as nullable first so rows from before competitions existed can be
+            // attached to the carried-over competition before the constraint is enforced.
+            add_column(m, table, "competition_id", ColType::IntegerNull).await?;
+            m.get_connection()
+                .execute_unprepared(&format!(
+                    "UPDATE {table} SET competition_id = (SELECT MIN(id) FROM competitions)"
+                ))
+                .await?;
+
+            // SQLite cannot alter a column, so there it stays nullable and without a
+            // foreign key. The models refuse to insert rows without a competition
+            // instead, rows inserted bypassing them are not checked.
+            if m.get_database_backend() == sea_orm::DatabaseBackend::Sqlite {
+                continue;
+            }
+            m.alter_table(
+                alter(Alias::new(table))
+                    .modify_column(
+                        ColumnDef::new(Alias::new("competition_id"))
+                            .integer()
+                            .not_null(),
+                    )
+                    .add_foreign_key(
+                        TableForeignKey::new()
+                            .name(format!("fk-{table}-competition_id-to-competitions"))
+                            .from_tbl(Alias::new(table))
+                            .from_col(Alias::new("competition_id"))
+                            .to_tbl(Alias::new("competitions"))
+                            .to_col(Alias::new("id"))
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        for table in TABLES {
+            remove_reference(m, table, "competitions", "").await?;
+            remove_column(m, table, "competition_id").await?;
+        }
+        Ok(())
+    }
+}
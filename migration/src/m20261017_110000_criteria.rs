@@ -0,0 +1,64 @@
+use loco_rs::schema::*;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        create_table(
+            m,
+            "criteria",
+            &[
+                ("id", ColType::PkAuto),
+                ("key", ColType::String),
+                ("name", ColType::String),
+                ("description", ColType::TextNull),
+                ("min_score", ColType::Integer),
+                ("max_score", ColType::Integer),
+                ("weight", ColType::Float),
+                ("position", ColType::Integer),
+            ],
+            &[("competition", "")],
+        )
+        .await?;
+        m.create_index(
+            Index::create()
+                .name("idx-criteria-competition_id-key")
+                .table(Alias::new("criteria"))
+                .col(Alias::new("competition_id"))
+                .col(Alias::new("key"))
+                .unique()
+                .to_owned(),
+        )
+        .await?;
+
+        // Every existing competition was judged on the five criteria that used to be
+        // hard-coded, so they become its rubric.
+        m.get_connection()
+            .execute_unprepared(
+                "INSERT INTO criteria (competition_id, key, name, description, min_score, \
+                 max_score, weight, position) \
+                 SELECT c.id, d.key, d.name, d.description, 0, 5, d.weight, d.position \
+                 FROM competitions c CROSS JOIN ( \
+                 SELECT 'problem_fit' AS key, 'Problem Fit' AS name, \
+                 'How well does the design solve the stated problem?' AS description, \
+                 0.25 AS weight, 0 AS position \
+                 UNION ALL SELECT 'clarity', 'Clarity', \
+                 'How clear and understandable is the design?', 0.20, 1 \
+                 UNION ALL SELECT 'style_interpretation', 'Style Interpretation', \
+                 'How well does it interpret the design style?', 0.20, 2 \
+                 UNION ALL SELECT 'originality', 'Originality', \
+                 'How creative and unique is the approach?', 0.15, 3 \
+                 UNION ALL SELECT 'overall_quality', 'Overall Quality', \
+                 'Your overall impression of the design', 0.20, 4) d",
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        drop_table(m, "criteria").await
+    }
+}
@@ -0,0 +1,99 @@
+use loco_rs::schema::*;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// The per-criterion columns of `votes`, keyed by the criterion they move to.
+const VOTE_COLUMNS: [(&str, &str); 5] = [
+    ("problem_fit", "problem_fit_score"),
+    ("clarity", "clarity_score"),
+    ("style_interpretation", "style_interpretation_score"),
+    ("originality", "originality_score"),
+    ("overall_quality", "overall_quality_score"),
+];
+
+/// The per-criterion columns of `scores`, keyed by the criterion they move to.
+const SCORE_COLUMNS: [(&str, &str); 5] = [
+    ("problem_fit", "problem_fit_score"),
+    ("clarity", "visual_clarity_score"),
+    ("style_interpretation", "style_interpretation_score"),
+    ("originality", "originality_score"),
+    ("overall_quality", "overall_quality_score"),
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        create_table(
+            m,
+            "vote_scores",
+            &[("id", ColType::PkAuto), ("score", ColType::Integer)],
+            &[("vote", ""), ("criteria", "criterion_id")],
+        )
+        .await?;
+        m.create_index(
+            Index::create()
+                .name("idx-vote_scores-vote_id-criterion_id")
+                .table(Alias::new("vote_scores"))
+                .col(Alias::new("vote_id"))
+                .col(Alias::new("criterion_id"))
+                .unique()
+                .to_owned(),
+        )
+        .await?;
+
+        create_table(
+            m,
+            "criterion_scores",
+            &[("id", ColType::PkAuto), ("score", ColType::Integer)],
+            &[("score", ""), ("criteria", "criterion_id")],
+        )
+        .await?;
+
+        let db = m.get_connection();
+        for (key, column) in VOTE_COLUMNS {
+            db.execute_unprepared(&format!(
+                "INSERT INTO vote_scores (vote_id, criterion_id, score) \
+                 SELECT v.id, c.id, v.{column} FROM votes v \
+                 JOIN criteria c ON c.competition_id = v.competition_id AND c.key = '{key}'"
+            ))
+            .await?;
+            remove_column(m, "votes", column).await?;
+        }
+        for (key, column) in SCORE_COLUMNS {
+            db.execute_unprepared(&format!(
+                "INSERT INTO criterion_scores (score_id, criterion_id, score) \
+                 SELECT s.id, c.id, s.{column} FROM scores s \
+                 JOIN criteria c ON c.competition_id = s.competition_id AND c.key = '{key}'"
+            ))
+            .await?;
+            remove_column(m, "scores", column).await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        let db = m.get_connection();
+        for (key, column) in VOTE_COLUMNS {
+            add_column(m, "votes", column, ColType::IntegerWithDefault(0)).await?;
+            db.execute_unprepared(&format!(
+                "UPDATE votes SET {column} = COALESCE((SELECT vs.score FROM vote_scores vs \
+                 JOIN criteria c ON c.id = vs.criterion_id \
+                 WHERE vs.vote_id = votes.id AND c.key = '{key}'), 0)"
+            ))
+            .await?;
+        }
+        for (key, column) in SCORE_COLUMNS {
+            add_column(m, "scores", column, ColType::IntegerWithDefault(0)).await?;
+            db.execute_unprepared(&format!(
+                "UPDATE scores SET {column} = COALESCE((SELECT cs.score FROM criterion_scores cs \
+                 JOIN criteria c ON c.id = cs.criterion_id \
+                 WHERE cs.score_id = scores.id AND c.key = '{key}'), 0)"
+            ))
+            .await?;
+        }
+        drop_table(m, "criterion_scores").await?;
+        drop_table(m, "vote_scores").await
+    }
+}
@@ -0,0 +1,32 @@
+use loco_rs::schema::*;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        add_column(
+            m,
+            "competitions",
+            "reviewers_per_submission",
+            ColType::IntegerWithDefault(6),
+        )
+        .await?;
+        add_column(
+            m,
+            "competitions",
+            "max_reviews_per_user",
+            ColType::IntegerNull,
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        remove_column(m, "competitions", "max_reviews_per_user").await?;
+        remove_column(m, "competitions", "reviewers_per_submission").await?;
+        Ok(())
+    }
+}
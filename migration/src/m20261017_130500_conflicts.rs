@@ -0,0 +1,33 @@
+use loco_rs::schema::*;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        create_table(
+            m,
+            "conflicts",
+            &[("id", ColType::PkAuto)],
+            &[("users", ""), ("users", "other_user_id")],
+        )
+        .await?;
+        m.create_index(
+            Index::create()
+                .name("idx-conflicts-user_id-other_user_id")
+                .table(Alias::new("conflicts"))
+                .col(Alias::new("user_id"))
+                .col(Alias::new("other_user_id"))
+                .unique()
+                .to_owned(),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        drop_table(m, "conflicts").await
+    }
+}
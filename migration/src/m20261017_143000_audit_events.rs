@@ -0,0 +1,42 @@
+use loco_rs::schema::*;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        // No foreign keys: the log has to outlive the users and rows it mentions
+        create_table(
+            m,
+            "audit_events",
+            &[
+                ("id", ColType::PkAuto),
+                ("actor_id", ColType::IntegerNull),
+                ("action", ColType::String),
+                ("target_type", ColType::String),
+                ("target_id", ColType::IntegerNull),
+                ("competition_id", ColType::IntegerNull),
+                ("before", ColType::JsonBinaryNull),
+                ("after", ColType::JsonBinaryNull),
+            ],
+            &[],
+        )
+        .await?;
+        m.create_index(
+            Index::create()
+                .name("idx-audit_events-target_type-target_id")
+                .table(Alias::new("audit_events"))
+                .col(Alias::new("target_type"))
+                .col(Alias::new("target_id"))
+                .to_owned(),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        drop_table(m, "audit_events").await
+    }
+}
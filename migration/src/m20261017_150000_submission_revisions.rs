@@ -0,0 +1,27 @@
+use loco_rs::schema::*;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        create_table(
+            m,
+            "submission_revisions",
+            &[
+                ("id", ColType::PkAuto),
+                ("revision", ColType::Integer),
+                ("snapshot", ColType::JsonBinary),
+            ],
+            &[("submissions", ""), ("users", "editor_id")],
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        drop_table(m, "submission_revisions").await
+    }
+}
@@ -0,0 +1,36 @@
+use loco_rs::schema::*;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        add_column(m, "submissions", "figma_file_key", ColType::StringNull).await?;
+        add_column(m, "submissions", "figma_node_id", ColType::StringNull).await?;
+        m.create_index(
+            Index::create()
+                .name("idx-submissions-competition_id-figma_file_key")
+                .table(Alias::new("submissions"))
+                .col(Alias::new("competition_id"))
+                .col(Alias::new("figma_file_key"))
+                .to_owned(),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        m.drop_index(
+            Index::drop()
+                .name("idx-submissions-competition_id-figma_file_key")
+                .table(Alias::new("submissions"))
+                .to_owned(),
+        )
+        .await?;
+        remove_column(m, "submissions", "figma_file_key").await?;
+        remove_column(m, "submissions", "figma_node_id").await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,26 @@
+use loco_rs::schema::*;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        add_column(
+            m,
+            "submissions",
+            "removed_at",
+            ColType::TimestampWithTimeZoneNull,
+        )
+        .await?;
+        add_column(m, "submissions", "removal_reason", ColType::TextNull).await?;
+        Ok(())
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        remove_column(m, "submissions", "removal_reason").await?;
+        remove_column(m, "submissions", "removed_at").await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,76 @@
+use loco_rs::schema::*;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        create_table(
+            m,
+            "teams",
+            &[("id", ColType::PkAuto), ("name", ColType::String)],
+            &[("competitions", "")],
+        )
+        .await?;
+        create_table(
+            m,
+            "team_members",
+            &[("id", ColType::PkAuto)],
+            &[("teams", ""), ("users", "")],
+        )
+        .await?;
+        m.create_index(
+            Index::create()
+                .name("idx-team_members-team_id-user_id")
+                .table(Alias::new("team_members"))
+                .col(Alias::new("team_id"))
+                .col(Alias::new("user_id"))
+                .unique()
+                .to_owned(),
+        )
+        .await?;
+        create_table(
+            m,
+            "team_invites",
+            &[
+                ("id", ColType::PkAuto),
+                ("email", ColType::String),
+                ("token", ColType::StringUniq),
+                ("accepted_at", ColType::TimestampWithTimeZoneNull),
+            ],
+            &[("teams", ""), ("users", "invited_by_id")],
+        )
+        .await?;
+
+        // Nullable, submissions of participants taking part on their own have no team
+        add_column(m, "submissions", "team_id", ColType::IntegerNull).await?;
+        if m.get_database_backend() != sea_orm::DatabaseBackend::Sqlite {
+            m.alter_table(
+                alter(Alias::new("submissions"))
+                    .add_foreign_key(
+                        TableForeignKey::new()
+                            .name("fk-submissions-team_id-to-teams")
+                            .from_tbl(Alias::new("submissions"))
+                            .from_col(Alias::new("team_id"))
+                            .to_tbl(Alias::new("teams"))
+                            .to_col(Alias::new("id"))
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        remove_reference(m, "submissions", "teams", "").await?;
+        remove_column(m, "submissions", "team_id").await?;
+        drop_table(m, "team_invites").await?;
+        drop_table(m, "team_members").await?;
+        drop_table(m, "teams").await
+    }
+}
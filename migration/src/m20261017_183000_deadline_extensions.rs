@@ -0,0 +1,52 @@
+use loco_rs::schema::*;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        add_column(
+            m,
+            "competitions",
+            "grace_period_minutes",
+            ColType::IntegerWithDefault(0),
+        )
+        .await?;
+        add_column(m, "submissions", "late", ColType::BooleanWithDefault(false)).await?;
+        create_table(
+            m,
+            "deadline_extensions",
+            &[
+                ("id", ColType::PkAuto),
+                ("extended_until", ColType::TimestampWithTimeZone),
+                ("reason", ColType::TextNull),
+            ],
+            &[
+                ("competitions", ""),
+                ("users", ""),
+                ("users", "granted_by_id"),
+            ],
+        )
+        .await?;
+        m.create_index(
+            Index::create()
+                .name("idx-deadline_extensions-competition_id-user_id")
+                .table(Alias::new("deadline_extensions"))
+                .col(Alias::new("competition_id"))
+                .col(Alias::new("user_id"))
+                .unique()
+                .to_owned(),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        drop_table(m, "deadline_extensions").await?;
+        remove_column(m, "submissions", "late").await?;
+        remove_column(m, "competitions", "grace_period_minutes").await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,36 @@
+use loco_rs::schema::*;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        add_column(
+            m,
+            "users",
+            "otp_failed_attempts",
+            ColType::IntegerWithDefault(0),
+        )
+        .await?;
+        add_column(
+            m,
+            "users",
+            "otp_locked_until",
+            ColType::TimestampWithTimeZoneNull,
+        )
+        .await?;
+        // OTPs are stored hashed from now on, pending plaintext ones can't be checked
+        m.get_connection()
+            .execute_unprepared("UPDATE users SET otp = NULL, otp_sent_at = NULL")
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        remove_column(m, "users", "otp_locked_until").await?;
+        remove_column(m, "users", "otp_failed_attempts").await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,48 @@
+use loco_rs::schema::*;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        create_table(
+            m,
+            "invite_codes",
+            &[
+                ("id", ColType::PkAuto),
+                ("code", ColType::StringUniq),
+                ("note", ColType::StringNull),
+                ("used_at", ColType::TimestampWithTimeZoneNull),
+            ],
+            &[("users", "created_by_id")],
+        )
+        .await?;
+
+        // Nullable, the code is unused until someone registers with it
+        add_column(m, "invite_codes", "used_by_id", ColType::IntegerNull).await?;
+        if m.get_database_backend() != sea_orm::DatabaseBackend::Sqlite {
+            m.alter_table(
+                alter(Alias::new("invite_codes"))
+                    .add_foreign_key(
+                        TableForeignKey::new()
+                            .name("fk-invite_codes-used_by_id-to-users")
+                            .from_tbl(Alias::new("invite_codes"))
+                            .from_col(Alias::new("used_by_id"))
+                            .to_tbl(Alias::new("users"))
+                            .to_col(Alias::new("id"))
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        drop_table(m, "invite_codes").await
+    }
+}
@@ -0,0 +1,28 @@
+use loco_rs::schema::*;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        create_table(
+            m,
+            "refresh_tokens",
+            &[
+                ("id", ColType::PkAuto),
+                // SHA-256 of the token, which is only ever known to the client
+                ("token_hash", ColType::StringUniq),
+                ("expires_at", ColType::TimestampWithTimeZone),
+                ("revoked_at", ColType::TimestampWithTimeZoneNull),
+            ],
+            &[("users", "")],
+        )
+        .await
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        drop_table(m, "refresh_tokens").await
+    }
+}
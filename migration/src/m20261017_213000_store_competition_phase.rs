@@ -0,0 +1,50 @@
+use loco_rs::schema::*;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        // The phase a competition was last moved into replaces the flags it was
+        // inferred from, the timings still move it on from there
+        add_column(
+            m,
+            "competitions",
+            "entered_phase",
+            ColType::StringWithDefault("draft".to_string()),
+        )
+        .await?;
+        m.get_connection()
+            .execute_unprepared(
+                "UPDATE competitions SET entered_phase = CASE \
+                 WHEN show_leaderboard THEN 'published' \
+                 WHEN created_scores THEN 'scored' \
+                 WHEN assigned THEN 'assigned' \
+                 ELSE 'draft' END",
+            )
+            .await?;
+        remove_column(m, "competitions", "show_leaderboard").await?;
+        remove_column(m, "competitions", "assigned").await?;
+        remove_column(m, "competitions", "created_scores").await?;
+        Ok(())
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        for flag in ["show_leaderboard", "assigned", "created_scores"] {
+            add_column(m, "competitions", flag, ColType::BooleanWithDefault(false)).await?;
+        }
+        m.get_connection()
+            .execute_unprepared(
+                "UPDATE competitions SET \
+                 show_leaderboard = entered_phase = 'published', \
+                 created_scores = entered_phase IN ('scored', 'published'), \
+                 assigned = entered_phase IN \
+                 ('assigned', 'voting_open', 'voting_closed', 'scored', 'published')",
+            )
+            .await?;
+        remove_column(m, "competitions", "entered_phase").await?;
+        Ok(())
+    }
+}
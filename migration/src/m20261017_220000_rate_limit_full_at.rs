@@ -0,0 +1,38 @@
+use loco_rs::schema::*;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        // When a bucket is full again and its row can go. The limit of existing rows
+        // is unknown, so they are treated as full already
+        add_column(
+            m,
+            "rate_limit_buckets",
+            "full_at",
+            ColType::TimestampWithTimeZoneNull,
+        )
+        .await?;
+        let db = m.get_connection();
+        db.execute_unprepared("UPDATE rate_limit_buckets SET full_at = refilled_at")
+            .await?;
+        db.execute_unprepared("ALTER TABLE rate_limit_buckets ALTER COLUMN full_at SET NOT NULL")
+            .await?;
+        m.create_index(
+            Index::create()
+                .name("idx-rate_limit_buckets-full_at")
+                .table(Alias::new("rate_limit_buckets"))
+                .col(Alias::new("full_at"))
+                .to_owned(),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        remove_column(m, "rate_limit_buckets", "full_at").await
+    }
+}
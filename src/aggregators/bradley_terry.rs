@@ -0,0 +1,115 @@
+use std::collections::{BTreeSet, HashMap};
+
+use super::{Aggregator, Rating, Scale};
+
+/// Ranks submissions on the pairwise preferences implied by the votes: whenever a
+/// voter scored one submission higher than another, that is a win for it (equal
+/// scores are a draw). A Bradley–Terry model is fitted to these results, the same
+/// model Elo ratings approximate incrementally.
+///
+/// The score of a submission is its probability of beating a submission of average
+/// strength, so an average submission lands at `0.5`.
+pub struct BradleyTerry {
+    /// Iterations of the minorization-maximization fit
+    pub iterations: usize,
+    /// Draws against an average submission added to every submission, keeping
+    /// strengths finite for submissions that never (or always) win
+    pub prior: f32,
+}
+
+impl BradleyTerry {
+    pub const NAME: &'static str = "bradley_terry";
+}
+
+impl Default for BradleyTerry {
+    fn default() -> Self {
+        Self {
+            iterations: 100,
+            prior: 1.0,
+        }
+    }
+}
+
+impl Aggregator for BradleyTerry {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn aggregate(&self, ratings: &[Rating], _scale: Scale) -> HashMap<i32, f32> {
+        let submissions: Vec<i32> = ratings
+            .iter()
+            .map(|rating| rating.submission_id)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        let index: HashMap<i32, usize> = submissions
+            .iter()
+            .enumerate()
+            .map(|(i, submission_id)| (*submission_id, i))
+            .collect();
+        let n = submissions.len();
+
+        // wins[i] counts the (half) wins of i, games[i][j] the comparisons of i and j
+        let mut wins = vec![0.0_f32; n];
+        let mut games = vec![vec![0.0_f32; n]; n];
+        let mut by_voter: HashMap<i32, Vec<&Rating>> = HashMap::new();
+        for rating in ratings {
+            by_voter.entry(rating.voter_id).or_default().push(rating);
+        }
+        for voter_ratings in by_voter.values() {
+            for (k, a) in voter_ratings.iter().enumerate() {
+                for b in &voter_ratings[k + 1..] {
+                    let (i, j) = (index[&a.submission_id], index[&b.submission_id]);
+                    if i == j {
+                        continue;
+                    }
+                    games[i][j] += 1.0;
+                    games[j][i] += 1.0;
+                    if a.score > b.score {
+                        wins[i] += 1.0;
+                    } else if a.score < b.score {
+                        wins[j] += 1.0;
+                    } else {
+                        wins[i] += 0.5;
+                        wins[j] += 0.5;
+                    }
+                }
+            }
+        }
+
+        // Minorization-maximization (Hunter, 2004), the prior being draws against a
+        // virtual opponent of strength 1
+        let mut strength = vec![1.0_f32; n];
+        for _ in 0..self.iterations {
+            let mut next = vec![0.0_f32; n];
+            for i in 0..n {
+                let mut denominator = self.prior / (strength[i] + 1.0);
+                for j in 0..n {
+                    if games[i][j] > 0.0 {
+                        denominator += games[i][j] / (strength[i] + strength[j]);
+                    }
+                }
+                let numerator = wins[i] + self.prior / 2.0;
+                next[i] = if denominator > 0.0 {
+                    numerator / denominator
+                } else {
+                    1.0
+                };
+            }
+            // rescale to a geometric mean of 1, i.e. the average submission
+            let log_mean = next
+                .iter()
+                .map(|s| s.max(f32::MIN_POSITIVE).ln())
+                .sum::<f32>()
+                / n.max(1) as f32;
+            let norm = log_mean.exp();
+            strength = next.into_iter().map(|s| s / norm).collect();
+        }
+
+        submissions
+            .into_iter()
+            .zip(strength)
+            .map(|(submission_id, s)| (submission_id, s / (s + 1.0)))
+            .collect()
+    }
+}
@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use super::{mean, Rating, Scale};
+
+/// Ratings of a voter after which their own mean and spread are trusted as much as
+/// the crowd's. Voters with fewer ratings are only partially corrected.
+const PRIOR_RATINGS: f32 = 5.0;
+
+/// How a single voter's scores are corrected, all values on a `0.0..=1.0` scale.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct VoterCalibration {
+    pub voter_id: i32,
+    /// Number of ratings (votes times criteria) the estimate is based on
+    pub ratings: usize,
+    /// Mean of the voter's normalized scores
+    pub mean: f32,
+    /// Standard deviation of the voter's normalized scores
+    pub std_dev: f32,
+    /// How much more lenient (positive) or harsh (negative) than the average voter
+    /// the voter is; subtracted from their scores
+    pub offset: f32,
+    /// Factor the voter's deviations from their own mean are stretched by to match
+    /// the spread of all voters
+    pub spread: f32,
+}
+
+/// Harshness/leniency correction: every voter's scores are shifted and stretched
+/// so that their mean and spread match those of all voters combined.
+///
+/// `calibrated = global_mean + (score - (global_mean + offset)) * spread`
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Calibration {
+    pub global_mean: f32,
+    pub global_std_dev: f32,
+    pub voters: HashMap<i32, VoterCalibration>,
+}
+
+fn std_dev(values: &[f32], mean: f32) -> f32 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    (values
+        .iter()
+        .map(|value| (value - mean).powi(2))
+        .sum::<f32>()
+        / values.len() as f32)
+        .sqrt()
+}
+
+impl Calibration {
+    /// Estimates every voter's offset and spread from their ratings on all criteria
+    /// of a rubric, each criterion given with the scale it is scored in.
+    #[must_use]
+    pub fn estimate(rubric: &[(Scale, Vec<Rating>)]) -> Self {
+        let mut by_voter: HashMap<i32, Vec<f32>> = HashMap::new();
+        for (scale, ratings) in rubric {
+            for rating in ratings {
+                by_voter
+                    .entry(rating.voter_id)
+                    .or_default()
+                    .push(scale.normalize(rating.score));
+            }
+        }
+        let all: Vec<f32> = by_voter.values().flatten().copied().collect();
+        let global_mean = mean(&all).unwrap_or(0.0);
+        let global_std_dev = std_dev(&all, global_mean);
+
+        let voters = by_voter
+            .into_iter()
+            .map(|(voter_id, scores)| {
+                let voter_mean = mean(&scores).unwrap_or(global_mean);
+                let voter_std_dev = std_dev(&scores, voter_mean);
+                // shrink towards "no correction" for voters with few ratings
+                let n = scores.len() as f32;
+                let trust = n / (n + PRIOR_RATINGS);
+                let spread = if voter_std_dev > f32::EPSILON {
+                    trust * (global_std_dev / voter_std_dev) + (1.0 - trust)
+                } else {
+                    1.0
+                };
+                let calibration = VoterCalibration {
+                    voter_id,
+                    ratings: scores.len(),
+                    mean: voter_mean,
+                    std_dev: voter_std_dev,
+                    offset: trust * (voter_mean - global_mean),
+                    spread,
+                };
+                (voter_id, calibration)
+            })
+            .collect();
+
+        Self {
+            global_mean,
+            global_std_dev,
+            voters,
+        }
+    }
+
+    /// Corrects a normalized score given by `voter_id`.
+    #[must_use]
+    pub fn apply(&self, voter_id: i32, score: f32) -> f32 {
+        let Some(voter) = self.voters.get(&voter_id) else {
+            return score;
+        };
+        let voter_mean = self.global_mean + voter.offset;
+        (self.global_mean + (score - voter_mean) * voter.spread).clamp(0.0, 1.0)
+    }
+
+    /// Corrects ratings given in `scale`, keeping them in that scale.
+    #[must_use]
+    pub fn calibrate(&self, scale: Scale, ratings: &[Rating]) -> Vec<Rating> {
+        ratings
+            .iter()
+            .map(|rating| Rating {
+                score: scale
+                    .denormalize(self.apply(rating.voter_id, scale.normalize(rating.score))),
+                ..*rating
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,59 @@
+use std::collections::HashMap;
+
+use super::{by_submission, mean, Aggregator, Rating, Scale};
+
+/// Median of each submission's ratings, smoothed towards the mean of all ratings so
+/// that submissions with few votes are not ranked on a single opinion.
+///
+/// `score = (v / (v + m)) * median + (m / (v + m)) * global_mean`, where `v` is the
+/// number of votes of the submission and `m` the number of dummy votes.
+pub struct MedianBayesian {
+    /// Smoothing factor (dummy votes)
+    pub smoothing: f32,
+}
+
+impl MedianBayesian {
+    pub const NAME: &'static str = "median_bayesian";
+}
+
+impl Default for MedianBayesian {
+    fn default() -> Self {
+        Self { smoothing: 2.0 }
+    }
+}
+
+/// Calculate median of a slice. Returns None if empty.
+fn median(values: &mut [f32]) -> Option<f32> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(f32::total_cmp);
+    let n = values.len();
+    if n % 2 == 1 {
+        Some(values[n / 2])
+    } else {
+        Some((values[n / 2 - 1] + values[n / 2]) / 2.0)
+    }
+}
+
+impl Aggregator for MedianBayesian {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn aggregate(&self, ratings: &[Rating], scale: Scale) -> HashMap<i32, f32> {
+        let all: Vec<f32> = ratings.iter().map(|rating| rating.score).collect();
+        let global_mean = mean(&all).unwrap_or(0.0);
+        let m = self.smoothing;
+
+        by_submission(ratings)
+            .into_iter()
+            .map(|(submission_id, mut scores)| {
+                let v = scores.len() as f32;
+                let local_median = median(&mut scores).unwrap_or(global_mean);
+                let smoothed = (v / (v + m)) * local_median + (m / (v + m)) * global_mean;
+                (submission_id, scale.normalize(smoothed))
+            })
+            .collect()
+    }
+}
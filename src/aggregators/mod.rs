@@ -0,0 +1,111 @@
+//! Strategies turning the raw votes on a criterion into a score per submission.
+//!
+//! Every [`Aggregator`] sees the ratings of a single rubric criterion and returns a
+//! score in `0.0..=1.0` for each rated submission; [`GenLeaderboard`] then weights
+//! these per the rubric. Which aggregator is used is configured per competition and
+//! can be overridden when running the task.
+//!
+//! [`GenLeaderboard`]: crate::tasks::gen_leaderboard::GenLeaderboard
+use std::collections::HashMap;
+
+mod bradley_terry;
+mod calibration;
+mod median_bayesian;
+mod trimmed_mean;
+mod z_score;
+
+pub use bradley_terry::BradleyTerry;
+pub use calibration::{Calibration, VoterCalibration};
+pub use median_bayesian::MedianBayesian;
+pub use trimmed_mean::TrimmedMean;
+pub use z_score::ZScore;
+
+/// The aggregator competitions use unless configured otherwise.
+pub const DEFAULT: &str = MedianBayesian::NAME;
+
+/// Names of all available aggregators, as accepted by [`from_name`].
+pub const NAMES: [&str; 4] = [
+    MedianBayesian::NAME,
+    TrimmedMean::NAME,
+    ZScore::NAME,
+    BradleyTerry::NAME,
+];
+
+/// One voter's score for a submission on a single criterion.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rating {
+    pub voter_id: i32,
+    pub submission_id: i32,
+    pub score: f32,
+}
+
+/// The range scores of a criterion are given in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Scale {
+    pub min: f32,
+    pub max: f32,
+}
+
+impl Scale {
+    #[must_use]
+    pub fn new(min: f32, max: f32) -> Self {
+        Self { min, max }
+    }
+
+    /// Maps a score on this scale onto `0.0..=1.0`.
+    #[must_use]
+    pub fn normalize(&self, score: f32) -> f32 {
+        let range = self.max - self.min;
+        if range <= 0.0 {
+            return 0.0;
+        }
+        ((score - self.min) / range).clamp(0.0, 1.0)
+    }
+
+    /// Maps a score in `0.0..=1.0` back onto this scale.
+    #[must_use]
+    pub fn denormalize(&self, score: f32) -> f32 {
+        self.min + score.clamp(0.0, 1.0) * (self.max - self.min)
+    }
+}
+
+pub trait Aggregator: Send + Sync {
+    /// The name the aggregator is selected by.
+    fn name(&self) -> &'static str;
+
+    /// Aggregates the ratings of one criterion into a score in `0.0..=1.0` for each
+    /// submission that has at least one rating.
+    fn aggregate(&self, ratings: &[Rating], scale: Scale) -> HashMap<i32, f32>;
+}
+
+/// Looks an aggregator up by name, using its default parameters.
+#[must_use]
+pub fn from_name(name: &str) -> Option<Box<dyn Aggregator>> {
+    match name {
+        MedianBayesian::NAME => Some(Box::new(MedianBayesian::default())),
+        TrimmedMean::NAME => Some(Box::new(TrimmedMean::default())),
+        ZScore::NAME => Some(Box::new(ZScore)),
+        BradleyTerry::NAME => Some(Box::new(BradleyTerry::default())),
+        _ => None,
+    }
+}
+
+/// Groups the scores of `ratings` by submission.
+fn by_submission(ratings: &[Rating]) -> HashMap<i32, Vec<f32>> {
+    let mut grouped: HashMap<i32, Vec<f32>> = HashMap::new();
+    for rating in ratings {
+        grouped
+            .entry(rating.submission_id)
+            .or_default()
+            .push(rating.score);
+    }
+    grouped
+}
+
+/// Calculate mean of a slice. Returns None if empty.
+fn mean(values: &[f32]) -> Option<f32> {
+    if values.is_empty() {
+        return None;
+    }
+    Some(values.iter().sum::<f32>() / values.len() as f32)
+}
@@ -0,0 +1,40 @@
+use std::collections::HashMap;
+
+use super::{by_submission, mean, Aggregator, Rating, Scale};
+
+/// Mean of each submission's ratings after dropping the most extreme ones, which
+/// keeps single outlier votes from moving the result.
+pub struct TrimmedMean {
+    /// Fraction of the ratings dropped at each end, in `0.0..0.5`
+    pub trim: f32,
+}
+
+impl TrimmedMean {
+    pub const NAME: &'static str = "trimmed_mean";
+}
+
+impl Default for TrimmedMean {
+    fn default() -> Self {
+        Self { trim: 0.2 }
+    }
+}
+
+impl Aggregator for TrimmedMean {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn aggregate(&self, ratings: &[Rating], scale: Scale) -> HashMap<i32, f32> {
+        by_submission(ratings)
+            .into_iter()
+            .map(|(submission_id, mut scores)| {
+                scores.sort_by(f32::total_cmp);
+                let n = scores.len();
+                // always keep at least one rating
+                let cut = ((n as f32 * self.trim.clamp(0.0, 0.5)) as usize).min((n - 1) / 2);
+                let trimmed_mean = mean(&scores[cut..n - cut]).unwrap_or(0.0);
+                (submission_id, scale.normalize(trimmed_mean))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,61 @@
+use std::collections::HashMap;
+
+use super::{mean, Aggregator, Rating, Scale};
+
+/// Normalises every voter's ratings to z-scores before averaging them per
+/// submission, so harsh and generous voters count the same. The mean z-score is
+/// mapped onto `0.0..=1.0` with a logistic approximation of the normal CDF, an
+/// average submission landing at `0.5`.
+pub struct ZScore;
+
+impl ZScore {
+    pub const NAME: &'static str = "z_score";
+}
+
+impl Aggregator for ZScore {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn aggregate(&self, ratings: &[Rating], _scale: Scale) -> HashMap<i32, f32> {
+        let mut by_voter: HashMap<i32, Vec<f32>> = HashMap::new();
+        for rating in ratings {
+            by_voter
+                .entry(rating.voter_id)
+                .or_default()
+                .push(rating.score);
+        }
+        let voter_stats: HashMap<i32, (f32, f32)> = by_voter
+            .into_iter()
+            .map(|(voter_id, scores)| {
+                let voter_mean = mean(&scores).unwrap_or(0.0);
+                let variance = scores
+                    .iter()
+                    .map(|score| (score - voter_mean).powi(2))
+                    .sum::<f32>()
+                    / scores.len() as f32;
+                (voter_id, (voter_mean, variance.sqrt()))
+            })
+            .collect();
+
+        let mut z_scores: HashMap<i32, Vec<f32>> = HashMap::new();
+        for rating in ratings {
+            let (voter_mean, std_dev) = voter_stats[&rating.voter_id];
+            // a voter giving everything the same score expresses no preference
+            let z = if std_dev > f32::EPSILON {
+                (rating.score - voter_mean) / std_dev
+            } else {
+                0.0
+            };
+            z_scores.entry(rating.submission_id).or_default().push(z);
+        }
+
+        z_scores
+            .into_iter()
+            .map(|(submission_id, zs)| {
+                let z = mean(&zs).unwrap_or(0.0);
+                (submission_id, 1.0 / (1.0 + (-1.702 * z).exp()))
+            })
+            .collect()
+    }
+}
@@ -14,7 +14,12 @@ use migration::Migrator;
 use std::path::Path;
 
 #[allow(unused_imports)]
-use crate::{controllers, models::_entities::users, tasks, workers::downloader::DownloadWorker};
+use crate::{
+    controllers, images, initializers,
+    models::_entities::{audit_events, rate_limit_buckets, refresh_tokens, teams, users},
+    tasks,
+    workers::previews::PreviewWorker,
+};
 
 pub struct App;
 #[async_trait]
@@ -41,8 +46,17 @@ impl Hooks for App {
         create_app::<Self, Migrator>(mode, environment, config).await
     }
 
+    async fn after_context(mut ctx: AppContext) -> Result<AppContext> {
+        ctx.storage = images::storage::Settings::from_config(&ctx.config)?
+            .storage()?
+            .into();
+        Ok(ctx)
+    }
+
     async fn initializers(_ctx: &AppContext) -> Result<Vec<Box<dyn Initializer>>> {
-        Ok(vec![])
+        Ok(vec![Box::new(
+            initializers::rate_limit::RateLimitInitializer,
+        )])
     }
 
     fn routes(_ctx: &AppContext) -> AppRoutes {
@@ -51,11 +65,21 @@ impl Hooks for App {
             .add_route(controllers::votes::routes())
             .add_route(controllers::vote_assignments::routes())
             .add_route(controllers::submissions::routes())
+            .add_route(controllers::teams::routes())
+            .add_route(controllers::extensions::routes())
             .add_route(controllers::config::routes())
+            .add_route(controllers::competitions::routes())
+            .add_route(controllers::criteria::routes())
+            .add_route(controllers::calibration::routes())
+            .add_route(controllers::conflicts::routes())
             .add_route(controllers::auth::routes())
+            .add_route(controllers::admin_users::routes())
+            .add_route(controllers::invite_codes::routes())
+            .add_route(controllers::admin_competition::routes())
+            .add_route(controllers::audit_events::routes())
     }
     async fn connect_workers(ctx: &AppContext, queue: &Queue) -> Result<()> {
-        queue.register(DownloadWorker::build(ctx)).await?;
+        queue.register(PreviewWorker::build(ctx)).await?;
         Ok(())
     }
 
@@ -68,10 +92,15 @@ impl Hooks for App {
         tasks.register(tasks::show_leaderboard::ShowLeaderboard);
         tasks.register(tasks::update_timings::UpdateTimings);
         tasks.register(tasks::assign_and_gen::AssignAndGen);
+        tasks.register(tasks::rebalance_assignments::RebalanceAssignments);
         // tasks-inject (do not remove)
     }
     async fn truncate(ctx: &AppContext) -> Result<()> {
+        truncate_table(&ctx.db, refresh_tokens::Entity).await?;
         truncate_table(&ctx.db, users::Entity).await?;
+        truncate_table(&ctx.db, audit_events::Entity).await?;
+        truncate_table(&ctx.db, teams::Entity).await?;
+        truncate_table(&ctx.db, rate_limit_buckets::Entity).await?;
         Ok(())
     }
     async fn seed(ctx: &AppContext, base: &Path) -> Result<()> {
@@ -79,4 +108,4 @@ impl Hooks for App {
             .await?;
         Ok(())
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,102 @@
+use std::collections::VecDeque;
+
+/// An edge of the residual graph. Edges are added in pairs, so the reverse of edge
+/// `i` is always `i ^ 1`.
+struct Edge {
+    to: usize,
+    residual: usize,
+}
+
+/// A flow network with integer capacities, solved by Edmonds-Karp.
+pub(super) struct Network {
+    edges: Vec<Edge>,
+    adjacency: Vec<Vec<usize>>,
+}
+
+impl Network {
+    pub fn new(nodes: usize) -> Self {
+        Self {
+            edges: Vec::new(),
+            adjacency: vec![Vec::new(); nodes],
+        }
+    }
+
+    /// Adds an edge and returns its index.
+    pub fn add_edge(&mut self, from: usize, to: usize, capacity: usize) -> usize {
+        let index = self.edges.len();
+        self.edges.push(Edge {
+            to,
+            residual: capacity,
+        });
+        self.edges.push(Edge {
+            to: from,
+            residual: 0,
+        });
+        self.adjacency[from].push(index);
+        self.adjacency[to].push(index + 1);
+        index
+    }
+
+    /// How much flow goes through an edge.
+    pub fn flow(&self, edge: usize) -> usize {
+        self.edges[edge ^ 1].residual
+    }
+
+    /// Changes the capacity of an edge, keeping the flow already going through it.
+    /// The new capacity must not be below that flow.
+    pub fn set_capacity(&mut self, edge: usize, capacity: usize) {
+        self.edges[edge].residual = capacity - self.flow(edge);
+    }
+
+    /// Pushes as much additional flow from `source` to `sink` as possible along
+    /// shortest augmenting paths, returning how much was added.
+    pub fn augment(&mut self, source: usize, sink: usize) -> usize {
+        let mut added = 0;
+        while let Some(path) = self.shortest_path(source, sink) {
+            let bottleneck = path
+                .iter()
+                .map(|&edge| self.edges[edge].residual)
+                .min()
+                .unwrap_or(0);
+            for edge in path {
+                self.edges[edge].residual -= bottleneck;
+                self.edges[edge ^ 1].residual += bottleneck;
+            }
+            added += bottleneck;
+        }
+        added
+    }
+
+    /// The edges of a shortest path with residual capacity, in order.
+    fn shortest_path(&self, source: usize, sink: usize) -> Option<Vec<usize>> {
+        let mut via: Vec<Option<usize>> = vec![None; self.adjacency.len()];
+        let mut visited = vec![false; self.adjacency.len()];
+        let mut queue = VecDeque::from([source]);
+        visited[source] = true;
+        while let Some(node) = queue.pop_front() {
+            if node == sink {
+                break;
+            }
+            for &edge in &self.adjacency[node] {
+                let to = self.edges[edge].to;
+                if !visited[to] && self.edges[edge].residual > 0 {
+                    visited[to] = true;
+                    via[to] = Some(edge);
+                    queue.push_back(to);
+                }
+            }
+        }
+        if !visited[sink] || source == sink {
+            return None;
+        }
+
+        let mut path = Vec::new();
+        let mut node = sink;
+        while let Some(edge) = via[node] {
+            path.push(edge);
+            node = self.edges[edge ^ 1].to;
+        }
+        path.reverse();
+        Some(path)
+    }
+}
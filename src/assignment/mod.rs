@@ -0,0 +1,271 @@
+//! Decides which users review which submission.
+//!
+//! Assigning reviewers is modelled as a flow network: every submission asks for
+//! [`Constraints::reviewers_per_submission`] reviews, a reviewer can review every
+//! submission they are eligible for once, and takes at most
+//! [`Constraints::max_per_reviewer`] reviews in total. A maximum flow covers as many
+//! review slots as the constraints allow. To keep things balanced, both the reviews a
+//! submission asks for and the capacity of reviewers are raised one review at a time,
+//! so no submission gets another reviewer and no reviewer another submission while
+//! someone else could still take it instead.
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt,
+};
+
+use rand::{seq::SliceRandom, Rng};
+use serde::Serialize;
+
+mod flow;
+
+use flow::Network;
+
+/// A submission to be reviewed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Submission {
+    pub id: i32,
+    pub author_id: i32,
+}
+
+/// A reviewer assigned to a submission.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+pub struct Assignment {
+    pub reviewer_id: i32,
+    pub submission_id: i32,
+}
+
+/// What an assignment has to respect.
+#[derive(Clone, Debug, Default)]
+pub struct Constraints {
+    /// How many reviewers each submission should get
+    pub reviewers_per_submission: usize,
+    /// How many submissions a reviewer may review at most, unlimited if `None`
+    pub max_per_reviewer: Option<usize>,
+    /// `(reviewer_id, author_id)` pairs where the reviewer must not review the
+    /// author's submission, on top of authors not reviewing themselves
+    pub conflicts: HashSet<(i32, i32)>,
+}
+
+impl Constraints {
+    #[must_use]
+    pub fn new(reviewers_per_submission: usize) -> Self {
+        Self {
+            reviewers_per_submission,
+            ..Default::default()
+        }
+    }
+
+    /// Whether `reviewer_id` may review `submission`.
+    #[must_use]
+    pub fn allows(&self, reviewer_id: i32, submission: &Submission) -> bool {
+        reviewer_id != submission.author_id
+            && !self
+                .conflicts
+                .contains(&(reviewer_id, submission.author_id))
+    }
+}
+
+/// How the reviews of an assignment are spread.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Distribution {
+    /// The targeted number of reviewers per submission
+    pub reviewers_per_submission: usize,
+    pub max_per_reviewer: Option<usize>,
+    /// Total number of assignments
+    pub assignments: usize,
+    /// Number of reviewers per submission id
+    pub reviews: BTreeMap<i32, usize>,
+    /// Number of assigned submissions per reviewer id
+    pub loads: BTreeMap<i32, usize>,
+    /// Submissions that got fewer reviewers than targeted
+    pub understaffed: Vec<i32>,
+}
+
+fn range<'a>(values: impl Iterator<Item = &'a usize>) -> (usize, usize) {
+    values
+        .fold(None, |range, &value| match range {
+            None => Some((value, value)),
+            Some((min, max)) => Some((usize::min(min, value), usize::max(max, value))),
+        })
+        .unwrap_or_default()
+}
+
+impl Distribution {
+    /// The fewest and most reviewers any submission got.
+    #[must_use]
+    pub fn review_range(&self) -> (usize, usize) {
+        range(self.reviews.values())
+    }
+
+    /// The fewest and most submissions any reviewer got.
+    #[must_use]
+    pub fn load_range(&self) -> (usize, usize) {
+        range(self.loads.values())
+    }
+
+    /// Whether every submission got the targeted number of reviewers.
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        self.understaffed.is_empty()
+    }
+}
+
+impl fmt::Display for Distribution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (min_reviews, max_reviews) = self.review_range();
+        let (min_load, max_load) = self.load_range();
+        write!(
+            f,
+            "{} assignments: {min_reviews}-{max_reviews} reviewers per submission (target {}), \
+             {min_load}-{max_load} submissions per reviewer, {} submissions understaffed",
+            self.assignments,
+            self.reviewers_per_submission,
+            self.understaffed.len()
+        )
+    }
+}
+
+/// A reviewer available for more reviews.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Reviewer {
+    pub id: i32,
+    /// How many submissions the reviewer is already assigned
+    pub load: usize,
+}
+
+/// Assigns `reviewers` to `submissions`. Ties are broken randomly, so running it
+/// again gives a different, equally balanced, assignment.
+pub fn assign<R: Rng + ?Sized>(
+    submissions: &[Submission],
+    reviewers: &[i32],
+    constraints: &Constraints,
+    rng: &mut R,
+) -> (Vec<Assignment>, Distribution) {
+    let needs: Vec<(Submission, usize)> = submissions
+        .iter()
+        .map(|&submission| (submission, constraints.reviewers_per_submission))
+        .collect();
+    let mut reviewers = reviewers.to_vec();
+    reviewers.sort_unstable();
+    reviewers.dedup();
+    let available: Vec<Reviewer> = reviewers
+        .iter()
+        .map(|&id| Reviewer { id, load: 0 })
+        .collect();
+    let assignments = solve(&needs, &available, constraints, &HashSet::new(), rng);
+
+    let mut distribution = Distribution {
+        reviewers_per_submission: constraints.reviewers_per_submission,
+        max_per_reviewer: constraints.max_per_reviewer,
+        assignments: assignments.len(),
+        reviews: submissions.iter().map(|s| (s.id, 0)).collect(),
+        loads: reviewers.iter().map(|&r| (r, 0)).collect(),
+        understaffed: Vec::new(),
+    };
+    for assignment in &assignments {
+        *distribution
+            .reviews
+            .entry(assignment.submission_id)
+            .or_default() += 1;
+        *distribution
+            .loads
+            .entry(assignment.reviewer_id)
+            .or_default() += 1;
+    }
+    distribution.understaffed = distribution
+        .reviews
+        .iter()
+        .filter(|(_, &reviews)| reviews < constraints.reviewers_per_submission)
+        .map(|(&id, _)| id)
+        .collect();
+
+    (assignments, distribution)
+}
+
+/// Finds extra reviewers for submissions that lack some, given as the submission
+/// and how many more reviewers it needs. Reviewers keep their current load, which
+/// counts towards [`Constraints::max_per_reviewer`], and are never assigned a
+/// submission they appear with in `existing`.
+pub fn top_up<R: Rng + ?Sized>(
+    needs: &[(Submission, usize)],
+    reviewers: &[Reviewer],
+    existing: &HashSet<Assignment>,
+    constraints: &Constraints,
+    rng: &mut R,
+) -> Vec<Assignment> {
+    solve(needs, reviewers, constraints, existing, rng)
+}
+
+fn solve<R: Rng + ?Sized>(
+    needs: &[(Submission, usize)],
+    reviewers: &[Reviewer],
+    constraints: &Constraints,
+    excluded: &HashSet<Assignment>,
+    rng: &mut R,
+) -> Vec<Assignment> {
+    let mut needs = needs.to_vec();
+    needs.shuffle(rng);
+    let mut reviewers = reviewers.to_vec();
+    reviewers.shuffle(rng);
+
+    // source -> submissions -> reviewers -> sink
+    let source = 0;
+    let sink = needs.len() + reviewers.len() + 1;
+    let reviewer_node = |i: usize| needs.len() + 1 + i;
+    let mut network = Network::new(sink + 1);
+    let mut demands = Vec::new();
+    let mut candidates = Vec::new();
+    for (s, (submission, _)) in needs.iter().enumerate() {
+        demands.push(network.add_edge(source, s + 1, 0));
+        for (r, reviewer) in reviewers.iter().enumerate() {
+            let assignment = Assignment {
+                reviewer_id: reviewer.id,
+                submission_id: submission.id,
+            };
+            if constraints.allows(reviewer.id, submission) && !excluded.contains(&assignment) {
+                let edge = network.add_edge(s + 1, reviewer_node(r), 1);
+                candidates.push((edge, assignment));
+            }
+        }
+    }
+    let capacities: Vec<usize> = (0..reviewers.len())
+        .map(|r| network.add_edge(reviewer_node(r), sink, 0))
+        .collect();
+    let set_load = |network: &mut Network, load: usize| {
+        for (&edge, reviewer) in capacities.iter().zip(&reviewers) {
+            network.set_capacity(edge, load.saturating_sub(reviewer.load));
+        }
+    };
+
+    let busiest = reviewers.iter().map(|r| r.load).max().unwrap_or(0);
+    let limit = constraints
+        .max_per_reviewer
+        .unwrap_or(busiest + needs.len())
+        .min(busiest + needs.len());
+    let mut load = reviewers.iter().map(|r| r.load).min().unwrap_or(0);
+    let most_needed = needs.iter().map(|&(_, n)| n).max().unwrap_or(0);
+    for reviews in 1..=most_needed {
+        for (&edge, &(_, needed)) in demands.iter().zip(&needs) {
+            network.set_capacity(edge, reviews.min(needed));
+        }
+        network.augment(source, sink);
+        // More capacity only helps while it leads to more reviews. Once every reviewer
+        // got extra capacity and that did not help, more of it won't either.
+        while load < limit {
+            set_load(&mut network, load + 1);
+            if network.augment(source, sink) == 0 && load >= busiest {
+                set_load(&mut network, load);
+                break;
+            }
+            load += 1;
+        }
+    }
+
+    let mut assignments: Vec<Assignment> = candidates
+        .into_iter()
+        .filter(|&(edge, _)| network.flow(edge) > 0)
+        .map(|(_, assignment)| assignment)
+        .collect();
+    assignments.sort_unstable();
+    assignments
+}
@@ -0,0 +1,255 @@
+#![allow(clippy::missing_errors_doc)]
+#![allow(clippy::unnecessary_struct_initialization)]
+#![allow(clippy::unused_async)]
+//! Lets admins move a competition through its phases from the API, running the same
+//! logic as the `assign_submissions`, `gen_leaderboard` and `show_leaderboard` tasks.
+//! Every transition is validated against the current [`Phase`] and recorded in the
+//! audit log along with the admin who triggered it.
+use chrono::Utc;
+use loco_rs::prelude::*;
+use sea_orm::PaginatorTrait;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    controllers::competitions::CompetitionPath,
+    extractors::AdminJWT,
+    models::{
+        _entities::scores,
+        audit_events::{self, Event},
+        competitions::{ActiveModel, Model, Phase},
+    },
+    tasks::{assign_submissions::AssignSubmissions, gen_leaderboard::GenLeaderboard},
+    views::transitions::TransitionResponse,
+};
+
+/// The audit log actions which move a competition between phases, whether run from
+/// here, a task or the scheduler.
+const TRANSITIONS: [&str; 5] = [
+    "assign",
+    "generate_scores",
+    "publish_leaderboard",
+    "show_leaderboard",
+    "move",
+];
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Params {
+    pub phase: Phase,
+}
+
+/// The result of a transition, along with anything the admin should double check.
+#[derive(Clone, Debug, Serialize)]
+pub struct Summary<T> {
+    #[serde(flatten)]
+    pub result: T,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Moved {
+    pub from: Phase,
+    pub to: Phase,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Published {
+    pub show_leaderboard: bool,
+    /// Number of scores on the leaderboard
+    pub scores: u64,
+}
+
+/// Checks that `competition` can move to phase `to` now.
+fn transition(competition: &Model, to: Phase) -> Result<ActiveModel> {
+    competition
+        .transition(to, Utc::now().into())
+        .map_err(|err| Error::BadRequest(err.to_string()))
+}
+
+/// Records the transition and responds with it.
+async fn record<T: Serialize>(
+    ctx: &AppContext,
+    admin: &AdminJWT,
+    competition: &Model,
+    action: &str,
+    summary: Summary<T>,
+) -> Result<Response> {
+    let event = Event::new(
+        &format!("competition.{action}"),
+        "competition",
+        Some(competition.id),
+    )
+    .by(admin.user.id)
+    .competition(competition.id)
+    .after(&summary)
+    .record(&ctx.db)
+    .await?;
+    tracing::info!(
+        competition = competition.slug,
+        user_id = admin.user.id,
+        action,
+        "phase transition"
+    );
+    format::json(TransitionResponse::new(event))
+}
+
+/// Assigns the submissions to reviewers, replacing existing assignments.
+#[debug_handler]
+pub async fn assign(
+    admin: AdminJWT,
+    Path(path): Path<CompetitionPath>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let competition = path.load(&ctx).await?;
+    let item = transition(&competition, Phase::Assigned)?;
+    let mut warnings = Vec::new();
+    if competition.entered() >= Phase::Assigned {
+        warnings.push("replaced the existing assignments".to_string());
+    }
+
+    let distribution = AssignSubmissions::assign(&ctx, &competition).await?;
+    if distribution.reviews.is_empty() {
+        warnings.push("there are no submissions to assign".to_string());
+    }
+    if !distribution.is_complete() {
+        warnings.push(format!(
+            "{} submissions got fewer than {} reviewers",
+            distribution.understaffed.len(),
+            distribution.reviewers_per_submission
+        ));
+    }
+
+    let competition = item.update(&ctx.db).await?;
+    let summary = Summary {
+        result: distribution,
+        warnings,
+    };
+    record(&ctx, &admin, &competition, "assign", summary).await
+}
+
+/// Generates the scores from the votes, replacing existing scores.
+#[debug_handler]
+pub async fn generate_scores(
+    admin: AdminJWT,
+    Path(path): Path<CompetitionPath>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let competition = path.load(&ctx).await?;
+    let item = transition(&competition, Phase::Scored)?;
+    let mut warnings = Vec::new();
+    if competition.entered() == Phase::Scored {
+        warnings.push("replaced the existing scores".to_string());
+    }
+
+    let leaderboard = GenLeaderboard::generate(&ctx, &competition).await?;
+    if !leaderboard.unvoted.is_empty() {
+        warnings.push(format!(
+            "{} submissions received no votes and are not scored",
+            leaderboard.unvoted.len()
+        ));
+    }
+
+    let competition = item.update(&ctx.db).await?;
+    let summary = Summary {
+        result: leaderboard,
+        warnings,
+    };
+    record(&ctx, &admin, &competition, "generate_scores", summary).await
+}
+
+/// Shows the leaderboard to everyone.
+#[debug_handler]
+pub async fn publish_leaderboard(
+    admin: AdminJWT,
+    Path(path): Path<CompetitionPath>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let competition = path.load(&ctx).await?;
+    let item = transition(&competition, Phase::Published)?;
+    let scores = scores::Entity::find()
+        .filter(scores::Column::CompetitionId.eq(competition.id))
+        .count(&ctx.db)
+        .await?;
+    let mut warnings = Vec::new();
+    if scores == 0 {
+        warnings.push("no scores have been generated yet".to_string());
+    }
+    if competition.is_published() {
+        warnings.push("the leaderboard was already published".to_string());
+    }
+
+    let competition = item.update(&ctx.db).await?;
+    let summary = Summary {
+        result: Published {
+            show_leaderboard: competition.is_published(),
+            scores,
+        },
+        warnings,
+    };
+    record(&ctx, &admin, &competition, "publish_leaderboard", summary).await
+}
+
+/// Moves the competition into a phase that follows from its timings, e.g. closes
+/// submissions early by ending the submission window now.
+#[debug_handler]
+pub async fn move_to(
+    admin: AdminJWT,
+    Path(path): Path<CompetitionPath>,
+    State(ctx): State<AppContext>,
+    Json(params): Json<Params>,
+) -> Result<Response> {
+    if params.phase.is_action() {
+        return bad_request(format!(
+            "{} is entered by running its action, not by moving to it",
+            params.phase
+        ));
+    }
+    let competition = path.load(&ctx).await?;
+    let from = competition.phase(Utc::now().into());
+    let competition = transition(&competition, params.phase)?
+        .update(&ctx.db)
+        .await?;
+    let summary = Summary {
+        result: Moved {
+            from,
+            to: params.phase,
+        },
+        warnings: Vec::new(),
+    };
+    record(&ctx, &admin, &competition, "move", summary).await
+}
+
+/// The transitions of the competition, most recent first.
+#[debug_handler]
+pub async fn transitions(
+    _admin: AdminJWT,
+    Path(path): Path<CompetitionPath>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let competition = path.load(&ctx).await?;
+    let actions: Vec<_> = TRANSITIONS
+        .iter()
+        .map(|action| format!("competition.{action}"))
+        .collect();
+    let events =
+        audit_events::Model::find_by_competition(&ctx.db, competition.id, &actions).await?;
+    format::json(
+        events
+            .into_iter()
+            .map(TransitionResponse::new)
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// Mounted under `/api/admin/competition` for the current competition, and under
+/// `/api/admin/competitions/{competition}` for any other.
+pub fn routes() -> Routes {
+    let routes = Routes::new()
+        .add("/assign", post(assign))
+        .add("/generate-scores", post(generate_scores))
+        .add("/publish-leaderboard", post(publish_leaderboard))
+        .add("/phase", post(move_to))
+        .add("/transitions", get(transitions));
+    Routes::new()
+        .nest("api/admin/competition", routes.clone())
+        .nest("api/admin/competitions/{competition}", routes)
+}
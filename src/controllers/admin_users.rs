@@ -0,0 +1,291 @@
+#![allow(clippy::missing_errors_doc)]
+#![allow(clippy::unnecessary_struct_initialization)]
+#![allow(clippy::unused_async)]
+use std::collections::HashSet;
+
+use loco_rs::{
+    controller::views::pagination::{Pager, PagerMeta},
+    model::query::{self, PaginationQuery},
+    prelude::*,
+};
+use sea_orm::{
+    sea_query::{Expr, Func, LikeExpr},
+    Condition, QueryOrder,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    extractors::AdminJWT,
+    models::{
+        _entities::{admins as admin_entities, users::Column},
+        admins,
+        audit_events::Event,
+        refresh_tokens,
+        users::{self, ActiveModel, Entity, Model, Validator},
+    },
+    views::users::UserResponse,
+};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Params {
+    pub name: String,
+    pub email: String,
+}
+
+impl Params {
+    /// Checks the name and email the same way saving a user does, so mistakes are
+    /// reported as a bad request.
+    fn validate(&self) -> Result<()> {
+        let validator = Validator {
+            name: self.name.trim().to_string(),
+            email: users::normalize_email(&self.email),
+        };
+        Validate::validate(&validator).map_err(|errors| Error::BadRequest(errors.to_string()))
+    }
+
+    fn update(&self, item: &mut ActiveModel) {
+        item.name = Set(self.name.trim().to_string());
+        item.email = Set(users::normalize_email(&self.email));
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListParams {
+    /// Only list users whose name or email contains this, ignoring case
+    pub q: Option<String>,
+    #[serde(flatten)]
+    pub pagination: PaginationQuery,
+}
+
+async fn load_item(ctx: &AppContext, id: i32) -> Result<Model> {
+    let item = Entity::find_by_id(id).one(&ctx.db).await?;
+    item.ok_or_else(|| Error::NotFound)
+}
+
+/// Makes sure no other user has `email` yet.
+async fn ensure_email_free(ctx: &AppContext, email: &str, id: Option<i32>) -> Result<()> {
+    match users::Model::find_by_email(&ctx.db, email).await {
+        Ok(existing) if Some(existing.id) != id => Err(Error::BadRequest(
+            "a user with this email already exists".to_string(),
+        )),
+        _ => Ok(()),
+    }
+}
+
+async fn respond(ctx: &AppContext, user: &Model) -> Result<Response> {
+    let is_admin = admins::Model::is_admin(&ctx.db, user.id).await?;
+    format::json(UserResponse::new(user, is_admin))
+}
+
+#[debug_handler]
+pub async fn list(
+    _admin: AdminJWT,
+    Query(params): Query<ListParams>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let condition = params
+        .q
+        .as_deref()
+        .map(str::trim)
+        .filter(|q| !q.is_empty())
+        .map(|q| {
+            // the search is taken literally, so wildcards in it must not match anything
+            let q = q
+                .to_lowercase()
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_");
+            let pattern = || LikeExpr::new(format!("%{q}%")).escape('\\');
+            Condition::any()
+                .add(Expr::expr(Func::lower(Expr::col(Column::Name))).like(pattern()))
+                .add(Expr::expr(Func::lower(Expr::col(Column::Email))).like(pattern()))
+        });
+    let page = query::paginate(
+        &ctx.db,
+        Entity::find().order_by_asc(Column::Id),
+        condition,
+        &params.pagination,
+    )
+    .await?;
+
+    let admin_ids: HashSet<i32> = admin_entities::Entity::find()
+        .filter(admin_entities::Column::UserId.is_in(page.page.iter().map(|u| u.id)))
+        .all(&ctx.db)
+        .await?
+        .into_iter()
+        .map(|admin| admin.user_id)
+        .collect();
+    let results: Vec<UserResponse> = page
+        .page
+        .iter()
+        .map(|user| UserResponse::new(user, admin_ids.contains(&user.id)))
+        .collect();
+
+    format::json(Pager::new(
+        results,
+        PagerMeta {
+            page: params.pagination.page,
+            page_size: params.pagination.page_size,
+            total_pages: page.total_pages,
+            total_items: page.total_items,
+        },
+    ))
+}
+
+#[debug_handler]
+pub async fn get_one(
+    _admin: AdminJWT,
+    Path(id): Path<i32>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    respond(&ctx, &load_item(&ctx, id).await?).await
+}
+
+/// Onboards a user, who can then log in with a one-time password sent by email.
+#[debug_handler]
+pub async fn add(
+    admin: AdminJWT,
+    State(ctx): State<AppContext>,
+    Json(params): Json<Params>,
+) -> Result<Response> {
+    params.validate()?;
+    ensure_email_free(&ctx, &params.email, None).await?;
+
+    let mut item = ActiveModel {
+        ..Default::default()
+    };
+    params.update(&mut item);
+    let item = item.insert(&ctx.db).await?;
+    Event::new("user.create", "user", Some(item.id))
+        .by(admin.user.id)
+        .after(&UserResponse::new(&item, false))
+        .record(&ctx.db)
+        .await?;
+    respond(&ctx, &item).await
+}
+
+#[debug_handler]
+pub async fn update(
+    admin: AdminJWT,
+    Path(id): Path<i32>,
+    State(ctx): State<AppContext>,
+    Json(params): Json<Params>,
+) -> Result<Response> {
+    let before = load_item(&ctx, id).await?;
+    params.validate()?;
+    ensure_email_free(&ctx, &params.email, Some(before.id)).await?;
+
+    let mut item = before.clone().into_active_model();
+    params.update(&mut item);
+    let item = item.update(&ctx.db).await?;
+    let is_admin = admins::Model::is_admin(&ctx.db, item.id).await?;
+    Event::new("user.update", "user", Some(item.id))
+        .by(admin.user.id)
+        .before(&UserResponse::new(&before, is_admin))
+        .after(&UserResponse::new(&item, is_admin))
+        .record(&ctx.db)
+        .await?;
+    respond(&ctx, &item).await
+}
+
+/// Deletes a user along with everything they submitted and voted.
+#[debug_handler]
+pub async fn remove(
+    admin: AdminJWT,
+    Path(id): Path<i32>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let item = load_item(&ctx, id).await?;
+    if item.id == admin.user.id {
+        return bad_request("you cannot delete yourself");
+    }
+    let is_admin = admins::Model::is_admin(&ctx.db, item.id).await?;
+    item.clone().delete(&ctx.db).await?;
+    Event::new("user.delete", "user", Some(item.id))
+        .by(admin.user.id)
+        .before(&UserResponse::new(&item, is_admin))
+        .record(&ctx.db)
+        .await?;
+    format::empty()
+}
+
+#[debug_handler]
+pub async fn promote(
+    admin: AdminJWT,
+    Path(id): Path<i32>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let item = load_item(&ctx, id).await?;
+    if !admins::Model::is_admin(&ctx.db, item.id).await? {
+        admins::ActiveModel {
+            user_id: Set(item.id),
+            ..Default::default()
+        }
+        .insert(&ctx.db)
+        .await?;
+        Event::new("user.promote", "user", Some(item.id))
+            .by(admin.user.id)
+            .before(&UserResponse::new(&item, false))
+            .after(&UserResponse::new(&item, true))
+            .record(&ctx.db)
+            .await?;
+    }
+    respond(&ctx, &item).await
+}
+
+#[debug_handler]
+pub async fn demote(
+    admin: AdminJWT,
+    Path(id): Path<i32>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let item = load_item(&ctx, id).await?;
+    if item.id == admin.user.id {
+        return bad_request("you cannot demote yourself");
+    }
+    let demoted = admin_entities::Entity::delete_many()
+        .filter(admin_entities::Column::UserId.eq(item.id))
+        .exec(&ctx.db)
+        .await?;
+    if demoted.rows_affected > 0 {
+        Event::new("user.demote", "user", Some(item.id))
+            .by(admin.user.id)
+            .before(&UserResponse::new(&item, true))
+            .after(&UserResponse::new(&item, false))
+            .record(&ctx.db)
+            .await?;
+    }
+    respond(&ctx, &item).await
+}
+
+/// Signs a user out everywhere, e.g. after they logged in on a shared computer.
+/// Their refresh tokens are revoked, so their sessions end once their short-lived
+/// JWTs expire.
+#[debug_handler]
+pub async fn revoke_sessions(
+    admin: AdminJWT,
+    Path(id): Path<i32>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let item = load_item(&ctx, id).await?;
+    let revoked = refresh_tokens::Entity::revoke_all_for_user(&ctx.db, item.id).await?;
+    Event::new("user.revoke_sessions", "user", Some(item.id))
+        .by(admin.user.id)
+        .after(&serde_json::json!({ "revoked": revoked }))
+        .record(&ctx.db)
+        .await?;
+    format::json(serde_json::json!({ "revoked": revoked }))
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("api/admin/users/")
+        .add("/", get(list))
+        .add("/", post(add))
+        .add("{id}", get(get_one))
+        .add("{id}", put(update))
+        .add("{id}", delete(remove))
+        .add("{id}/admin", post(promote))
+        .add("{id}/admin", delete(demote))
+        .add("{id}/sessions", delete(revoke_sessions))
+}
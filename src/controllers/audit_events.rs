@@ -0,0 +1,85 @@
+#![allow(clippy::missing_errors_doc)]
+#![allow(clippy::unnecessary_struct_initialization)]
+#![allow(clippy::unused_async)]
+use loco_rs::{
+    controller::views::pagination::{Pager, PagerMeta},
+    model::query::{self, PaginationQuery},
+    prelude::*,
+};
+use sea_orm::{Condition, QueryOrder};
+use serde::Deserialize;
+
+use crate::{
+    extractors::AdminJWT,
+    models::audit_events::{Column, Entity},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct ListParams {
+    pub actor_id: Option<i32>,
+    /// Either a full action like `score.update`, or a target type like `score`
+    pub action: Option<String>,
+    pub target_type: Option<String>,
+    pub target_id: Option<i32>,
+    pub competition: Option<i32>,
+    #[serde(flatten)]
+    pub pagination: PaginationQuery,
+}
+
+impl ListParams {
+    fn condition(&self) -> Condition {
+        let mut condition = Condition::all();
+        if let Some(actor_id) = self.actor_id {
+            condition = condition.add(Column::ActorId.eq(actor_id));
+        }
+        if let Some(action) = &self.action {
+            condition = if action.contains('.') {
+                condition.add(Column::Action.eq(action))
+            } else {
+                condition.add(Column::Action.starts_with(format!("{action}.")))
+            };
+        }
+        if let Some(target_type) = &self.target_type {
+            condition = condition.add(Column::TargetType.eq(target_type));
+        }
+        if let Some(target_id) = self.target_id {
+            condition = condition.add(Column::TargetId.eq(target_id));
+        }
+        if let Some(competition) = self.competition {
+            condition = condition.add(Column::CompetitionId.eq(competition));
+        }
+        condition
+    }
+}
+
+/// Pages through the audit log, most recent first.
+#[debug_handler]
+pub async fn list(
+    _admin: AdminJWT,
+    Query(params): Query<ListParams>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let page = query::paginate(
+        &ctx.db,
+        Entity::find().order_by_desc(Column::Id),
+        Some(params.condition()),
+        &params.pagination,
+    )
+    .await?;
+
+    format::json(Pager::new(
+        page.page,
+        PagerMeta {
+            page: params.pagination.page,
+            page_size: params.pagination.page_size,
+            total_pages: page.total_pages,
+            total_items: page.total_items,
+        },
+    ))
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("api/admin/audit_events/")
+        .add("/", get(list))
+}
@@ -1,10 +1,21 @@
 use crate::{
+    extractors::forbidden,
+    login::{self, too_many_requests},
     mailers::auth::AuthMailer,
-    models::{_entities::users, users::LoginParams},
+    models::{
+        _entities::users,
+        invite_codes, refresh_tokens,
+        users::{normalize_email, LoginParams, RegisterParams},
+    },
+    rate_limit,
+    validation::FieldErrors,
     views::auth::{CurrentResponse, LoginResponse},
 };
+use axum::middleware::from_fn_with_state;
+use chrono::Local;
 use loco_rs::prelude::*;
 use regex::Regex;
+use sea_orm::TransactionTrait;
 use serde::{Deserialize, Serialize};
 use std::sync::OnceLock;
 
@@ -19,6 +30,30 @@ pub struct OTPParams {
     pub email: String,
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MagicLinkParams {
+    pub token: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RefreshParams {
+    pub refresh_token: String,
+}
+
+/// Starts a session for a user who just proved who they are, answering with a
+/// short-lived JWT and the refresh token to get the next one with.
+async fn start_session(ctx: &AppContext, user: &users::Model) -> Result<Response> {
+    let settings = login::Settings::from_config(&ctx.config)?;
+    let jwt_secret = ctx.config.get_jwt_config()?;
+    let token = user
+        .generate_jwt(&jwt_secret.secret, jwt_secret.expiration)
+        .or_else(|_| unauthorized("unauthorized!"))?;
+    let (_, refresh_token) =
+        refresh_tokens::ActiveModel::issue(&ctx.db, user.id, settings.refresh_token_lifetime())
+            .await?;
+    format::json(LoginResponse::new(user, &token, &refresh_token))
+}
+
 /// Creates a user login and returns a token
 #[debug_handler]
 async fn login(State(ctx): State<AppContext>, Json(params): Json<LoginParams>) -> Result<Response> {
@@ -30,22 +65,69 @@ async fn login(State(ctx): State<AppContext>, Json(params): Json<LoginParams>) -
         return unauthorized("Invalid credentials!");
     };
 
-    let mut valid = false;
-    if let Some(otp) = &user.otp {
-        valid = otp == &params.otp;
-    };
+    let settings = login::Settings::from_config(&ctx.config)?;
+    let now = DateTimeWithTimeZone::from(Local::now());
+    if user.is_otp_locked(now) {
+        return Err(too_many_requests(
+            "too many failed attempts, try again later",
+        ));
+    }
+    if !user.verify_otp(&params.otp, now, settings.otp_expiration()) {
+        user.record_failed_otp(&ctx.db, &settings, now).await?;
+        return unauthorized("unauthorized!");
+    }
+
+    user = user.into_active_model().consume_login(&ctx.db).await?;
+    start_session(&ctx, &user).await
+}
 
-    if !valid {
+/// Exchanges a refresh token for a new JWT and refresh token, the old one can't be
+/// used again. Using it again anyway means it leaked, so every session of the user
+/// is ended.
+#[debug_handler]
+async fn refresh(
+    State(ctx): State<AppContext>,
+    Json(params): Json<RefreshParams>,
+) -> Result<Response> {
+    let Some(current) =
+        refresh_tokens::Model::find_by_token(&ctx.db, &params.refresh_token).await?
+    else {
+        return unauthorized("unauthorized!");
+    };
+    if current.revoked_at.is_some() {
+        tracing::warn!(
+            user_id = current.user_id,
+            "revoked refresh token used, revoking every session of the user"
+        );
+        refresh_tokens::Entity::revoke_all_for_user(&ctx.db, current.user_id).await?;
+        return unauthorized("unauthorized!");
+    }
+    // revoking only succeeds once, so concurrent refreshes can't both rotate the token
+    if !current.is_active(DateTimeWithTimeZone::from(Local::now()))
+        || !current.revoke(&ctx.db).await?
+    {
         return unauthorized("unauthorized!");
     }
 
-    let jwt_secret = ctx.config.get_jwt_config()?;
+    let user = users::Entity::find_by_id(current.user_id)
+        .one(&ctx.db)
+        .await?
+        .ok_or_else(|| Error::Unauthorized("not found".to_string()))?;
+    start_session(&ctx, &user).await
+}
 
-    let token = user
-        .generate_jwt(&jwt_secret.secret, jwt_secret.expiration)
-        .or_else(|_| unauthorized("unauthorized!"))?;
-    user = user.into_active_model().consume_otp(&ctx.db).await?;
-    format::json(LoginResponse::new(&user, &token))
+/// Ends the session of the refresh token, its JWT still works until it expires.
+#[debug_handler]
+async fn logout(
+    State(ctx): State<AppContext>,
+    Json(params): Json<RefreshParams>,
+) -> Result<Response> {
+    if let Some(current) =
+        refresh_tokens::Model::find_by_token(&ctx.db, &params.refresh_token).await?
+    {
+        current.revoke(&ctx.db).await?;
+    }
+    format::empty_json()
 }
 #[debug_handler]
 async fn send_otp(
@@ -56,7 +138,7 @@ async fn send_otp(
     if !email_regex.is_match(&params.email) {
         tracing::debug!(
             email = params.email,
-            "The provided email is invalid or does not match the allowed domains"
+            "The provided email is not a valid address"
         );
         return bad_request("invalid request");
     }
@@ -67,8 +149,123 @@ async fn send_otp(
         );
         return unauthorized("Invalid credentials!");
     };
-    let user = user.into_active_model().create_otp(&ctx.db).await?;
-    AuthMailer::send_otp(&ctx, &user).await?;
+    // a new OTP would be unusable until the lockout ends
+    if user.is_otp_locked(DateTimeWithTimeZone::from(Local::now())) {
+        return Err(too_many_requests(
+            "too many failed attempts, try again later",
+        ));
+    }
+    let (user, otp) = user.into_active_model().create_otp(&ctx.db).await?;
+    AuthMailer::send_otp(&ctx, &user, &otp).await?;
+    format::empty_json()
+}
+
+/// Mails a link to login with instead of typing an OTP
+#[debug_handler]
+async fn magic_link(
+    State(ctx): State<AppContext>,
+    Json(params): Json<OTPParams>,
+) -> Result<Response> {
+    let email_regex = get_allow_email_domain_re();
+    if !email_regex.is_match(&params.email) {
+        tracing::debug!(
+            email = params.email,
+            "The provided email is not a valid address"
+        );
+        return bad_request("invalid request");
+    }
+    let Ok(user) = users::Model::find_by_email(&ctx.db, &params.email).await else {
+        tracing::debug!(
+            email = params.email,
+            "magic link request with non-existent email"
+        );
+        return unauthorized("Invalid credentials!");
+    };
+    if user.is_otp_locked(DateTimeWithTimeZone::from(Local::now())) {
+        return Err(too_many_requests(
+            "too many failed attempts, try again later",
+        ));
+    }
+    let (user, token) = user.into_active_model().create_magic_link(&ctx.db).await?;
+    AuthMailer::send_magic_link(&ctx, &user, &token).await?;
+    format::empty_json()
+}
+
+/// Logs in with the token of a magic link, which can only be used once. A POST
+/// rather than the link itself, so mail scanners following links don't use it up.
+#[debug_handler]
+async fn verify_magic_link(
+    State(ctx): State<AppContext>,
+    Json(params): Json<MagicLinkParams>,
+) -> Result<Response> {
+    let now = DateTimeWithTimeZone::from(Local::now());
+    let Ok(user) = users::Model::consume_magic_token(&ctx.db, &params.token, now).await else {
+        tracing::debug!("login attempt with unknown, expired or used magic link");
+        return unauthorized("unauthorized!");
+    };
+    start_session(&ctx, &user).await
+}
+
+/// Registers a participant who has an invite code or an email of an allowed domain,
+/// mailing them the OTP for their first login.
+#[debug_handler]
+async fn register(
+    State(ctx): State<AppContext>,
+    JsonValidateWithMessage(params): JsonValidateWithMessage<RegisterParams>,
+) -> Result<Response> {
+    let settings = login::Settings::from_config(&ctx.config)?;
+    let email = normalize_email(&params.email);
+    if users::Model::find_by_email(&ctx.db, &email).await.is_ok() {
+        return Err(FieldErrors::single(
+            "email",
+            "taken",
+            "is already registered",
+        ));
+    }
+
+    // emails of allowed domains don't use up a code they were given anyway
+    let code = params
+        .secret
+        .as_deref()
+        .filter(|code| !code.trim().is_empty());
+    let invite = if settings.allows_email_domain(&email) {
+        None
+    } else if let Some(code) = code {
+        let Some(invite) = invite_codes::Model::find_unused(&ctx.db, code).await? else {
+            return Err(FieldErrors::single(
+                "secret",
+                "invalid",
+                "is not a valid invite code",
+            ));
+        };
+        Some(invite)
+    } else {
+        return Err(forbidden(
+            "registering needs an invite code or an email of an allowed domain",
+        ));
+    };
+
+    let txn = ctx.db.begin().await?;
+    let user = users::ActiveModel {
+        email: Set(email),
+        name: Set(params.name.trim().to_string()),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await?;
+    if let Some(invite) = invite {
+        if !invite.redeem(&txn, user.id).await? {
+            return Err(FieldErrors::single(
+                "secret",
+                "invalid",
+                "is not a valid invite code",
+            ));
+        }
+    }
+    txn.commit().await?;
+
+    let (user, otp) = user.into_active_model().create_otp(&ctx.db).await?;
+    AuthMailer::send_otp(&ctx, &user, &otp).await?;
     format::empty_json()
 }
 
@@ -81,7 +278,27 @@ async fn current(auth: auth::JWT, State(ctx): State<AppContext>) -> Result<Respo
 pub fn routes() -> Routes {
     Routes::new()
         .prefix("/api/auth")
-        .add("/login", post(login))
-        .add("/send-otp", post(send_otp))
+        .add(
+            "/login",
+            post(login).layer(from_fn_with_state("login", rate_limit::limit)),
+        )
+        .add(
+            "/register",
+            post(register).layer(from_fn_with_state("register", rate_limit::limit)),
+        )
+        .add(
+            "/send-otp",
+            post(send_otp).layer(from_fn_with_state("send_otp", rate_limit::limit)),
+        )
+        .add(
+            "/magic-link",
+            post(magic_link).layer(from_fn_with_state("send_otp", rate_limit::limit)),
+        )
+        .add(
+            "/magic-link/verify",
+            post(verify_magic_link).layer(from_fn_with_state("login", rate_limit::limit)),
+        )
+        .add("/refresh", post(refresh))
+        .add("/logout", post(logout))
         .add("/current", get(current))
 }
@@ -0,0 +1,92 @@
+#![allow(clippy::missing_errors_doc)]
+#![allow(clippy::unnecessary_struct_initialization)]
+#![allow(clippy::unused_async)]
+use std::collections::HashMap;
+
+use loco_rs::prelude::*;
+use serde::Serialize;
+
+use crate::{
+    aggregators::{Calibration, VoterCalibration},
+    controllers::competitions::{scoped, CompetitionPath},
+    extractors::AdminJWT,
+    models::{_entities::users as user_entities, vote_scores},
+};
+
+/// Calibration of a single voter, along with who they are
+#[derive(Clone, Debug, Serialize)]
+pub struct VoterReport {
+    pub name: String,
+    pub email: String,
+    #[serde(flatten)]
+    pub calibration: VoterCalibration,
+}
+
+/// Per-voter harshness/leniency factors of a competition, to explain how scores
+/// were corrected when the competition calibrates voters
+#[derive(Clone, Debug, Serialize)]
+pub struct CalibrationReport {
+    pub competition_id: i32,
+    /// Whether the factors are applied when generating scores
+    pub applied: bool,
+    pub global_mean: f32,
+    pub global_std_dev: f32,
+    /// Most lenient voters first
+    pub voters: Vec<VoterReport>,
+}
+
+#[debug_handler]
+pub async fn report(
+    _admin: AdminJWT,
+    Path(path): Path<CompetitionPath>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let competition = path.load(&ctx).await?;
+
+    let ratings = vote_scores::Entity::find_ratings(&ctx.db, competition.id).await?;
+    let calibration = Calibration::estimate(
+        &ratings
+            .into_iter()
+            .map(|(criterion, ratings)| (criterion.scale(), ratings))
+            .collect::<Vec<_>>(),
+    );
+
+    let voters: HashMap<i32, user_entities::Model> = user_entities::Entity::find()
+        .filter(user_entities::Column::Id.is_in(calibration.voters.keys().copied()))
+        .all(&ctx.db)
+        .await?
+        .into_iter()
+        .map(|voter| (voter.id, voter))
+        .collect();
+
+    let mut reports: Vec<VoterReport> = calibration
+        .voters
+        .into_values()
+        .map(|calibration| {
+            let voter = voters.get(&calibration.voter_id);
+            VoterReport {
+                name: voter.map_or_else(|| "Unknown".to_string(), |v| v.name.clone()),
+                email: voter.map(|v| v.email.clone()).unwrap_or_default(),
+                calibration,
+            }
+        })
+        .collect();
+    reports.sort_by(|a, b| {
+        b.calibration
+            .offset
+            .total_cmp(&a.calibration.offset)
+            .then(a.calibration.voter_id.cmp(&b.calibration.voter_id))
+    });
+
+    format::json(CalibrationReport {
+        competition_id: competition.id,
+        applied: competition.calibrate_voters,
+        global_mean: calibration.global_mean,
+        global_std_dev: calibration.global_std_dev,
+        voters: reports,
+    })
+}
+
+pub fn routes() -> Routes {
+    scoped("calibration", Routes::new().add("/", get(report)))
+}
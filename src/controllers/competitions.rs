@@ -0,0 +1,297 @@
+#![allow(clippy::missing_errors_doc)]
+#![allow(clippy::unnecessary_struct_initialization)]
+#![allow(clippy::unused_async)]
+use chrono::Utc;
+use loco_rs::prelude::*;
+use sea_orm::QueryOrder;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::{
+    aggregators,
+    extractors::AdminJWT,
+    models::{
+        audit_events::Event,
+        competitions::{self, ActiveModel, Entity, Model},
+    },
+    views::competitions::CompetitionResponse,
+};
+
+/// Path parameters of a collection route. `competition` is only present when the
+/// route is mounted under `/api/competitions/{competition}`, otherwise the current
+/// competition is used.
+#[derive(Clone, Debug, Deserialize)]
+pub struct CompetitionPath {
+    pub competition: Option<String>,
+}
+
+/// Path parameters of an item route, see [`CompetitionPath`].
+#[derive(Clone, Debug, Deserialize)]
+pub struct CompetitionItemPath {
+    pub competition: Option<String>,
+    pub id: i32,
+}
+
+impl CompetitionPath {
+    pub async fn load(&self, ctx: &AppContext) -> Result<Model> {
+        load_competition(ctx, self.competition.as_deref()).await
+    }
+
+    /// Like [`CompetitionPath::load`], but resolves to `None` when no competition
+    /// has been created yet, so listings can answer with an empty result.
+    pub async fn try_load(&self, ctx: &AppContext) -> Result<Option<Model>> {
+        match Model::try_resolve(&ctx.db, self.competition.as_deref()).await {
+            Ok(competition) => Ok(competition),
+            Err(ModelError::EntityNotFound) => Err(Error::NotFound),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+impl CompetitionItemPath {
+    pub async fn load(&self, ctx: &AppContext) -> Result<Model> {
+        load_competition(ctx, self.competition.as_deref()).await
+    }
+}
+
+/// Resolves a competition by id or slug, `None` being the current competition.
+pub async fn load_competition(ctx: &AppContext, reference: Option<&str>) -> Result<Model> {
+    match Model::resolve(&ctx.db, reference).await {
+        Ok(competition) => Ok(competition),
+        Err(ModelError::EntityNotFound) => Err(Error::NotFound),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Mounts the routes of a competition scoped resource twice: under `/api/<resource>`,
+/// acting on the current competition, and under
+/// `/api/competitions/{competition}/<resource>` so past rounds stay browsable.
+#[must_use]
+pub fn scoped(resource: &str, routes: Routes) -> Routes {
+    Routes::new()
+        .nest(&format!("api/{resource}"), routes.clone())
+        .nest(
+            &format!("api/competitions/{{competition}}/{resource}"),
+            routes,
+        )
+}
+
+/// Tells a `null` field, `Some(None)`, apart from a missing one, `None`.
+fn deserialize_nullable<'de, T, D>(deserializer: D) -> std::result::Result<Option<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Params {
+    pub name: String,
+    /// Derived from the name when creating a competition, kept as is when missing
+    /// on update so its urls stay stable
+    pub slug: Option<String>,
+    /// The timings are kept as is when missing, `null` unsetting them
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub submission_start: Option<Option<DateTimeWithTimeZone>>,
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub submission_end: Option<Option<DateTimeWithTimeZone>>,
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub voting_start: Option<Option<DateTimeWithTimeZone>>,
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub voting_end: Option<Option<DateTimeWithTimeZone>>,
+    /// How votes are turned into scores, see [`aggregators::NAMES`]
+    pub aggregator: Option<String>,
+    /// Whether voters' harshness/leniency is corrected before aggregating
+    pub calibrate_voters: Option<bool>,
+    /// How many reviewers each submission is assigned
+    pub reviewers_per_submission: Option<i32>,
+    /// How many submissions a user is assigned at most, kept as is when missing,
+    /// `null` making it unlimited
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub max_reviews_per_user: Option<Option<i32>>,
+    /// Hours into voting after which reviewers who have not voted are topped up
+    pub reassign_after_hours: Option<i32>,
+    /// Minutes after the submission window closes during which entries are still
+    /// accepted, flagged as late
+    pub grace_period_minutes: Option<i32>,
+}
+
+impl Params {
+    /// Checks the params, the timings once applied to the `current` ones.
+    fn validate(&self, current: competitions::Timings) -> Result<()> {
+        if self.reviewers_per_submission.is_some_and(|n| n < 1) {
+            return Err(Error::BadRequest(
+                "reviewers_per_submission must be at least 1".to_string(),
+            ));
+        }
+        if self.reassign_after_hours.is_some_and(|n| n < 0) {
+            return Err(Error::BadRequest(
+                "reassign_after_hours must not be negative".to_string(),
+            ));
+        }
+        if self.grace_period_minutes.is_some_and(|n| n < 0) {
+            return Err(Error::BadRequest(
+                "grace_period_minutes must not be negative".to_string(),
+            ));
+        }
+        if matches!(self.max_reviews_per_user, Some(Some(n)) if n < 1) {
+            return Err(Error::BadRequest(
+                "max_reviews_per_user must be at least 1".to_string(),
+            ));
+        }
+        if let Some(aggregator) = &self.aggregator {
+            if aggregators::from_name(aggregator).is_none() {
+                return Err(Error::BadRequest(format!(
+                    "unknown aggregator {aggregator}, expected one of {}",
+                    aggregators::NAMES.join(", ")
+                )));
+            }
+        }
+        competitions::Timings {
+            submission_start: self.submission_start.unwrap_or(current.submission_start),
+            submission_end: self.submission_end.unwrap_or(current.submission_end),
+            voting_start: self.voting_start.unwrap_or(current.voting_start),
+            voting_end: self.voting_end.unwrap_or(current.voting_end),
+        }
+        .validate()
+        .map_err(Error::BadRequest)
+    }
+
+    /// The slug of a new competition.
+    fn slug(&self) -> String {
+        self.slug
+            .clone()
+            .unwrap_or_else(|| competitions::slugify(&self.name))
+    }
+
+    fn update(&self, item: &mut ActiveModel) {
+        item.name = Set(self.name.clone());
+        if let Some(slug) = &self.slug {
+            item.slug = Set(slug.clone());
+        }
+        if let Some(submission_start) = self.submission_start {
+            item.submission_start = Set(submission_start);
+        }
+        if let Some(submission_end) = self.submission_end {
+            item.submission_end = Set(submission_end);
+        }
+        if let Some(voting_start) = self.voting_start {
+            item.voting_start = Set(voting_start);
+        }
+        if let Some(voting_end) = self.voting_end {
+            item.voting_end = Set(voting_end);
+        }
+        if let Some(aggregator) = &self.aggregator {
+            item.aggregator = Set(aggregator.clone());
+        }
+        if let Some(calibrate_voters) = self.calibrate_voters {
+            item.calibrate_voters = Set(calibrate_voters);
+        }
+        if let Some(reviewers_per_submission) = self.reviewers_per_submission {
+            item.reviewers_per_submission = Set(reviewers_per_submission);
+        }
+        if let Some(max_reviews_per_user) = self.max_reviews_per_user {
+            item.max_reviews_per_user = Set(max_reviews_per_user);
+        }
+        if let Some(reassign_after_hours) = self.reassign_after_hours {
+            item.reassign_after_hours = Set(reassign_after_hours);
+        }
+        if let Some(grace_period_minutes) = self.grace_period_minutes {
+            item.grace_period_minutes = Set(grace_period_minutes);
+        }
+    }
+}
+
+/// Makes sure no competition other than `id` uses `slug` yet.
+async fn ensure_slug_free(ctx: &AppContext, slug: &str, id: Option<i32>) -> Result<()> {
+    let existing = Entity::find()
+        .filter(competitions::Column::Slug.eq(slug))
+        .one(&ctx.db)
+        .await?;
+    match existing {
+        Some(existing) if Some(existing.id) != id => Err(Error::BadRequest(format!(
+            "a competition with the slug {slug} already exists"
+        ))),
+        _ => Ok(()),
+    }
+}
+
+#[debug_handler]
+pub async fn list(State(ctx): State<AppContext>) -> Result<Response> {
+    format::json(
+        Entity::find()
+            .order_by_desc(competitions::Column::Id)
+            .all(&ctx.db)
+            .await?,
+    )
+}
+
+#[debug_handler]
+pub async fn get_one(
+    Path(competition): Path<String>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    format::json(CompetitionResponse::new(
+        load_competition(&ctx, Some(&competition)).await?,
+        Utc::now().into(),
+    ))
+}
+
+#[debug_handler]
+pub async fn add(
+    admin: AdminJWT,
+    State(ctx): State<AppContext>,
+    Json(params): Json<Params>,
+) -> Result<Response> {
+    params.validate(competitions::Timings::default())?;
+    let slug = params.slug();
+    ensure_slug_free(&ctx, &slug, None).await?;
+
+    let mut item = ActiveModel {
+        slug: Set(slug),
+        ..Default::default()
+    };
+    params.update(&mut item);
+    let item = item.insert(&ctx.db).await?;
+    Event::new("competition.create", "competition", Some(item.id))
+        .by(admin.user.id)
+        .competition(item.id)
+        .after(&item)
+        .record(&ctx.db)
+        .await?;
+    format::json(item)
+}
+
+#[debug_handler]
+pub async fn update(
+    admin: AdminJWT,
+    Path(competition): Path<String>,
+    State(ctx): State<AppContext>,
+    Json(params): Json<Params>,
+) -> Result<Response> {
+    let before = load_competition(&ctx, Some(&competition)).await?;
+    params.validate(before.timings())?;
+    if let Some(slug) = &params.slug {
+        ensure_slug_free(&ctx, slug, Some(before.id)).await?;
+    }
+    let mut item = before.clone().into_active_model();
+    params.update(&mut item);
+    let item = item.update(&ctx.db).await?;
+    Event::new("competition.update", "competition", Some(item.id))
+        .by(admin.user.id)
+        .competition(item.id)
+        .before(&before)
+        .after(&item)
+        .record(&ctx.db)
+        .await?;
+    format::json(item)
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("api/competitions/")
+        .add("/", get(list))
+        .add("/", post(add))
+        .add("{competition}", get(get_one))
+        .add("{competition}", put(update))
+}
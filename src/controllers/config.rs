@@ -1,11 +1,16 @@
 #![allow(clippy::missing_errors_doc)]
 #![allow(clippy::unnecessary_struct_initialization)]
 #![allow(clippy::unused_async)]
-use crate::models::{
-    _entities::configs::{ActiveModel, Entity, Model},
-    admins, users,
+use crate::{
+    controllers::competitions::load_competition,
+    extractors::AdminJWT,
+    models::{
+        audit_events::Event,
+        competitions::{ActiveModel, Model, Timings},
+    },
+    views::competitions::CompetitionResponse,
 };
-use loco_rs::controller::extractor::auth;
+use chrono::Utc;
 use loco_rs::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -18,6 +23,17 @@ pub struct Params {
 }
 
 impl Params {
+    fn validate(&self) -> Result<()> {
+        Timings {
+            submission_start: self.submission_start,
+            submission_end: self.submission_end,
+            voting_start: self.voting_start,
+            voting_end: self.voting_end,
+        }
+        .validate()
+        .map_err(Error::BadRequest)
+    }
+
     fn update(&self, item: &mut ActiveModel) {
         item.submission_start = Set(self.submission_start);
         item.submission_end = Set(self.submission_end);
@@ -26,33 +42,38 @@ impl Params {
     }
 }
 
+/// The config endpoints always act on the current competition.
 async fn load_item(ctx: &AppContext) -> Result<Model> {
-    let item = Entity::find().one(&ctx.db).await?;
-    item.ok_or_else(|| Error::NotFound)
+    load_competition(ctx, None).await
 }
 
 #[debug_handler]
 pub async fn update(
     State(ctx): State<AppContext>,
-    auth: auth::JWT,
+    admin: AdminJWT,
     Json(params): Json<Params>,
 ) -> Result<Response> {
-    let user = users::Model::find_by_pid(&ctx.db, &auth.claims.pid).await?;
-    let is_admin = admins::Model::is_admin(&ctx.db, user.id).await?;
-    if !is_admin {
-        return unauthorized("unauthorized access");
-    }
-
-    let item = load_item(&ctx).await?;
-    let mut item = item.into_active_model();
+    params.validate()?;
+    let before = load_item(&ctx).await?;
+    let mut item = before.clone().into_active_model();
     params.update(&mut item);
     let item = item.update(&ctx.db).await?;
+    Event::new("competition.update_timings", "competition", Some(item.id))
+        .by(admin.user.id)
+        .competition(item.id)
+        .before(&before)
+        .after(&item)
+        .record(&ctx.db)
+        .await?;
     format::json(item)
 }
 
 #[debug_handler]
 pub async fn get_one(State(ctx): State<AppContext>) -> Result<Response> {
-    format::json(load_item(&ctx).await?)
+    format::json(CompetitionResponse::new(
+        load_item(&ctx).await?,
+        Utc::now().into(),
+    ))
 }
 
 pub fn routes() -> Routes {
@@ -0,0 +1,121 @@
+#![allow(clippy::missing_errors_doc)]
+#![allow(clippy::unnecessary_struct_initialization)]
+#![allow(clippy::unused_async)]
+use std::collections::HashMap;
+
+use loco_rs::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::models::{
+    _entities::users as user_entities,
+    conflicts::{self, ActiveModel, Entity, Model},
+    users,
+};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Params {
+    /// Email of the user the conflict is with
+    pub email: String,
+}
+
+/// A declared conflict, along with who it is with
+#[derive(Clone, Debug, Serialize)]
+pub struct ConflictWithUser {
+    #[serde(flatten)]
+    pub conflict: Model,
+    pub other_user_name: String,
+    pub other_user_email: String,
+}
+
+async fn with_users(ctx: &AppContext, conflicts: Vec<Model>) -> Result<Vec<ConflictWithUser>> {
+    let other_users: HashMap<i32, user_entities::Model> = user_entities::Entity::find()
+        .filter(user_entities::Column::Id.is_in(conflicts.iter().map(|c| c.other_user_id)))
+        .all(&ctx.db)
+        .await?
+        .into_iter()
+        .map(|user| (user.id, user))
+        .collect();
+
+    Ok(conflicts
+        .into_iter()
+        .map(|conflict| {
+            let other_user = other_users.get(&conflict.other_user_id);
+            ConflictWithUser {
+                other_user_name: other_user
+                    .map_or_else(|| "Unknown".to_string(), |u| u.name.clone()),
+                other_user_email: other_user.map(|u| u.email.clone()).unwrap_or_default(),
+                conflict,
+            }
+        })
+        .collect())
+}
+
+/// Lists the conflicts of interest the current user declared.
+#[debug_handler]
+pub async fn mine(auth: auth::JWT, State(ctx): State<AppContext>) -> Result<Response> {
+    let user = users::Model::find_by_pid(&ctx.db, &auth.claims.pid).await?;
+    let conflicts = Model::find_by_user(&ctx.db, user.id).await?;
+    format::json(with_users(&ctx, conflicts).await?)
+}
+
+/// Declares a conflict of interest with another user, so neither is assigned to
+/// review the other's submission.
+#[debug_handler]
+pub async fn add(
+    auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(params): Json<Params>,
+) -> Result<Response> {
+    let user = users::Model::find_by_pid(&ctx.db, &auth.claims.pid).await?;
+    let Ok(other_user) = users::Model::find_by_email(&ctx.db, &params.email).await else {
+        return not_found();
+    };
+    if other_user.id == user.id {
+        return bad_request("you cannot declare a conflict of interest with yourself");
+    }
+
+    let existing = Entity::find()
+        .filter(conflicts::Column::UserId.eq(user.id))
+        .filter(conflicts::Column::OtherUserId.eq(other_user.id))
+        .one(&ctx.db)
+        .await?;
+    let conflict = if let Some(conflict) = existing {
+        conflict
+    } else {
+        ActiveModel {
+            user_id: Set(user.id),
+            other_user_id: Set(other_user.id),
+            ..Default::default()
+        }
+        .insert(&ctx.db)
+        .await?
+    };
+
+    let mut conflicts = with_users(&ctx, vec![conflict]).await?;
+    format::json(conflicts.remove(0))
+}
+
+/// Withdraws a conflict of interest the current user declared.
+#[debug_handler]
+pub async fn remove(
+    auth: auth::JWT,
+    Path(id): Path<i32>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let user = users::Model::find_by_pid(&ctx.db, &auth.claims.pid).await?;
+    let conflict = Entity::find_by_id(id)
+        .one(&ctx.db)
+        .await?
+        .filter(|conflict| conflict.user_id == user.id)
+        .ok_or(Error::NotFound)?;
+    conflict.delete(&ctx.db).await?;
+    format::empty()
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("api/conflicts/")
+        .add("mine", get(mine))
+        .add("/", post(add))
+        .add("{id}", delete(remove))
+}
@@ -0,0 +1,152 @@
+#![allow(clippy::missing_errors_doc)]
+#![allow(clippy::unnecessary_struct_initialization)]
+#![allow(clippy::unused_async)]
+use std::collections::HashSet;
+
+use loco_rs::prelude::*;
+use sea_orm::{PaginatorTrait, TransactionTrait};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    controllers::competitions::{scoped, CompetitionPath},
+    extractors::AdminJWT,
+    models::{
+        _entities::votes,
+        audit_events::Event,
+        criteria::{self, ActiveModel, Entity, Model},
+    },
+};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Params {
+    pub key: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub min_score: i32,
+    pub max_score: i32,
+    pub weight: f32,
+}
+
+impl Params {
+    fn update(&self, item: &mut ActiveModel, competition_id: i32, position: i32) {
+        item.competition_id = Set(competition_id);
+        item.key = Set(self.key.clone());
+        item.name = Set(self.name.clone());
+        item.description = Set(self.description.clone());
+        item.min_score = Set(self.min_score);
+        item.max_score = Set(self.max_score);
+        item.weight = Set(self.weight);
+        item.position = Set(position);
+    }
+}
+
+/// Validates a whole rubric: at least one criterion, unique non-empty keys, a
+/// non-empty score range and a positive weight for each criterion.
+fn validate(rubric: &[Params]) -> Result<()> {
+    if rubric.is_empty() {
+        return Err(Error::BadRequest(
+            "a rubric needs at least one criterion".to_string(),
+        ));
+    }
+
+    let mut keys = HashSet::new();
+    for criterion in rubric {
+        if criterion.key.is_empty()
+            || !criterion
+                .key
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        {
+            return Err(Error::BadRequest(format!(
+                "criterion key '{}' must only contain lowercase letters, digits and '_'",
+                criterion.key
+            )));
+        }
+        if !keys.insert(criterion.key.as_str()) {
+            return Err(Error::BadRequest(format!(
+                "criterion key '{}' is used twice",
+                criterion.key
+            )));
+        }
+        if criterion.min_score >= criterion.max_score {
+            return Err(Error::BadRequest(format!(
+                "{} must have a min_score below its max_score",
+                criterion.key
+            )));
+        }
+        if criterion.weight.is_nan() || criterion.weight <= 0.0 {
+            return Err(Error::BadRequest(format!(
+                "{} must have a positive weight",
+                criterion.key
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+#[debug_handler]
+pub async fn list(
+    Path(path): Path<CompetitionPath>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let competition = path.load(&ctx).await?;
+    format::json(Model::for_competition(&ctx.db, competition.id).await?)
+}
+
+/// Replaces the rubric of a competition. Only possible before the first vote is
+/// cast, as existing votes would no longer match the rubric.
+#[debug_handler]
+pub async fn replace(
+    admin: AdminJWT,
+    Path(path): Path<CompetitionPath>,
+    State(ctx): State<AppContext>,
+    Json(params): Json<Vec<Params>>,
+) -> Result<Response> {
+    let competition = path.load(&ctx).await?;
+    validate(&params)?;
+
+    let vote_count = votes::Entity::find()
+        .filter(votes::Column::CompetitionId.eq(competition.id))
+        .count(&ctx.db)
+        .await?;
+    if vote_count > 0 {
+        return bad_request("the rubric cannot be changed once votes have been cast");
+    }
+
+    let before = Model::for_competition(&ctx.db, competition.id).await?;
+    let txn = ctx.db.begin().await?;
+    Entity::delete_many()
+        .filter(criteria::Column::CompetitionId.eq(competition.id))
+        .exec(&txn)
+        .await?;
+    for (position, criterion) in params.iter().enumerate() {
+        let mut item = ActiveModel {
+            ..Default::default()
+        };
+        criterion.update(
+            &mut item,
+            competition.id,
+            i32::try_from(position).unwrap_or_default(),
+        );
+        item.insert(&txn).await?;
+    }
+    txn.commit().await?;
+
+    let rubric = Model::for_competition(&ctx.db, competition.id).await?;
+    Event::new("criteria.replace", "competition", Some(competition.id))
+        .by(admin.user.id)
+        .competition(competition.id)
+        .before(&before)
+        .after(&rubric)
+        .record(&ctx.db)
+        .await?;
+    format::json(rubric)
+}
+
+pub fn routes() -> Routes {
+    scoped(
+        "criteria",
+        Routes::new().add("/", get(list)).add("/", put(replace)),
+    )
+}
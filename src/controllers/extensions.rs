@@ -0,0 +1,174 @@
+#![allow(clippy::missing_errors_doc)]
+#![allow(clippy::unnecessary_struct_initialization)]
+#![allow(clippy::unused_async)]
+use std::collections::HashMap;
+
+use loco_rs::prelude::*;
+use sea_orm::{QueryOrder, TransactionTrait};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    controllers::competitions::{scoped, CompetitionItemPath, CompetitionPath},
+    extractors::AdminJWT,
+    models::{
+        _entities::users,
+        audit_events::Event,
+        deadline_extensions::{self, ActiveModel, Entity, Model},
+    },
+    validation::FieldErrors,
+};
+
+#[derive(Clone, Debug, Serialize, Deserialize, Validate)]
+pub struct Params {
+    /// Email of the user the extension is granted to
+    #[validate(email(message = "must be an email address"))]
+    pub email: String,
+    pub extended_until: DateTimeWithTimeZone,
+    #[validate(length(max = 2000, message = "must be at most 2000 characters"))]
+    pub reason: Option<String>,
+}
+
+/// An extension, along with who it was granted to
+#[derive(Clone, Debug, Serialize)]
+pub struct ExtensionWithUser {
+    #[serde(flatten)]
+    pub extension: Model,
+    pub user_name: String,
+    pub user_email: String,
+}
+
+async fn with_users(ctx: &AppContext, extensions: Vec<Model>) -> Result<Vec<ExtensionWithUser>> {
+    let users: HashMap<i32, users::Model> = users::Entity::find()
+        .filter(users::Column::Id.is_in(extensions.iter().map(|e| e.user_id)))
+        .all(&ctx.db)
+        .await?
+        .into_iter()
+        .map(|user| (user.id, user))
+        .collect();
+
+    Ok(extensions
+        .into_iter()
+        .map(|extension| {
+            let user = users.get(&extension.user_id);
+            ExtensionWithUser {
+                user_name: user.map_or_else(|| "Unknown".to_string(), |u| u.name.clone()),
+                user_email: user.map(|u| u.email.clone()).unwrap_or_default(),
+                extension,
+            }
+        })
+        .collect())
+}
+
+/// Lists the deadline extensions granted in the competition.
+#[debug_handler]
+pub async fn list(
+    _admin: AdminJWT,
+    Path(path): Path<CompetitionPath>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let competition = path.load(&ctx).await?;
+    let extensions = Entity::find()
+        .filter(deadline_extensions::Column::CompetitionId.eq(competition.id))
+        .order_by_asc(deadline_extensions::Column::Id)
+        .all(&ctx.db)
+        .await?;
+    format::json(with_users(&ctx, extensions).await?)
+}
+
+/// Grants a user until `extended_until` to submit, replacing an extension they
+/// were granted before.
+#[debug_handler]
+pub async fn grant(
+    admin: AdminJWT,
+    Path(path): Path<CompetitionPath>,
+    State(ctx): State<AppContext>,
+    JsonValidateWithMessage(params): JsonValidateWithMessage<Params>,
+) -> Result<Response> {
+    let competition = path.load(&ctx).await?;
+    let Ok(user) = users::Model::find_by_email(&ctx.db, &params.email).await else {
+        return not_found();
+    };
+    if competition
+        .submission_end
+        .is_none_or(|end| params.extended_until <= end)
+    {
+        return Err(FieldErrors::single(
+            "extended_until",
+            "after_submission_end",
+            "must be after the end of the submission period",
+        ));
+    }
+    let reason = params
+        .reason
+        .map(|reason| reason.trim().to_string())
+        .filter(|reason| !reason.is_empty());
+
+    let txn = ctx.db.begin().await?;
+    let existing = deadline_extensions::Model::find_for_user(&txn, competition.id, user.id).await?;
+    let extension = if let Some(before) = existing.clone() {
+        let mut item = before.into_active_model();
+        item.extended_until = Set(params.extended_until);
+        item.reason = Set(reason);
+        item.granted_by_id = Set(admin.user.id);
+        item.update(&txn).await?
+    } else {
+        ActiveModel {
+            competition_id: Set(competition.id),
+            user_id: Set(user.id),
+            granted_by_id: Set(admin.user.id),
+            extended_until: Set(params.extended_until),
+            reason: Set(reason),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?
+    };
+    let mut event = Event::new("extension.grant", "deadline_extension", Some(extension.id))
+        .by(admin.user.id)
+        .competition(competition.id)
+        .after(&extension);
+    if let Some(before) = &existing {
+        event = event.before(before);
+    }
+    event.record(&txn).await?;
+    txn.commit().await?;
+
+    let mut extensions = with_users(&ctx, vec![extension]).await?;
+    format::json(extensions.remove(0))
+}
+
+/// Revokes an extension, the user is held to the regular deadline again.
+#[debug_handler]
+pub async fn revoke(
+    admin: AdminJWT,
+    Path(path): Path<CompetitionItemPath>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let competition = path.load(&ctx).await?;
+    let extension = Entity::find_by_id(path.id)
+        .filter(deadline_extensions::Column::CompetitionId.eq(competition.id))
+        .one(&ctx.db)
+        .await?
+        .ok_or(Error::NotFound)?;
+
+    let txn = ctx.db.begin().await?;
+    extension.clone().delete(&txn).await?;
+    Event::new("extension.revoke", "deadline_extension", Some(extension.id))
+        .by(admin.user.id)
+        .competition(competition.id)
+        .before(&extension)
+        .record(&txn)
+        .await?;
+    txn.commit().await?;
+    format::empty()
+}
+
+pub fn routes() -> Routes {
+    scoped(
+        "extensions",
+        Routes::new()
+            .add("/", get(list))
+            .add("/", post(grant))
+            .add("/{id}", delete(revoke)),
+    )
+}
@@ -0,0 +1,124 @@
+#![allow(clippy::missing_errors_doc)]
+#![allow(clippy::unnecessary_struct_initialization)]
+#![allow(clippy::unused_async)]
+use std::collections::HashMap;
+
+use loco_rs::prelude::*;
+use sea_orm::{QueryOrder, TransactionTrait};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    extractors::AdminJWT,
+    models::{
+        _entities::users,
+        audit_events::Event,
+        invite_codes::{ActiveModel, Column, Entity, Model},
+    },
+};
+
+#[derive(Clone, Debug, Deserialize, Validate)]
+pub struct Params {
+    /// How many codes to generate, one by default
+    #[validate(range(min = 1, max = 100, message = "must be between 1 and 100"))]
+    pub count: Option<u32>,
+    /// Who the codes are meant for, to tell them apart later
+    #[validate(length(max = 200, message = "must be at most 200 characters"))]
+    pub note: Option<String>,
+}
+
+/// A code, along with who registered with it
+#[derive(Clone, Debug, Serialize)]
+pub struct InviteCodeWithUser {
+    #[serde(flatten)]
+    pub invite_code: Model,
+    pub used_by_email: Option<String>,
+}
+
+/// Lists every invite code, newest first.
+#[debug_handler]
+pub async fn list(_admin: AdminJWT, State(ctx): State<AppContext>) -> Result<Response> {
+    let codes = Entity::find()
+        .order_by_desc(Column::Id)
+        .all(&ctx.db)
+        .await?;
+    let emails: HashMap<i32, String> = users::Entity::find()
+        .filter(users::Column::Id.is_in(codes.iter().filter_map(|code| code.used_by_id)))
+        .all(&ctx.db)
+        .await?
+        .into_iter()
+        .map(|user| (user.id, user.email))
+        .collect();
+
+    format::json(
+        codes
+            .into_iter()
+            .map(|invite_code| InviteCodeWithUser {
+                used_by_email: invite_code
+                    .used_by_id
+                    .and_then(|id| emails.get(&id).cloned()),
+                invite_code,
+            })
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// Generates single-use codes participants can register with.
+#[debug_handler]
+pub async fn add(
+    admin: AdminJWT,
+    State(ctx): State<AppContext>,
+    JsonValidateWithMessage(params): JsonValidateWithMessage<Params>,
+) -> Result<Response> {
+    let note = params
+        .note
+        .map(|note| note.trim().to_string())
+        .filter(|note| !note.is_empty());
+
+    let txn = ctx.db.begin().await?;
+    let mut codes = Vec::new();
+    for _ in 0..params.count.unwrap_or(1) {
+        let code = ActiveModel::generate(&txn, admin.user.id, note.clone()).await?;
+        Event::new("invite_code.create", "invite_code", Some(code.id))
+            .by(admin.user.id)
+            .after(&code)
+            .record(&txn)
+            .await?;
+        codes.push(code);
+    }
+    txn.commit().await?;
+    format::json(codes)
+}
+
+/// Revokes a code nobody registered with yet.
+#[debug_handler]
+pub async fn remove(
+    admin: AdminJWT,
+    Path(id): Path<i32>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let code = Entity::find_by_id(id)
+        .one(&ctx.db)
+        .await?
+        .ok_or(Error::NotFound)?;
+    if code.used_at.is_some() {
+        return bad_request("invite code was already used");
+    }
+
+    let txn = ctx.db.begin().await?;
+    code.clone().delete(&txn).await?;
+    Event::new("invite_code.revoke", "invite_code", Some(code.id))
+        .by(admin.user.id)
+        .before(&code)
+        .record(&txn)
+        .await?;
+    txn.commit().await?;
+    format::empty()
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("api/admin/invite-codes/")
+        .add("/", get(list))
+        .add("/", post(add))
+        .add("{id}", delete(remove))
+}
@@ -1,8 +1,18 @@
+pub mod admin_competition;
+pub mod admin_users;
+pub mod audit_events;
 pub mod auth;
+pub mod calibration;
+pub mod competitions;
+pub mod conflicts;
+pub mod criteria;
+pub mod extensions;
+pub mod invite_codes;
 
 pub mod config;
 
 pub mod scores;
 pub mod submissions;
+pub mod teams;
 pub mod vote_assignments;
 pub mod votes;
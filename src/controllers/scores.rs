@@ -1,25 +1,28 @@
 #![allow(clippy::missing_errors_doc)]
 #![allow(clippy::unnecessary_struct_initialization)]
 #![allow(clippy::unused_async)]
+use std::collections::BTreeMap;
+
 use loco_rs::prelude::*;
+use sea_orm::QueryOrder;
 use serde::{Deserialize, Serialize};
 
-use crate::models::{
-    _entities::{
-        scores::{ActiveModel, Entity, Model},
-        submissions, users,
+use crate::{
+    controllers::competitions::{scoped, CompetitionItemPath, CompetitionPath},
+    extractors::AdminJWT,
+    models::{
+        _entities::{
+            scores::{self, ActiveModel, Entity, Model},
+            submissions, users,
+        },
+        audit_events::Event,
+        criterion_scores, teams,
     },
-    configs,
 };
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Params {
     pub submission_id: i32,
-    pub problem_fit_score: i32,
-    pub visual_clarity_score: i32,
-    pub style_interpretation_score: i32,
-    pub originality_score: i32,
-    pub overall_quality_score: i32,
     pub final_score: i32,
 }
 
@@ -28,119 +31,195 @@ pub struct Params {
 pub struct ScoreWithUser {
     pub id: i32,
     pub submission_id: i32,
-    pub problem_fit_score: i32,
-    pub visual_clarity_score: i32,
-    pub style_interpretation_score: i32,
-    pub originality_score: i32,
-    pub overall_quality_score: i32,
+    /// Per-criterion scores (0-1000) keyed by criterion key
+    pub criteria: BTreeMap<String, i32>,
     pub final_score: i32,
+    /// The names of all authors, comma separated
     pub user_name: String,
+    /// Set for submissions made by a team
+    pub team_name: Option<String>,
+    /// The names of the authors, every member for a team
+    pub member_names: Vec<String>,
     pub created_at: DateTimeWithTimeZone,
     pub updated_at: DateTimeWithTimeZone,
 }
 
 impl Params {
-    fn update(&self, item: &mut ActiveModel) {
+    fn update(&self, item: &mut ActiveModel, competition_id: i32) {
+        item.competition_id = Set(competition_id);
         item.submission_id = Set(self.submission_id);
-        item.problem_fit_score = Set(self.problem_fit_score);
-        item.visual_clarity_score = Set(self.visual_clarity_score);
-        item.style_interpretation_score = Set(self.style_interpretation_score);
-        item.originality_score = Set(self.originality_score);
-        item.overall_quality_score = Set(self.overall_quality_score);
         item.final_score = Set(self.final_score);
     }
 }
 
-async fn load_item(ctx: &AppContext, id: i32) -> Result<Model> {
-    let item = Entity::find_by_id(id).one(&ctx.db).await?;
+async fn load_item(ctx: &AppContext, competition_id: i32, id: i32) -> Result<Model> {
+    let item = Entity::find_by_id(id)
+        .filter(scores::Column::CompetitionId.eq(competition_id))
+        .one(&ctx.db)
+        .await?;
+    item.ok_or_else(|| Error::NotFound)
+}
+
+/// Scores can only rank submissions of their own competition.
+async fn load_submission(
+    ctx: &AppContext,
+    competition_id: i32,
+    params: &Params,
+) -> Result<submissions::Model> {
+    let item = submissions::Entity::find_by_id(params.submission_id)
+        .filter(submissions::Column::CompetitionId.eq(competition_id))
+        .one(&ctx.db)
+        .await?;
     item.ok_or_else(|| Error::NotFound)
 }
 
 #[debug_handler]
-pub async fn list(State(ctx): State<AppContext>) -> Result<Response> {
-    let config = configs::Entity::find().one(&ctx.db).await?;
-    if let Some(config) = config {
-        if config.show_leaderboard {
-            // Fetch all scores
-            let scores = Entity::find().all(&ctx.db).await?;
-
-            // Build response with user names
-            let mut scores_with_users: Vec<ScoreWithUser> = Vec::new();
-
-            for score in scores {
-                // Get submission for this score
-                let submission = submissions::Entity::find_by_id(score.submission_id)
-                    .one(&ctx.db)
-                    .await?;
-
-                if let Some(submission) = submission {
-                    // Get user for this submission
-                    let user = users::Entity::find_by_id(submission.user_id)
-                        .one(&ctx.db)
-                        .await?;
-
-                    let user_name = user
-                        .map(|u| u.name)
-                        .unwrap_or_else(|| "Unknown".to_string());
-
-                    scores_with_users.push(ScoreWithUser {
-                        id: score.id,
-                        submission_id: score.submission_id,
-                        problem_fit_score: score.problem_fit_score,
-                        visual_clarity_score: score.visual_clarity_score,
-                        style_interpretation_score: score.style_interpretation_score,
-                        originality_score: score.originality_score,
-                        overall_quality_score: score.overall_quality_score,
-                        final_score: score.final_score,
-                        user_name,
-                        created_at: score.created_at,
-                        updated_at: score.updated_at,
-                    });
-                }
+pub async fn list(
+    Path(path): Path<CompetitionPath>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let competition = path.load(&ctx).await?;
+    if !competition.is_published() {
+        return not_found();
+    }
+
+    // Fetch all scores of this competition
+    let scores = Entity::find()
+        .filter(scores::Column::CompetitionId.eq(competition.id))
+        .all(&ctx.db)
+        .await?;
+    let mut criteria = criterion_scores::Entity::find_by_scores(
+        &ctx.db,
+        scores.iter().map(|score| score.id).collect(),
+    )
+    .await?;
+
+    // Build response with user names
+    let mut scores_with_users: Vec<ScoreWithUser> = Vec::new();
+
+    for score in scores {
+        // Get submission for this score
+        let submission = submissions::Entity::find_by_id(score.submission_id)
+            .one(&ctx.db)
+            .await?;
+
+        if let Some(submission) = submission {
+            // Get the users who made this submission
+            let team = match submission.team_id {
+                Some(team_id) => teams::Entity::find_by_id(team_id).one(&ctx.db).await?,
+                None => None,
+            };
+            let mut member_names: Vec<String> = users::Entity::find()
+                .filter(users::Column::Id.is_in(submission.author_ids(&ctx.db).await?))
+                .order_by_asc(users::Column::Id)
+                .all(&ctx.db)
+                .await?
+                .into_iter()
+                .map(|u| u.name)
+                .collect();
+            if member_names.is_empty() {
+                member_names.push("Unknown".to_string());
             }
 
-            return format::json(scores_with_users);
-        } else {
-            return not_found();
+            scores_with_users.push(ScoreWithUser {
+                id: score.id,
+                submission_id: score.submission_id,
+                criteria: criteria.remove(&score.id).unwrap_or_default(),
+                final_score: score.final_score,
+                user_name: member_names.join(", "),
+                team_name: team.map(|team| team.name),
+                member_names,
+                created_at: score.created_at,
+                updated_at: score.updated_at,
+            });
         }
     }
-    not_found()
+
+    format::json(scores_with_users)
 }
 
 #[debug_handler]
-pub async fn add(State(ctx): State<AppContext>, Json(params): Json<Params>) -> Result<Response> {
+pub async fn add(
+    admin: AdminJWT,
+    Path(path): Path<CompetitionPath>,
+    State(ctx): State<AppContext>,
+    Json(params): Json<Params>,
+) -> Result<Response> {
+    let competition = path.load(&ctx).await?;
+    load_submission(&ctx, competition.id, &params).await?;
     let mut item = ActiveModel {
         ..Default::default()
     };
-    params.update(&mut item);
+    params.update(&mut item, competition.id);
     let item = item.insert(&ctx.db).await?;
+    Event::new("score.create", "score", Some(item.id))
+        .by(admin.user.id)
+        .competition(competition.id)
+        .after(&item)
+        .record(&ctx.db)
+        .await?;
     format::json(item)
 }
 
 #[debug_handler]
 pub async fn update(
-    Path(id): Path<i32>,
+    admin: AdminJWT,
+    Path(path): Path<CompetitionItemPath>,
     State(ctx): State<AppContext>,
     Json(params): Json<Params>,
 ) -> Result<Response> {
-    let item = load_item(&ctx, id).await?;
-    let mut item = item.into_active_model();
-    params.update(&mut item);
+    let competition = path.load(&ctx).await?;
+    let before = load_item(&ctx, competition.id, path.id).await?;
+    load_submission(&ctx, competition.id, &params).await?;
+    let mut item = before.clone().into_active_model();
+    params.update(&mut item, competition.id);
     let item = item.update(&ctx.db).await?;
+    Event::new("score.update", "score", Some(item.id))
+        .by(admin.user.id)
+        .competition(competition.id)
+        .before(&before)
+        .after(&item)
+        .record(&ctx.db)
+        .await?;
     format::json(item)
 }
 
 #[debug_handler]
-pub async fn remove(Path(id): Path<i32>, State(ctx): State<AppContext>) -> Result<Response> {
-    load_item(&ctx, id).await?.delete(&ctx.db).await?;
+pub async fn remove(
+    admin: AdminJWT,
+    Path(path): Path<CompetitionItemPath>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let competition = path.load(&ctx).await?;
+    let item = load_item(&ctx, competition.id, path.id).await?;
+    item.clone().delete(&ctx.db).await?;
+    Event::new("score.delete", "score", Some(item.id))
+        .by(admin.user.id)
+        .competition(competition.id)
+        .before(&item)
+        .record(&ctx.db)
+        .await?;
     format::empty()
 }
 
 #[debug_handler]
-pub async fn get_one(Path(id): Path<i32>, State(ctx): State<AppContext>) -> Result<Response> {
-    format::json(load_item(&ctx, id).await?)
+pub async fn get_one(
+    _admin: AdminJWT,
+    Path(path): Path<CompetitionItemPath>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let competition = path.load(&ctx).await?;
+    format::json(load_item(&ctx, competition.id, path.id).await?)
 }
 
 pub fn routes() -> Routes {
-    Routes::new().prefix("api/scores/").add("/", get(list))
+    scoped(
+        "scores",
+        Routes::new()
+            .add("/", get(list))
+            .add("/", post(add))
+            .add("/{id}", get(get_one))
+            .add("/{id}", put(update))
+            .add("/{id}", delete(remove)),
+    )
 }
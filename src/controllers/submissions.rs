@@ -1,38 +1,164 @@
 #![allow(clippy::missing_errors_doc)]
 #![allow(clippy::unnecessary_struct_initialization)]
 #![allow(clippy::unused_async)]
+use std::collections::HashMap;
+
+use axum::{extract::Multipart, http::header};
 use chrono::Local;
-use loco_rs::controller::extractor::auth;
 use loco_rs::prelude::*;
-use sea_orm::Condition;
+use sea_orm::{Condition, QueryOrder, TransactionTrait, TryIntoModel};
 use serde::{Deserialize, Serialize};
+use validator::ValidationError;
 
 use crate::{
+    controllers::competitions::{scoped, CompetitionItemPath, CompetitionPath},
+    extractors::{forbidden, AdminJWT, CurrentUser},
+    figma::FigmaLink,
+    images::{self, Upload},
+    mailers::submissions::SubmissionMailer,
     models::{
-        _entities::vote_assignments,
-        admins, configs,
-        submissions::{self, ActiveModel, Entity, Model},
-        users,
+        _entities::{submissions::Column, users, vote_assignments},
+        audit_events::Event,
+        competitions, deadline_extensions, submission_revisions,
+        submissions::{self, ActiveModel, Entity, Model, Status},
+        teams,
     },
-    tasks::show_leaderboard,
+    validation::{self, FieldErrors},
+    workers::previews::{PreviewWorker, PreviewWorkerArgs},
 };
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, Validate)]
 pub struct Params {
+    #[validate(custom(function = "validate_figma_link"))]
     pub figma_link: String,
+    #[validate(custom(function = "validation::http_url_or_path"))]
     pub design_image: String,
+    #[validate(length(
+        min = 10,
+        max = 2000,
+        message = "must be between 10 and 2000 characters"
+    ))]
     pub target_user_and_goal: String,
+    #[validate(length(
+        min = 10,
+        max = 2000,
+        message = "must be between 10 and 2000 characters"
+    ))]
     pub layout_explanation: String,
+    #[validate(length(
+        min = 10,
+        max = 2000,
+        message = "must be between 10 and 2000 characters"
+    ))]
     pub style_interpretation: String,
+    #[validate(length(
+        min = 10,
+        max = 2000,
+        message = "must be between 10 and 2000 characters"
+    ))]
     pub key_trade_off: String,
+    #[validate(custom(function = "validation::must_be_true"))]
     pub originality_confirmed: bool,
+    #[validate(custom(function = "validation::must_be_true"))]
     pub template_compliance_confirmed: bool,
+    #[validate(length(max = 2000, message = "must be at most 2000 characters"))]
     pub future_improvements: Option<String>,
 }
 
-impl Params {
+fn validate_figma_link(link: &str) -> std::result::Result<(), ValidationError> {
+    FigmaLink::parse(link)
+        .map(|_| ())
+        .map_err(|err| validation::error("figma_link", err.to_string()))
+}
+
+/// A draft being saved: any field may still be missing or empty, but what is given
+/// has to be well-formed.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Validate)]
+pub struct DraftParams {
+    #[validate(custom(function = "validate_draft_figma_link"))]
+    pub figma_link: Option<String>,
+    #[validate(custom(function = "validate_draft_image"))]
+    pub design_image: Option<String>,
+    #[validate(length(max = 2000, message = "must be at most 2000 characters"))]
+    pub target_user_and_goal: Option<String>,
+    #[validate(length(max = 2000, message = "must be at most 2000 characters"))]
+    pub layout_explanation: Option<String>,
+    #[validate(length(max = 2000, message = "must be at most 2000 characters"))]
+    pub style_interpretation: Option<String>,
+    #[validate(length(max = 2000, message = "must be at most 2000 characters"))]
+    pub key_trade_off: Option<String>,
+    pub originality_confirmed: Option<bool>,
+    pub template_compliance_confirmed: Option<bool>,
+    #[validate(length(max = 2000, message = "must be at most 2000 characters"))]
+    pub future_improvements: Option<String>,
+}
+
+fn validate_draft_figma_link(link: &str) -> std::result::Result<(), ValidationError> {
+    if link.trim().is_empty() {
+        return Ok(());
+    }
+    validate_figma_link(link)
+}
+
+fn validate_draft_image(url: &str) -> std::result::Result<(), ValidationError> {
+    if url.is_empty() {
+        return Ok(());
+    }
+    validation::http_url_or_path(url)
+}
+
+impl DraftParams {
+    /// Replaces the draft with these params, missing fields being left empty.
     fn update(&self, item: &mut ActiveModel) {
-        item.figma_link = Set(self.figma_link.clone());
+        let link = self.figma_link.clone().unwrap_or_default();
+        let figma = FigmaLink::parse(&link).ok();
+        item.figma_link = Set(figma.as_ref().map_or(link, |figma| figma.url.clone()));
+        item.figma_file_key = Set(figma.as_ref().map(|figma| figma.file_key.clone()));
+        item.figma_node_id = Set(figma.and_then(|figma| figma.node_id));
+        let design_image = self.design_image.clone().unwrap_or_default();
+        if item.design_image.try_as_ref() != Some(&design_image) {
+            item.gallery_images = Set(None);
+            item.placeholder_image = Set(None);
+        }
+        item.design_image = Set(design_image);
+        item.target_user_and_goal = Set(self.target_user_and_goal.clone().unwrap_or_default());
+        item.layout_explanation = Set(self.layout_explanation.clone().unwrap_or_default());
+        item.style_interpretation = Set(self.style_interpretation.clone().unwrap_or_default());
+        item.key_trade_off = Set(self.key_trade_off.clone().unwrap_or_default());
+        item.originality_confirmed = Set(self.originality_confirmed.unwrap_or_default());
+        item.template_compliance_confirmed =
+            Set(self.template_compliance_confirmed.unwrap_or_default());
+        item.future_improvements = Set(self.future_improvements.clone());
+    }
+}
+
+/// A draft as the params it would be finalised with.
+impl From<&Model> for Params {
+    fn from(item: &Model) -> Self {
+        Self {
+            figma_link: item.figma_link.clone(),
+            design_image: item.design_image.clone(),
+            target_user_and_goal: item.target_user_and_goal.clone(),
+            layout_explanation: item.layout_explanation.clone(),
+            style_interpretation: item.style_interpretation.clone(),
+            key_trade_off: item.key_trade_off.clone(),
+            originality_confirmed: item.originality_confirmed,
+            template_compliance_confirmed: item.template_compliance_confirmed,
+            future_improvements: item.future_improvements.clone(),
+        }
+    }
+}
+
+impl Params {
+    fn update(&self, item: &mut ActiveModel, figma: &FigmaLink) {
+        item.figma_link = Set(figma.url.clone());
+        item.figma_file_key = Set(Some(figma.file_key.clone()));
+        item.figma_node_id = Set(figma.node_id.clone());
+        if item.design_image.try_as_ref() != Some(&self.design_image) {
+            // previews of the replaced image are regenerated by `PreviewWorker`
+            item.gallery_images = Set(None);
+            item.placeholder_image = Set(None);
+        }
         item.design_image = Set(self.design_image.clone());
         item.target_user_and_goal = Set(self.target_user_and_goal.clone());
         item.layout_explanation = Set(self.layout_explanation.clone());
@@ -44,78 +170,452 @@ impl Params {
     }
 }
 
-async fn load_item(ctx: &AppContext, id: i32) -> Result<Model> {
-    let item = Entity::find_by_id(id).one(&ctx.db).await?;
+/// Normalises the Figma link of a submission by `user_id`, which must not point at a
+/// file another participant already submitted.
+async fn check_figma_link(
+    ctx: &AppContext,
+    competition_id: i32,
+    user_id: i32,
+    link: &str,
+) -> Result<FigmaLink> {
+    let link = FigmaLink::parse(link)
+        .map_err(|err| FieldErrors::single("figma_link", "figma_link", err.to_string()))?;
+    let duplicate =
+        submissions::Model::find_same_figma_file(&ctx.db, competition_id, user_id, &link.file_key)
+            .await?;
+    if duplicate.is_some() {
+        return Err(FieldErrors::single(
+            "figma_link",
+            "duplicate",
+            "this Figma file was already submitted by another participant",
+        ));
+    }
+    Ok(link)
+}
+
+/// Generates the previews of the submission's design image unless they are already
+/// there. The submission is saved either way, so failing to do so is only logged.
+async fn generate_previews(ctx: &AppContext, item: &Model) {
+    if item.placeholder_image.is_some() {
+        return;
+    }
+    let args = PreviewWorkerArgs {
+        submission_id: item.id,
+    };
+    if let Err(err) = PreviewWorker::perform_later(ctx, args).await {
+        tracing::error!(submission_id = item.id, error = %err, "could not generate previews");
+    }
+}
+
+/// A submission about to be made by `user_id`, on behalf of their team if they are in
+/// one.
+async fn new_item(ctx: &AppContext, competition_id: i32, user_id: i32) -> Result<ActiveModel> {
+    let team = teams::Model::find_by_member(&ctx.db, competition_id, user_id).await?;
+    Ok(ActiveModel {
+        user_id: Set(user_id),
+        competition_id: Set(competition_id),
+        team_id: Set(team.map(|team| team.id)),
+        ..Default::default()
+    })
+}
+
+/// Everyone who made a submission, see [`Model::author_ids`].
+async fn load_authors(ctx: &AppContext, item: &Model) -> Result<Vec<users::Model>> {
+    Ok(users::Entity::find()
+        .filter(users::Column::Id.is_in(item.author_ids(&ctx.db).await?))
+        .all(&ctx.db)
+        .await?)
+}
+
+async fn load_item(ctx: &AppContext, competition_id: i32, id: i32) -> Result<Model> {
+    let item = Entity::find_by_id(id)
+        .filter(Column::CompetitionId.eq(competition_id))
+        .one(&ctx.db)
+        .await?;
     item.ok_or_else(|| Error::NotFound)
 }
 
-// #[debug_handler]
-// pub async fn list(State(ctx): State<AppContext>) -> Result<Response> {
-//     format::json(Entity::find().all(&ctx.db).await?)
-// }
-///Only accept submissions during submission period
+/// Whether `user_id` may still submit at `now`, honouring the grace period and their
+/// deadline extension.
+async fn accepts_submission(
+    ctx: &AppContext,
+    competition: &competitions::Model,
+    user_id: i32,
+    now: DateTimeWithTimeZone,
+) -> Result<bool> {
+    Ok(competition
+        .accepts_submission_from(&ctx.db, user_id, now)
+        .await?)
+}
+
+/// A submission as listed to admins
+#[derive(Clone, Debug, Serialize)]
+pub struct SubmissionListing {
+    #[serde(flatten)]
+    pub submission: Model,
+    pub user_name: String,
+    /// The deadline extension granted to the submitter, if any
+    pub extended_until: Option<DateTimeWithTimeZone>,
+}
+
+/// Lists every submission of the competition, late ones flagged by `late`.
+#[debug_handler]
+pub async fn list(
+    _admin: AdminJWT,
+    Path(path): Path<CompetitionPath>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let competition = path.load(&ctx).await?;
+    let items = Entity::find()
+        .filter(Column::CompetitionId.eq(competition.id))
+        .order_by_asc(Column::Id)
+        .all(&ctx.db)
+        .await?;
+    let names: HashMap<i32, String> = users::Entity::find()
+        .filter(users::Column::Id.is_in(items.iter().map(|item| item.user_id)))
+        .all(&ctx.db)
+        .await?
+        .into_iter()
+        .map(|user| (user.id, user.name))
+        .collect();
+    let extensions: HashMap<i32, DateTimeWithTimeZone> = deadline_extensions::Entity::find()
+        .filter(deadline_extensions::Column::CompetitionId.eq(competition.id))
+        .all(&ctx.db)
+        .await?
+        .into_iter()
+        .map(|extension| (extension.user_id, extension.extended_until))
+        .collect();
+
+    format::json(
+        items
+            .into_iter()
+            .map(|submission| SubmissionListing {
+                user_name: names
+                    .get(&submission.user_id)
+                    .cloned()
+                    .unwrap_or_else(|| "Unknown".to_string()),
+                extended_until: extensions.get(&submission.user_id).copied(),
+                submission,
+            })
+            .collect::<Vec<_>>(),
+    )
+}
+
+///Only accept submissions during submission period, plus the grace period or the
+///caller's extension, flagging those after the period as late. A draft of the caller
+///is finalised with the given params.
 #[debug_handler]
 pub async fn add(
-    auth: auth::JWT,
+    CurrentUser { user, .. }: CurrentUser,
+    Path(path): Path<CompetitionPath>,
     State(ctx): State<AppContext>,
-    Json(params): Json<Params>,
+    JsonValidateWithMessage(params): JsonValidateWithMessage<Params>,
 ) -> Result<Response> {
-    let user = users::Model::find_by_pid(&ctx.db, &auth.claims.pid).await?;
-    let item = submissions::Model::find_by_userid(&ctx.db, user.id).await?;
-    if item.is_some() {
+    let competition = path.load(&ctx).await?;
+    let existing = submissions::Model::find_by_userid(&ctx.db, competition.id, user.id).await?;
+    if existing.as_ref().is_some_and(|item| !item.is_draft()) {
         return bad_request("submission already exists.");
     }
-    let config = configs::Entity::find().one(&ctx.db).await?;
-    if let Some(config) = config {
-        let now = Local::now();
-        let now = DateTimeWithTimeZone::from(now);
-        if let (Some(ss), Some(se)) = (config.submission_start, config.submission_end) {
-            if ss <= now && now <= se {
-                let mut item = ActiveModel {
-                    ..Default::default()
-                };
-                item.user_id = Set(user.id);
-                params.update(&mut item);
-                let item = item.insert(&ctx.db).await?;
-                return format::json(item);
-            }
-        }
+    let now = DateTimeWithTimeZone::from(Local::now());
+    if accepts_submission(&ctx, &competition, user.id, now).await? {
+        let owner_id = existing.as_ref().map_or(user.id, |item| item.user_id);
+        let figma = check_figma_link(&ctx, competition.id, owner_id, &params.figma_link).await?;
+        let mut item = match existing {
+            Some(draft) => draft.into_active_model(),
+            None => new_item(&ctx, competition.id, user.id).await?,
+        };
+        item.status = Set(Status::Final.as_str().to_string());
+        item.late = Set(competition.is_late(now));
+        params.update(&mut item, &figma);
+        let item = item.save(&ctx.db).await?.try_into_model()?;
+        generate_previews(&ctx, &item).await;
+        return format::json(item);
     }
     bad_request("not submission period yet")
 }
 
+/// Saves the caller's draft, creating it on the first save. While submissions are
+/// open, a draft can be saved as often as needed, incomplete, until it is finalised.
 #[debug_handler]
-pub async fn update(
-    auth: auth::JWT,
-    Path(id): Path<i32>,
+pub async fn save_draft(
+    CurrentUser { user, .. }: CurrentUser,
+    Path(path): Path<CompetitionPath>,
     State(ctx): State<AppContext>,
-    Json(params): Json<Params>,
+    JsonValidateWithMessage(params): JsonValidateWithMessage<DraftParams>,
 ) -> Result<Response> {
-    let user = users::Model::find_by_pid(&ctx.db, &auth.claims.pid).await?;
-    let item = load_item(&ctx, id).await?;
-    let is_admin = admins::Model::is_admin(&ctx.db, user.id).await?;
-    if item.user_id != user.id && !is_admin {
-        return unauthorized("unauthorized access.");
+    let competition = path.load(&ctx).await?;
+    let now = DateTimeWithTimeZone::from(Local::now());
+    if !accepts_submission(&ctx, &competition, user.id, now).await? {
+        return bad_request("drafts can only be saved during the submission period");
     }
-    let mut item = item.into_active_model();
+    let existing = submissions::Model::find_by_userid(&ctx.db, competition.id, user.id).await?;
+    let mut item = match existing {
+        Some(item) if item.is_draft() => item.into_active_model(),
+        Some(_) => return bad_request("submission is already finalised"),
+        None => {
+            let mut item = new_item(&ctx, competition.id, user.id).await?;
+            item.status = Set(Status::Draft.as_str().to_string());
+            item
+        }
+    };
     params.update(&mut item);
+    let item = item.save(&ctx.db).await?.try_into_model()?;
+    generate_previews(&ctx, &item).await;
+    format::json(item)
+}
+
+/// Finalises a draft once all of it is filled in properly, after which it is
+/// reviewed like any other submission.
+#[debug_handler]
+pub async fn finalise(
+    current: CurrentUser,
+    Path(path): Path<CompetitionItemPath>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let competition = path.load(&ctx).await?;
+    let item = load_item(&ctx, competition.id, path.id).await?;
+    if !item.is_author(&ctx.db, current.user.id).await? {
+        return Err(forbidden("only the authors can finalise a submission"));
+    }
+    if !item.is_draft() {
+        return bad_request("submission is already finalised");
+    }
+    let now = DateTimeWithTimeZone::from(Local::now());
+    if !accepts_submission(&ctx, &competition, current.user.id, now).await? {
+        return bad_request("drafts can only be finalised during the submission period");
+    }
+
+    let params = Params::from(&item);
+    Validate::validate(&params).map_err(|errors| Error::Validation(errors.into()))?;
+    let figma = check_figma_link(&ctx, competition.id, item.user_id, &params.figma_link).await?;
+    let mut item = item.into_active_model();
+    item.status = Set(Status::Final.as_str().to_string());
+    item.late = Set(competition.is_late(now));
+    params.update(&mut item, &figma);
     let item = item.update(&ctx.db).await?;
+    generate_previews(&ctx, &item).await;
     format::json(item)
 }
+
+/// Authors, every member of a team, can edit their submission while submissions are
+/// open, including the grace period and their extension, admins at any time. Edits
+/// by authors after the period flag the submission as late.
+/// The version being replaced is kept as a revision.
+#[debug_handler]
+pub async fn update(
+    current: CurrentUser,
+    Path(path): Path<CompetitionItemPath>,
+    State(ctx): State<AppContext>,
+    JsonValidateWithMessage(params): JsonValidateWithMessage<Params>,
+) -> Result<Response> {
+    let competition = path.load(&ctx).await?;
+    let before = load_item(&ctx, competition.id, path.id).await?;
+    let by_author = before.is_author(&ctx.db, current.user.id).await?;
+    let mut late = false;
+    if !current.is_admin(&ctx).await? {
+        if !by_author {
+            return Err(forbidden(
+                "only the authors or an admin can edit a submission",
+            ));
+        }
+        if before.is_removed() {
+            return bad_request("removed submissions cannot be edited");
+        }
+        let now = DateTimeWithTimeZone::from(Local::now());
+        if !accepts_submission(&ctx, &competition, current.user.id, now).await? {
+            return bad_request("submissions can only be edited during the submission period");
+        }
+        late = competition.is_late(now);
+    }
+
+    let figma = check_figma_link(&ctx, competition.id, before.user_id, &params.figma_link).await?;
+
+    let txn = ctx.db.begin().await?;
+    submission_revisions::ActiveModel::snapshot(&txn, &before, current.user.id).await?;
+    let mut item = before.clone().into_active_model();
+    params.update(&mut item, &figma);
+    if late {
+        item.late = Set(true);
+    }
+    let item = item.update(&txn).await?;
+    txn.commit().await?;
+    generate_previews(&ctx, &item).await;
+
+    // authors editing their own work is not an administrative action
+    if !by_author {
+        Event::new("submission.update", "submission", Some(item.id))
+            .by(current.user.id)
+            .competition(competition.id)
+            .before(&before)
+            .after(&item)
+            .record(&ctx.db)
+            .await?;
+    }
+    format::json(item)
+}
+
+/// Withdraws the caller's submission while submissions are open. It is kept, but no
+/// longer reviewed, and its authors may submit again.
+#[debug_handler]
+pub async fn withdraw(
+    current: CurrentUser,
+    Path(path): Path<CompetitionItemPath>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let competition = path.load(&ctx).await?;
+    let item = load_item(&ctx, competition.id, path.id).await?;
+    if !item.is_author(&ctx.db, current.user.id).await? {
+        return Err(forbidden("only the authors can withdraw a submission"));
+    }
+    if item.is_removed() {
+        return bad_request("submission was already removed");
+    }
+    let now = DateTimeWithTimeZone::from(Local::now());
+    if !accepts_submission(&ctx, &competition, current.user.id, now).await? {
+        return bad_request("submissions can only be withdrawn during the submission period");
+    }
+
+    let authors = load_authors(&ctx, &item).await?;
+    let txn = ctx.db.begin().await?;
+    let item = ActiveModel::remove(&txn, item, Status::Withdrawn, None).await?;
+    txn.commit().await?;
+    for author in &authors {
+        if let Err(err) = SubmissionMailer::send_withdrawn(&ctx, author, &competition).await {
+            tracing::error!(submission_id = item.id, error = %err, "could not send withdrawal mail");
+        }
+    }
+    format::json(item)
+}
+
+#[derive(Clone, Debug, Deserialize, Validate)]
+pub struct DisqualifyParams {
+    #[validate(length(min = 1, max = 2000, message = "must be between 1 and 2000 characters"))]
+    pub reason: String,
+}
+
+/// Disqualifies a submission, e.g. for plagiarism. Its authors are told the reason.
+#[debug_handler]
+pub async fn disqualify(
+    admin: AdminJWT,
+    Path(path): Path<CompetitionItemPath>,
+    State(ctx): State<AppContext>,
+    JsonValidateWithMessage(params): JsonValidateWithMessage<DisqualifyParams>,
+) -> Result<Response> {
+    let competition = path.load(&ctx).await?;
+    let before = load_item(&ctx, competition.id, path.id).await?;
+    if before.is_removed() {
+        return bad_request("submission was already removed");
+    }
+    let authors = load_authors(&ctx, &before).await?;
+
+    let reason = params.reason.trim().to_string();
+    let txn = ctx.db.begin().await?;
+    let item = ActiveModel::remove(
+        &txn,
+        before.clone(),
+        Status::Disqualified,
+        Some(reason.clone()),
+    )
+    .await?;
+    Event::new("submission.disqualify", "submission", Some(item.id))
+        .by(admin.user.id)
+        .competition(competition.id)
+        .before(&before)
+        .after(&item)
+        .record(&txn)
+        .await?;
+    txn.commit().await?;
+    for author in &authors {
+        if let Err(err) =
+            SubmissionMailer::send_disqualified(&ctx, author, &competition, &reason).await
+        {
+            tracing::error!(submission_id = item.id, error = %err, "could not send disqualification mail");
+        }
+    }
+    format::json(item)
+}
+
+/// The earlier versions of a submission, oldest first.
+#[debug_handler]
+pub async fn revisions(
+    _admin: AdminJWT,
+    Path(path): Path<CompetitionItemPath>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let competition = path.load(&ctx).await?;
+    let item = load_item(&ctx, competition.id, path.id).await?;
+    format::json(submission_revisions::Model::find_by_submission(&ctx.db, item.id).await?)
+}
+
+/// Stores an uploaded design image, sent as the `image` field of a multipart form.
+/// The returned `url` is what a submission's `design_image` should reference.
+#[debug_handler]
+pub async fn upload_image(
+    _current: CurrentUser,
+    State(ctx): State<AppContext>,
+    mut multipart: Multipart,
+) -> Result<Response> {
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|err| Error::BadRequest(err.body_text()))?
+    {
+        if field.name() != Some("image") {
+            continue;
+        }
+        let content_type = field.content_type().unwrap_or_default().to_string();
+        let bytes = field
+            .bytes()
+            .await
+            .map_err(|err| Error::BadRequest(err.body_text()))?;
+        let upload = tokio::task::spawn_blocking(move || Upload::decode(&content_type, bytes))
+            .await
+            .map_err(Error::wrap)?
+            .map_err(|err| Error::BadRequest(err.to_string()))?;
+        return format::json(images::store(&ctx, upload).await?);
+    }
+    bad_request("missing `image` field")
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImagePath {
+    pub image: String,
+    pub file: String,
+}
+
+/// Serves a stored design image or one of its variants.
+#[debug_handler]
+pub async fn get_image(
+    Path(path): Path<ImagePath>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    if !images::is_valid_path(&path.image, &path.file) {
+        return Err(Error::NotFound);
+    }
+    let content: Vec<u8> = ctx
+        .storage
+        .download(&images::path(&path.image, &path.file))
+        .await
+        .map_err(|_| Error::NotFound)?;
+    format::render()
+        .header(header::CONTENT_TYPE, images::content_type(&path.file))
+        .header(header::CACHE_CONTROL, "public, max-age=31536000, immutable")
+        .response()
+        .body(axum::body::Body::from(content))
+        .map_err(Error::wrap)
+}
+
 ///Allow for loading submissions that have been assigned to a particular user.
 #[debug_handler]
 pub async fn get_one(
-    auth: Result<auth::JWT, Error>,
-    Path(id): Path<i32>,
+    current: Result<CurrentUser, Error>,
+    Path(path): Path<CompetitionItemPath>,
     State(ctx): State<AppContext>,
 ) -> Result<Response> {
-    let user = if let Ok(auth) = auth {
-        Some(users::Model::find_by_pid(&ctx.db, &auth.claims.pid).await?)
-    } else {
-        None
-    };
-    let item = load_item(&ctx, id).await?;
-    let (assignment, is_users_submission, is_admin) = if let Some(user) = user {
+    let competition = path.load(&ctx).await?;
+    let item = load_item(&ctx, competition.id, path.id).await?;
+    let (assignment, is_users_submission, is_admin) = if let Ok(current) = current {
+        let user = &current.user;
         (
             vote_assignments::Entity::find()
                 .filter(
@@ -125,29 +625,30 @@ pub async fn get_one(
                 )
                 .one(&ctx.db)
                 .await?,
-            item.user_id == user.id,
-            admins::Model::is_admin(&ctx.db, user.id).await?,
+            item.is_author(&ctx.db, user.id).await?,
+            current.is_admin(&ctx).await?,
         )
     } else {
         (None, false, false)
     };
-    let config = configs::Entity::find().one(&ctx.db).await?;
-    let mut show_leaderboard = false;
-    if let Some(config) = config {
-        show_leaderboard = config.show_leaderboard;
-    }
 
-    if is_admin || is_users_submission || assignment.is_some() || show_leaderboard {
+    // removed submissions are only shown to their author and admins
+    let is_listed = !item.is_removed() && (assignment.is_some() || competition.is_published());
+    if is_admin || is_users_submission || is_listed {
         return format::json(item);
     }
 
-    return unauthorized("unauthorized access.");
+    unauthorized("unauthorized access.")
 }
 
 #[debug_handler]
-pub async fn get_mine(auth: auth::JWT, State(ctx): State<AppContext>) -> Result<Response> {
-    let user = users::Model::find_by_pid(&ctx.db, &auth.claims.pid).await?;
-    let item = submissions::Model::find_by_userid(&ctx.db, user.id).await?;
+pub async fn get_mine(
+    CurrentUser { user, .. }: CurrentUser,
+    Path(path): Path<CompetitionPath>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let competition = path.load(&ctx).await?;
+    let item = submissions::Model::find_by_userid(&ctx.db, competition.id, user.id).await?;
     if let Some(item) = item {
         format::json(item)
     } else {
@@ -156,11 +657,21 @@ pub async fn get_mine(auth: auth::JWT, State(ctx): State<AppContext>) -> Result<
 }
 
 pub fn routes() -> Routes {
-    Routes::new()
-        .prefix("api/submissions/")
-        .add("/", post(add))
-        .add("/mine", get(get_mine))
-        .add("{id}", get(get_one))
-        .add("{id}", put(update))
-        .add("{id}", patch(update))
+    scoped(
+        "submissions",
+        Routes::new()
+            .add("/", get(list))
+            .add("/", post(add))
+            .add("/mine", get(get_mine))
+            .add("/draft", put(save_draft))
+            .add("/image", post(upload_image))
+            .add("/image/{image}/{file}", get(get_image))
+            .add("/{id}", get(get_one))
+            .add("/{id}", put(update))
+            .add("/{id}", patch(update))
+            .add("/{id}/finalise", post(finalise))
+            .add("/{id}/withdraw", post(withdraw))
+            .add("/{id}/disqualify", post(disqualify))
+            .add("/{id}/revisions", get(revisions)),
+    )
 }
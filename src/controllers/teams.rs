@@ -0,0 +1,273 @@
+#![allow(clippy::missing_errors_doc)]
+#![allow(clippy::unnecessary_struct_initialization)]
+#![allow(clippy::unused_async)]
+use chrono::Local;
+use loco_rs::prelude::*;
+use sea_orm::{sea_query::Expr, TransactionTrait};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    controllers::competitions::{scoped, CompetitionItemPath, CompetitionPath},
+    extractors::{forbidden, CurrentUser},
+    mailers::teams::TeamMailer,
+    models::{
+        _entities::{submissions, team_members},
+        competitions,
+        submissions::Status,
+        team_invites,
+        teams::{self, ActiveModel, Entity, Model},
+    },
+};
+
+#[derive(Clone, Debug, Serialize, Deserialize, Validate)]
+pub struct Params {
+    #[validate(length(min = 1, max = 100, message = "must be between 1 and 100 characters"))]
+    pub name: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Validate)]
+pub struct InviteParams {
+    #[validate(email(message = "must be an email address"))]
+    pub email: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct InvitePath {
+    pub competition: Option<String>,
+    pub token: String,
+}
+
+/// A member as shown to the rest of the team
+#[derive(Clone, Debug, Serialize)]
+pub struct Member {
+    pub id: i32,
+    pub name: String,
+    pub email: String,
+}
+
+/// An invite that was not accepted yet, without the token only the invitee gets
+#[derive(Clone, Debug, Serialize)]
+pub struct PendingInvite {
+    pub id: i32,
+    pub email: String,
+    pub created_at: DateTimeWithTimeZone,
+}
+
+impl From<team_invites::Model> for PendingInvite {
+    fn from(invite: team_invites::Model) -> Self {
+        Self {
+            id: invite.id,
+            email: invite.email,
+            created_at: invite.created_at,
+        }
+    }
+}
+
+/// A team along with its members and pending invites
+#[derive(Clone, Debug, Serialize)]
+pub struct TeamWithMembers {
+    #[serde(flatten)]
+    pub team: Model,
+    pub members: Vec<Member>,
+    pub invites: Vec<PendingInvite>,
+}
+
+async fn with_members(ctx: &AppContext, team: Model) -> Result<TeamWithMembers> {
+    let members = team
+        .members(&ctx.db)
+        .await?
+        .into_iter()
+        .map(|user| Member {
+            id: user.id,
+            name: user.name,
+            email: user.email,
+        })
+        .collect();
+    let invites = team_invites::Model::find_pending_by_team(&ctx.db, team.id)
+        .await?
+        .into_iter()
+        .map(PendingInvite::from)
+        .collect();
+    Ok(TeamWithMembers {
+        team,
+        members,
+        invites,
+    })
+}
+
+/// Teams are formed while `user_id` may still submit, like the submissions they
+/// make, honouring the grace period and their deadline extension.
+async fn check_open(
+    ctx: &AppContext,
+    competition: &competitions::Model,
+    user_id: i32,
+) -> Result<()> {
+    let now = DateTimeWithTimeZone::from(Local::now());
+    if competition
+        .accepts_submission_from(&ctx.db, user_id, now)
+        .await?
+    {
+        Ok(())
+    } else {
+        Err(Error::BadRequest(
+            "teams can only be changed during the submission period".to_string(),
+        ))
+    }
+}
+
+async fn join<C: ConnectionTrait>(db: &C, team_id: i32, user_id: i32) -> Result<()> {
+    team_members::ActiveModel {
+        team_id: Set(team_id),
+        user_id: Set(user_id),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+    Ok(())
+}
+
+/// The caller's team in the competition.
+#[debug_handler]
+pub async fn mine(
+    CurrentUser { user, .. }: CurrentUser,
+    Path(path): Path<CompetitionPath>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let competition = path.load(&ctx).await?;
+    let team = Model::find_by_member(&ctx.db, competition.id, user.id)
+        .await?
+        .ok_or(Error::NotFound)?;
+    format::json(with_members(&ctx, team).await?)
+}
+
+/// Creates a team with the caller as its first member. A submission the caller
+/// already made becomes the team's.
+#[debug_handler]
+pub async fn add(
+    CurrentUser { user, .. }: CurrentUser,
+    Path(path): Path<CompetitionPath>,
+    State(ctx): State<AppContext>,
+    JsonValidateWithMessage(params): JsonValidateWithMessage<Params>,
+) -> Result<Response> {
+    let competition = path.load(&ctx).await?;
+    check_open(&ctx, &competition, user.id).await?;
+    if Model::find_by_member(&ctx.db, competition.id, user.id)
+        .await?
+        .is_some()
+    {
+        return bad_request("you are already in a team");
+    }
+
+    let txn = ctx.db.begin().await?;
+    let team = ActiveModel {
+        name: Set(params.name.trim().to_string()),
+        competition_id: Set(competition.id),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await?;
+    join(&txn, team.id, user.id).await?;
+    submissions::Entity::update_many()
+        .col_expr(submissions::Column::TeamId, Expr::value(team.id))
+        .filter(submissions::Column::CompetitionId.eq(competition.id))
+        .filter(submissions::Column::UserId.eq(user.id))
+        .filter(submissions::Column::TeamId.is_null())
+        .filter(submissions::Column::Status.ne(Status::Withdrawn.as_str()))
+        .exec(&txn)
+        .await?;
+    txn.commit().await?;
+    format::json(with_members(&ctx, team).await?)
+}
+
+/// Invites someone to the caller's team by email. They join by accepting the invite
+/// once signed in with that email address.
+#[debug_handler]
+pub async fn invite(
+    CurrentUser { user, .. }: CurrentUser,
+    Path(path): Path<CompetitionItemPath>,
+    State(ctx): State<AppContext>,
+    JsonValidateWithMessage(params): JsonValidateWithMessage<InviteParams>,
+) -> Result<Response> {
+    let competition = path.load(&ctx).await?;
+    let team = Entity::find_by_id(path.id)
+        .filter(teams::Column::CompetitionId.eq(competition.id))
+        .one(&ctx.db)
+        .await?
+        .ok_or(Error::NotFound)?;
+    let members = team.members(&ctx.db).await?;
+    if !members.iter().any(|member| member.id == user.id) {
+        return Err(forbidden("only members can invite to a team"));
+    }
+    check_open(&ctx, &competition, user.id).await?;
+    if members
+        .iter()
+        .any(|member| member.email.eq_ignore_ascii_case(params.email.trim()))
+    {
+        return bad_request("already a member of the team");
+    }
+
+    let invite =
+        team_invites::ActiveModel::invite(&ctx.db, team.id, &params.email, user.id).await?;
+    if let Err(err) = TeamMailer::send_invite(&ctx, &invite, &team, &competition, &user).await {
+        tracing::error!(team_id = team.id, error = %err, "could not send team invite");
+    }
+    format::json(PendingInvite::from(invite))
+}
+
+/// Joins the team of an invite addressed to the caller. Participants who already
+/// submitted on their own have to withdraw first.
+#[debug_handler]
+pub async fn accept(
+    CurrentUser { user, .. }: CurrentUser,
+    Path(path): Path<InvitePath>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let competition = CompetitionPath {
+        competition: path.competition,
+    }
+    .load(&ctx)
+    .await?;
+    let invite = team_invites::Model::find_pending_by_token(&ctx.db, &path.token)
+        .await?
+        .ok_or(Error::NotFound)?;
+    let team = Entity::find_by_id(invite.team_id)
+        .filter(teams::Column::CompetitionId.eq(competition.id))
+        .one(&ctx.db)
+        .await?
+        .ok_or(Error::NotFound)?;
+    if !invite.email.eq_ignore_ascii_case(&user.email) {
+        return Err(forbidden("the invite is for another email address"));
+    }
+    check_open(&ctx, &competition, user.id).await?;
+    if Model::find_by_member(&ctx.db, competition.id, user.id)
+        .await?
+        .is_some()
+    {
+        return bad_request("you are already in a team");
+    }
+    if submissions::Model::find_by_userid(&ctx.db, competition.id, user.id)
+        .await?
+        .is_some()
+    {
+        return bad_request("withdraw your own submission before joining a team");
+    }
+
+    let txn = ctx.db.begin().await?;
+    join(&txn, team.id, user.id).await?;
+    let mut invite = invite.into_active_model();
+    invite.accepted_at = Set(Some(DateTimeWithTimeZone::from(Local::now())));
+    invite.update(&txn).await?;
+    txn.commit().await?;
+    format::json(with_members(&ctx, team).await?)
+}
+
+pub fn routes() -> Routes {
+    scoped(
+        "teams",
+        Routes::new()
+            .add("/", post(add))
+            .add("/mine", get(mine))
+            .add("/invites/{token}/accept", post(accept))
+            .add("/{id}/invites", post(invite)),
+    )
+}
@@ -4,9 +4,17 @@
 use loco_rs::prelude::*;
 use serde::{Deserialize, Serialize};
 
-use crate::models::{
-    _entities::vote_assignments::{self, ActiveModel, Entity, Model},
-    users,
+use crate::{
+    controllers::competitions::{scoped, CompetitionItemPath, CompetitionPath},
+    extractors::{AdminJWT, CurrentUser},
+    models::{
+        _entities::{
+            submissions,
+            vote_assignments::{self, ActiveModel, Entity, Model},
+        },
+        audit_events::Event,
+        users,
+    },
 };
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -16,23 +24,55 @@ pub struct Params {
 }
 
 impl Params {
-    fn update(&self, item: &mut ActiveModel) {
+    fn update(&self, item: &mut ActiveModel, competition_id: i32) {
+        item.competition_id = Set(competition_id);
         item.user_id = Set(self.user_id);
         item.submission_id = Set(self.submission_id);
     }
 }
 
-async fn load_item(ctx: &AppContext, id: i32) -> Result<Model> {
-    let item = Entity::find_by_id(id).one(&ctx.db).await?;
+/// Assignments can only point at submissions of their own competition, and at
+/// existing users.
+async fn load_submission(
+    ctx: &AppContext,
+    competition_id: i32,
+    params: &Params,
+) -> Result<submissions::Model> {
+    if users::Entity::find_by_id(params.user_id)
+        .one(&ctx.db)
+        .await?
+        .is_none()
+    {
+        return not_found();
+    }
+    let item = submissions::Entity::find_by_id(params.submission_id)
+        .filter(submissions::Column::CompetitionId.eq(competition_id))
+        .one(&ctx.db)
+        .await?;
+    item.ok_or_else(|| Error::NotFound)
+}
+
+async fn load_item(ctx: &AppContext, competition_id: i32, id: i32) -> Result<Model> {
+    let item = Entity::find_by_id(id)
+        .filter(vote_assignments::Column::CompetitionId.eq(competition_id))
+        .one(&ctx.db)
+        .await?;
     item.ok_or_else(|| Error::NotFound)
 }
 
 #[debug_handler]
-pub async fn mine(auth: auth::JWT, State(ctx): State<AppContext>) -> Result<Response> {
-    let user = users::Model::find_by_pid(&ctx.db, &auth.claims.pid).await?;
+pub async fn mine(
+    CurrentUser { user, .. }: CurrentUser,
+    Path(path): Path<CompetitionPath>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let Some(competition) = path.try_load(&ctx).await? else {
+        return format::json(Vec::<Model>::new());
+    };
 
     format::json(
         Entity::find()
+            .filter(vote_assignments::Column::CompetitionId.eq(competition.id))
             .filter(vote_assignments::Column::UserId.eq(user.id))
             .all(&ctx.db)
             .await?,
@@ -40,41 +80,87 @@ pub async fn mine(auth: auth::JWT, State(ctx): State<AppContext>) -> Result<Resp
 }
 
 #[debug_handler]
-pub async fn add(State(ctx): State<AppContext>, Json(params): Json<Params>) -> Result<Response> {
+pub async fn add(
+    admin: AdminJWT,
+    Path(path): Path<CompetitionPath>,
+    State(ctx): State<AppContext>,
+    Json(params): Json<Params>,
+) -> Result<Response> {
+    let competition = path.load(&ctx).await?;
+    load_submission(&ctx, competition.id, &params).await?;
     let mut item = ActiveModel {
         ..Default::default()
     };
-    params.update(&mut item);
+    params.update(&mut item, competition.id);
     let item = item.insert(&ctx.db).await?;
+    Event::new("vote_assignment.create", "vote_assignment", Some(item.id))
+        .by(admin.user.id)
+        .competition(competition.id)
+        .after(&item)
+        .record(&ctx.db)
+        .await?;
     format::json(item)
 }
 
 #[debug_handler]
 pub async fn update(
-    Path(id): Path<i32>,
+    admin: AdminJWT,
+    Path(path): Path<CompetitionItemPath>,
     State(ctx): State<AppContext>,
     Json(params): Json<Params>,
 ) -> Result<Response> {
-    let item = load_item(&ctx, id).await?;
-    let mut item = item.into_active_model();
-    params.update(&mut item);
+    let competition = path.load(&ctx).await?;
+    let before = load_item(&ctx, competition.id, path.id).await?;
+    load_submission(&ctx, competition.id, &params).await?;
+    let mut item = before.clone().into_active_model();
+    params.update(&mut item, competition.id);
     let item = item.update(&ctx.db).await?;
+    Event::new("vote_assignment.update", "vote_assignment", Some(item.id))
+        .by(admin.user.id)
+        .competition(competition.id)
+        .before(&before)
+        .after(&item)
+        .record(&ctx.db)
+        .await?;
     format::json(item)
 }
 
 #[debug_handler]
-pub async fn remove(Path(id): Path<i32>, State(ctx): State<AppContext>) -> Result<Response> {
-    load_item(&ctx, id).await?.delete(&ctx.db).await?;
+pub async fn remove(
+    admin: AdminJWT,
+    Path(path): Path<CompetitionItemPath>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let competition = path.load(&ctx).await?;
+    let item = load_item(&ctx, competition.id, path.id).await?;
+    item.clone().delete(&ctx.db).await?;
+    Event::new("vote_assignment.delete", "vote_assignment", Some(item.id))
+        .by(admin.user.id)
+        .competition(competition.id)
+        .before(&item)
+        .record(&ctx.db)
+        .await?;
     format::empty()
 }
 
 #[debug_handler]
-pub async fn get_one(Path(id): Path<i32>, State(ctx): State<AppContext>) -> Result<Response> {
-    format::json(load_item(&ctx, id).await?)
+pub async fn get_one(
+    _admin: AdminJWT,
+    Path(path): Path<CompetitionItemPath>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let competition = path.load(&ctx).await?;
+    format::json(load_item(&ctx, competition.id, path.id).await?)
 }
 
 pub fn routes() -> Routes {
-    Routes::new()
-        .prefix("api/vote_assignments/")
-        .add("/mine", get(mine))
+    scoped(
+        "vote_assignments",
+        Routes::new()
+            .add("/mine", get(mine))
+            .add("/", post(add))
+            .add("/{id}", get(get_one))
+            .add("/{id}", put(update))
+            .add("/{id}", delete(remove)),
+    )
 }
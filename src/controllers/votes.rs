@@ -1,61 +1,129 @@
 #![allow(clippy::missing_errors_doc)]
 #![allow(clippy::unnecessary_struct_initialization)]
 #![allow(clippy::unused_async)]
+use std::collections::{BTreeMap, HashMap};
+
 use chrono::Local;
 use loco_rs::prelude::*;
+use sea_orm::TransactionTrait;
 use serde::{Deserialize, Serialize};
 
-use crate::models::{
-    _entities::vote_assignments,
-    _entities::votes::{self, ActiveModel, Entity, Model},
-    configs, users,
+use crate::{
+    controllers::competitions::{scoped, CompetitionItemPath, CompetitionPath},
+    models::{
+        _entities::submissions,
+        _entities::vote_assignments,
+        _entities::votes::{self, ActiveModel, Entity, Model},
+        criteria, users, vote_scores,
+    },
+    validation::FieldErrors,
 };
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Params {
     pub submission_id: i32,
-    pub problem_fit_score: i32,
-    pub clarity_score: i32,
-    pub style_interpretation_score: i32,
-    pub originality_score: i32,
-    pub overall_quality_score: i32,
+    /// One score per criterion of the competition's rubric, keyed by criterion key
+    pub scores: BTreeMap<String, i32>,
+}
+
+/// A vote along with its per-criterion scores
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VoteWithScores {
+    #[serde(flatten)]
+    pub vote: Model,
+    pub scores: BTreeMap<String, i32>,
 }
 
 impl Params {
-    /// Validates that all score fields are between 0 and 5 (inclusive).
-    fn validate(&self) -> Result<()> {
-        let scores = [
-            ("problem_fit_score", self.problem_fit_score),
-            ("clarity_score", self.clarity_score),
-            (
-                "style_interpretation_score",
-                self.style_interpretation_score,
-            ),
-            ("originality_score", self.originality_score),
-            ("overall_quality_score", self.overall_quality_score),
-        ];
-
-        for (name, value) in scores {
-            if !(0..=5).contains(&value) {
-                return Err(Error::BadRequest(format!(
-                    "{} must be between 0 and 5, got {}",
-                    name, value
-                )));
+    /// Validates that every criterion of `rubric` is scored within its range and
+    /// that no unknown criteria are scored, reporting every failing score as
+    /// `scores.<criterion key>`.
+    fn validate(&self, rubric: &[criteria::Model]) -> Result<()> {
+        let mut errors = FieldErrors::new();
+        for criterion in rubric {
+            let field = format!("scores.{}", criterion.key);
+            let Some(&value) = self.scores.get(&criterion.key) else {
+                errors.add(
+                    field,
+                    "required",
+                    format!("missing score for {}", criterion.key),
+                );
+                continue;
+            };
+            if !criterion.accepts(value) {
+                errors.add_with_params(
+                    field,
+                    "range",
+                    format!(
+                        "must be between {} and {}, got {}",
+                        criterion.min_score, criterion.max_score, value
+                    ),
+                    HashMap::from([
+                        ("min".to_string(), criterion.min_score.into()),
+                        ("max".to_string(), criterion.max_score.into()),
+                        ("value".to_string(), value.into()),
+                    ]),
+                );
             }
         }
 
-        Ok(())
+        for key in self
+            .scores
+            .keys()
+            .filter(|key| !rubric.iter().any(|criterion| &criterion.key == *key))
+        {
+            errors.add(
+                format!("scores.{key}"),
+                "unknown_criterion",
+                format!("unknown criterion {key}"),
+            );
+        }
+
+        errors.into_result()
     }
 
-    fn update(&self, item: &mut ActiveModel, user_id: i32) {
+    fn update(&self, item: &mut ActiveModel, competition_id: i32, user_id: i32) {
+        item.competition_id = Set(competition_id);
         item.user_id = Set(user_id);
         item.submission_id = Set(self.submission_id);
-        item.problem_fit_score = Set(self.problem_fit_score);
-        item.clarity_score = Set(self.clarity_score);
-        item.style_interpretation_score = Set(self.style_interpretation_score);
-        item.originality_score = Set(self.originality_score);
-        item.overall_quality_score = Set(self.overall_quality_score);
     }
+
+    /// Stores the vote along with its per-criterion scores.
+    async fn save(
+        &self,
+        ctx: &AppContext,
+        item: ActiveModel,
+        rubric: &[criteria::Model],
+    ) -> Result<VoteWithScores> {
+        let txn = ctx.db.begin().await?;
+        // a loaded vote has its id unchanged rather than set
+        let vote = if item.id.is_not_set() {
+            item.insert(&txn).await?
+        } else {
+            item.update(&txn).await?
+        };
+        vote.set_scores(&txn, rubric, &self.scores).await?;
+        txn.commit().await?;
+
+        Ok(VoteWithScores {
+            vote,
+            scores: self.scores.clone(),
+        })
+    }
+}
+
+/// Attaches the per-criterion scores to each vote.
+async fn with_scores(ctx: &AppContext, votes: Vec<Model>) -> Result<Vec<VoteWithScores>> {
+    let mut scores =
+        vote_scores::Entity::find_by_votes(&ctx.db, votes.iter().map(|vote| vote.id).collect())
+            .await?;
+    Ok(votes
+        .into_iter()
+        .map(|vote| VoteWithScores {
+            scores: scores.remove(&vote.id).unwrap_or_default(),
+            vote,
+        })
+        .collect())
 }
 
 async fn load_item(ctx: &AppContext, id: i32) -> Result<Model> {
@@ -64,30 +132,55 @@ async fn load_item(ctx: &AppContext, id: i32) -> Result<Model> {
 }
 
 #[debug_handler]
-pub async fn list(State(ctx): State<AppContext>) -> Result<Response> {
-    format::json(Entity::find().all(&ctx.db).await?)
+pub async fn list(
+    Path(path): Path<CompetitionPath>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let competition = path.load(&ctx).await?;
+    let votes = Entity::find()
+        .filter(votes::Column::CompetitionId.eq(competition.id))
+        .all(&ctx.db)
+        .await?;
+    format::json(with_scores(&ctx, votes).await?)
 }
 
 #[debug_handler]
-pub async fn mine(auth: auth::JWT, State(ctx): State<AppContext>) -> Result<Response> {
+pub async fn mine(
+    auth: auth::JWT,
+    Path(path): Path<CompetitionPath>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let Some(competition) = path.try_load(&ctx).await? else {
+        return format::json(Vec::<VoteWithScores>::new());
+    };
     let user = users::Model::find_by_pid(&ctx.db, &auth.claims.pid).await?;
 
-    format::json(
-        Entity::find()
-            .filter(votes::Column::UserId.eq(user.id))
-            .all(&ctx.db)
-            .await?,
-    )
+    let votes = Entity::find()
+        .filter(votes::Column::CompetitionId.eq(competition.id))
+        .filter(votes::Column::UserId.eq(user.id))
+        .all(&ctx.db)
+        .await?;
+    format::json(with_scores(&ctx, votes).await?)
 }
 
 #[debug_handler]
 pub async fn add(
     auth: auth::JWT,
+    Path(path): Path<CompetitionPath>,
     State(ctx): State<AppContext>,
     Json(params): Json<Params>,
 ) -> Result<Response> {
+    let competition = path.load(&ctx).await?;
     let user = users::Model::find_by_pid(&ctx.db, &auth.claims.pid).await?;
-    params.validate()?;
+    let rubric = criteria::Model::for_competition(&ctx.db, competition.id).await?;
+    params.validate(&rubric)?;
+
+    // The submission has to be up for review in the competition being voted in
+    submissions::Entity::find_finalised(competition.id)
+        .filter(submissions::Column::Id.eq(params.submission_id))
+        .one(&ctx.db)
+        .await?
+        .ok_or_else(|| Error::NotFound)?;
 
     // Check if user already voted on this submission
     let existing_vote = Entity::find()
@@ -96,7 +189,12 @@ pub async fn add(
         .one(&ctx.db)
         .await?;
 
-    // Check if user is assigned to vote on this submission (only needed for new votes)
+    // A vote is changed through `update`
+    if existing_vote.is_some() {
+        return bad_request("already voted on this submission");
+    }
+
+    // Check if user is assigned to vote on this submission
     let is_assigned = vote_assignments::Entity::find()
         .filter(vote_assignments::Column::UserId.eq(user.id))
         .filter(vote_assignments::Column::SubmissionId.eq(params.submission_id))
@@ -105,29 +203,16 @@ pub async fn add(
         .is_some();
 
     // Check voting period
-    let config = configs::Entity::find().one(&ctx.db).await?;
-    if let Some(config) = config {
-        let now = Local::now();
-        let now = DateTimeWithTimeZone::from(now);
-        if let (Some(vs), Some(ve)) = (config.voting_start, config.voting_end) {
-            if vs <= now && now <= ve {
-                // Update existing vote or create new one
-                if let Some(existing) = existing_vote {
-                    let mut item = existing.into_active_model();
-                    params.update(&mut item, user.id);
-                    let item = item.update(&ctx.db).await?;
-                    return format::json(item);
-                } else if is_assigned {
-                    let mut item = ActiveModel {
-                        ..Default::default()
-                    };
-                    params.update(&mut item, user.id);
-                    let item = item.insert(&ctx.db).await?;
-                    return format::json(item);
-                } else {
-                    return bad_request("you are not assigned to vote on this submission");
-                }
-            }
+    let now = DateTimeWithTimeZone::from(Local::now());
+    if competition.is_voting_open(now) {
+        if is_assigned {
+            let mut item = ActiveModel {
+                ..Default::default()
+            };
+            params.update(&mut item, competition.id, user.id);
+            return format::json(params.save(&ctx, item, &rubric).await?);
+        } else {
+            return bad_request("you are not assigned to vote on this submission");
         }
     }
     bad_request("voting is not currently open")
@@ -136,31 +221,29 @@ pub async fn add(
 #[debug_handler]
 pub async fn update(
     auth: auth::JWT,
-    Path(id): Path<i32>,
+    Path(path): Path<CompetitionItemPath>,
     State(ctx): State<AppContext>,
     Json(params): Json<Params>,
 ) -> Result<Response> {
+    let competition = path.load(&ctx).await?;
     let user = users::Model::find_by_pid(&ctx.db, &auth.claims.pid).await?;
-    let item = load_item(&ctx, id).await?;
-    // Only allow updating own votes
-    if item.user_id != user.id {
+    let item = load_item(&ctx, path.id).await?;
+    // Only allow updating own votes, on the submission they were cast for
+    if item.user_id != user.id || item.competition_id != competition.id {
         return unauthorized("unauthorized access.");
     }
-    params.validate()?;
+    if item.submission_id != params.submission_id {
+        return bad_request("a vote cannot move to another submission");
+    }
+    let rubric = criteria::Model::for_competition(&ctx.db, competition.id).await?;
+    params.validate(&rubric)?;
 
     // Check voting period
-    let config = configs::Entity::find().one(&ctx.db).await?;
-    if let Some(config) = config {
-        let now = Local::now();
-        let now = DateTimeWithTimeZone::from(now);
-        if let (Some(vs), Some(ve)) = (config.voting_start, config.voting_end) {
-            if vs <= now && now <= ve {
-                let mut item = item.into_active_model();
-                params.update(&mut item, user.id);
-                let item = item.update(&ctx.db).await?;
-                return format::json(item);
-            }
-        }
+    let now = DateTimeWithTimeZone::from(Local::now());
+    if competition.is_voting_open(now) {
+        let mut item = item.into_active_model();
+        params.update(&mut item, competition.id, user.id);
+        return format::json(params.save(&ctx, item, &rubric).await?);
     }
     bad_request("voting is not currently open")
 }
@@ -173,12 +256,16 @@ pub async fn remove(Path(id): Path<i32>, State(ctx): State<AppContext>) -> Resul
 
 #[debug_handler]
 pub async fn get_one(Path(id): Path<i32>, State(ctx): State<AppContext>) -> Result<Response> {
-    format::json(load_item(&ctx, id).await?)
+    let item = load_item(&ctx, id).await?;
+    format::json(with_scores(&ctx, vec![item]).await?.pop())
 }
 
 pub fn routes() -> Routes {
-    Routes::new()
-        .prefix("api/votes/")
-        .add("/", post(add))
-        .add("/mine", get(mine))
+    scoped(
+        "votes",
+        Routes::new()
+            .add("/", post(add))
+            .add("/mine", get(mine))
+            .add("/{id}", put(update)),
+    )
 }
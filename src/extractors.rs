@@ -0,0 +1,94 @@
+//! Axum extractors resolving who is calling a controller.
+//!
+//! [`CurrentUser`] resolves the user behind a valid JWT, [`AdminJWT`] additionally
+//! requires that user to be an admin:
+//!
+//! ```ignore
+//! async fn update(admin: AdminJWT, State(ctx): State<AppContext>) -> Result<Response> {
+//!     format::json(admin.user)
+//! }
+//! ```
+use axum::{
+    extract::{FromRef, FromRequestParts},
+    http::{request::Parts, StatusCode},
+};
+use loco_rs::{
+    auth::jwt::UserClaims,
+    controller::{extractor::auth, ErrorDetail},
+    model::ModelError,
+    prelude::*,
+};
+
+use crate::models::{admins, users};
+
+/// The error for an authenticated user lacking the rights to do something.
+#[must_use]
+pub fn forbidden(description: &str) -> Error {
+    Error::CustomError(
+        StatusCode::FORBIDDEN,
+        ErrorDetail::new("forbidden", description),
+    )
+}
+
+/// The user making the request. Rejects requests without a valid token, or whose
+/// user no longer exists, with 401.
+#[derive(Debug)]
+pub struct CurrentUser {
+    pub claims: UserClaims,
+    pub user: users::Model,
+}
+
+impl CurrentUser {
+    /// Whether the user is an admin.
+    pub async fn is_admin(&self, ctx: &AppContext) -> Result<bool> {
+        Ok(admins::Model::is_admin(&ctx.db, self.user.id).await?)
+    }
+}
+
+impl<S> FromRequestParts<S> for CurrentUser
+where
+    AppContext: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self> {
+        let ctx = AppContext::from_ref(state);
+        let jwt = auth::JWT::from_request_parts(parts, state).await?;
+        let user = users::Model::find_by_pid(&ctx.db, &jwt.claims.pid)
+            .await
+            .map_err(|err| match err {
+                ModelError::EntityNotFound => Error::Unauthorized("not found".to_string()),
+                err => Error::Model(err),
+            })?;
+        Ok(Self {
+            claims: jwt.claims,
+            user,
+        })
+    }
+}
+
+/// An admin making the request. Rejects unauthenticated requests like
+/// [`CurrentUser`], and users who are not an admin with 403.
+#[derive(Debug)]
+pub struct AdminJWT {
+    pub claims: UserClaims,
+    pub user: users::Model,
+}
+
+impl<S> FromRequestParts<S> for AdminJWT
+where
+    AppContext: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self> {
+        let ctx = AppContext::from_ref(state);
+        let CurrentUser { claims, user } = CurrentUser::from_request_parts(parts, state).await?;
+        if !admins::Model::is_admin(&ctx.db, user.id).await? {
+            return Err(forbidden("admin access required"));
+        }
+        Ok(Self { claims, user })
+    }
+}
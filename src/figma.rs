@@ -0,0 +1,113 @@
+//! Parsing of the Figma links participants submit.
+//!
+//! Accepted are links to a Figma file or prototype, e.g.
+//! `https://www.figma.com/design/<file key>/<title>?node-id=12-34&t=<tracking>`. A
+//! link is normalised to `https://www.figma.com/<kind>/<file key>`, keeping only the
+//! query parameters pointing at a node, so the same file always ends up with the same
+//! link no matter where it was copied from.
+use std::fmt;
+
+use serde::Serialize;
+use url::Url;
+
+/// The path segments a link may start with.
+pub const KINDS: [&str; 3] = ["file", "design", "proto"];
+
+/// Query parameters kept when normalising, all others are dropped.
+const KEPT_PARAMS: [&str; 2] = ["node-id", "starting-point-node-id"];
+
+/// A parsed Figma link.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct FigmaLink {
+    /// One of [`KINDS`]
+    pub kind: String,
+    pub file_key: String,
+    /// The node the link points at, as `12:34` like in the Figma API
+    pub node_id: Option<String>,
+    /// The normalised link
+    pub url: String,
+}
+
+/// Why a link is not a Figma link.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InvalidFigmaLink {
+    /// Not a URL at all
+    Malformed,
+    /// A URL, but not on figma.com
+    NotFigma,
+    /// On figma.com, but not pointing at a file or prototype
+    NotAFile,
+}
+
+impl fmt::Display for InvalidFigmaLink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Malformed => "not a valid URL",
+            Self::NotFigma => "not a figma.com link",
+            Self::NotAFile => "not a link to a Figma file or prototype",
+        })
+    }
+}
+
+fn is_file_key(key: &str) -> bool {
+    (22..=128).contains(&key.len()) && key.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+/// Node ids are written `12-34` in links but `12:34` everywhere else.
+fn node_id(value: &str) -> Option<String> {
+    let id = value.replace('-', ":");
+    let (parent, child) = id.split_once(':')?;
+    let numeric = |part: &str| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit());
+    (numeric(parent) && numeric(child)).then_some(id)
+}
+
+impl FigmaLink {
+    /// Parses and normalises a link.
+    ///
+    /// # Errors
+    ///
+    /// When `link` is not a link to a Figma file or prototype
+    pub fn parse(link: &str) -> Result<Self, InvalidFigmaLink> {
+        let url = Url::parse(link.trim()).map_err(|_| InvalidFigmaLink::Malformed)?;
+        if !matches!(url.scheme(), "http" | "https")
+            || !matches!(url.host_str(), Some("figma.com" | "www.figma.com"))
+        {
+            return Err(InvalidFigmaLink::NotFigma);
+        }
+
+        let mut segments = url.path_segments().ok_or(InvalidFigmaLink::NotAFile)?;
+        let kind = segments
+            .next()
+            .filter(|kind| KINDS.contains(kind))
+            .ok_or(InvalidFigmaLink::NotAFile)?;
+        let file_key = segments
+            .next()
+            .filter(|key| is_file_key(key))
+            .ok_or(InvalidFigmaLink::NotAFile)?;
+
+        let mut params = Vec::new();
+        let mut node = None;
+        for (name, value) in url.query_pairs() {
+            if !KEPT_PARAMS.contains(&name.as_ref()) {
+                continue;
+            }
+            let id = node_id(&value).ok_or(InvalidFigmaLink::NotAFile)?;
+            params.push(format!("{name}={}", id.replace(':', "-")));
+            if name == "node-id" {
+                node = Some(id);
+            }
+        }
+
+        let mut normalised = format!("https://www.figma.com/{kind}/{file_key}");
+        if !params.is_empty() {
+            normalised.push('?');
+            normalised.push_str(&params.join("&"));
+        }
+        Ok(Self {
+            kind: kind.to_string(),
+            file_key: file_key.to_string(),
+            node_id: node,
+            url: normalised,
+        })
+    }
+}
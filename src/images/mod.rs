@@ -0,0 +1,255 @@
+//! Design images uploaded alongside submissions.
+//!
+//! An upload has to decode as one of the [`ACCEPTED`] types and be between
+//! [`MIN_SIZE`] and [`MAX_SIZE`] pixels on each side. It is then stored under a freshly
+//! generated id together with a thumbnail, every file of an image living at
+//! `designs/<id>/<file>`. [`url`] turns such a file into the URL clients reference,
+//! e.g. as the `design_image` of a submission. Once a submission references an
+//! upload, [`previews`] of it are generated in the background for the gallery.
+use std::{fmt, io::Cursor, path::PathBuf};
+
+use axum::body::Bytes;
+use image::{imageops::FilterType, DynamicImage, ImageFormat};
+use loco_rs::{app::AppContext, Error, Result};
+use serde::Serialize;
+
+pub mod storage;
+
+/// Content types accepted for uploads, along with the format they must decode as.
+pub const ACCEPTED: [(&str, ImageFormat); 3] = [
+    ("image/png", ImageFormat::Png),
+    ("image/jpeg", ImageFormat::Jpeg),
+    ("image/webp", ImageFormat::WebP),
+];
+
+/// The smallest width and height an upload may have, in pixels.
+pub const MIN_SIZE: u32 = 320;
+/// The largest width and height an upload may have, in pixels.
+pub const MAX_SIZE: u32 = 8192;
+/// The width thumbnails are scaled down to, in pixels.
+pub const THUMBNAIL_WIDTH: u32 = 480;
+
+/// The widths gallery thumbnails are generated at, in pixels.
+pub const GALLERY_WIDTHS: [u32; 3] = [320, 640, 1280];
+/// The width of the blurred placeholder shown while a gallery thumbnail loads.
+pub const PLACEHOLDER_WIDTH: u32 = 32;
+
+/// File names of the stored variants of an image.
+pub const ORIGINAL: &str = "original";
+pub const THUMBNAIL: &str = "thumbnail.jpg";
+pub const PLACEHOLDER: &str = "placeholder.jpg";
+
+/// The file name of the gallery thumbnail of the given width.
+#[must_use]
+pub fn gallery_file(width: u32) -> String {
+    format!("gallery-{width}.jpg")
+}
+
+/// Why an upload was refused.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvalidImage {
+    /// The content type is not one of [`ACCEPTED`]
+    Unsupported(String),
+    /// The content is not an image of its declared type
+    Undecodable,
+    /// The image is smaller than [`MIN_SIZE`] or larger than [`MAX_SIZE`]
+    Dimensions { width: u32, height: u32 },
+}
+
+impl fmt::Display for InvalidImage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unsupported(content_type) => write!(
+                f,
+                "unsupported content type {content_type:?}, expected one of {}",
+                ACCEPTED.map(|(content_type, _)| content_type).join(", ")
+            ),
+            Self::Undecodable => f.write_str("the file is not a valid image of its type"),
+            Self::Dimensions { width, height } => write!(
+                f,
+                "image is {width}x{height}, both sides must be between {MIN_SIZE} and \
+                 {MAX_SIZE} pixels"
+            ),
+        }
+    }
+}
+
+/// An upload that passed validation.
+pub struct Upload {
+    pub format: ImageFormat,
+    pub image: DynamicImage,
+    pub bytes: Bytes,
+}
+
+impl Upload {
+    /// Decodes `bytes` as an image of `content_type` and checks its dimensions.
+    ///
+    /// # Errors
+    ///
+    /// When the upload is not an acceptable image
+    pub fn decode(content_type: &str, bytes: Bytes) -> Result<Self, InvalidImage> {
+        let format = ACCEPTED
+            .iter()
+            .find(|(accepted, _)| *accepted == content_type)
+            .map(|(_, format)| *format)
+            .ok_or_else(|| InvalidImage::Unsupported(content_type.to_string()))?;
+        let image = image::load_from_memory_with_format(&bytes, format)
+            .map_err(|_| InvalidImage::Undecodable)?;
+        let (width, height) = (image.width(), image.height());
+        let allowed = MIN_SIZE..=MAX_SIZE;
+        if !allowed.contains(&width) || !allowed.contains(&height) {
+            return Err(InvalidImage::Dimensions { width, height });
+        }
+        Ok(Self {
+            format,
+            image,
+            bytes,
+        })
+    }
+
+    /// The file name the upload is stored under.
+    #[must_use]
+    pub fn file_name(&self) -> String {
+        let extension = self.format.extensions_str().first().unwrap_or(&"img");
+        format!("{ORIGINAL}.{extension}")
+    }
+}
+
+/// The scaled down version of `image` shown in previews, as JPEG.
+///
+/// # Errors
+///
+/// When the image cannot be encoded
+pub fn thumbnail(image: &DynamicImage) -> Result<Bytes> {
+    let width = image.width().min(THUMBNAIL_WIDTH);
+    let thumbnail = image.resize(width, u32::MAX, FilterType::Triangle);
+    encode_jpeg(&thumbnail)
+}
+
+/// The gallery thumbnails and placeholder of an image.
+pub struct Previews {
+    /// Thumbnails by width, none of them wider than the image itself
+    pub gallery: Vec<(u32, Bytes)>,
+    pub placeholder: Bytes,
+}
+
+/// Generates the [`Previews`] of a stored image.
+///
+/// # Errors
+///
+/// When the image cannot be decoded or its previews encoded
+pub fn previews(bytes: &[u8]) -> Result<Previews> {
+    let image = image::load_from_memory(bytes).map_err(Error::wrap)?;
+    let mut gallery = Vec::new();
+    for width in GALLERY_WIDTHS {
+        let width = width.min(image.width());
+        if gallery.last().is_some_and(|(last, _)| *last == width) {
+            break;
+        }
+        let thumbnail = image.resize(width, u32::MAX, FilterType::Triangle);
+        gallery.push((width, encode_jpeg(&thumbnail)?));
+    }
+    let placeholder = image
+        .resize(PLACEHOLDER_WIDTH, u32::MAX, FilterType::Triangle)
+        .blur(2.0);
+    Ok(Previews {
+        gallery,
+        placeholder: encode_jpeg(&placeholder)?,
+    })
+}
+
+fn encode_jpeg(image: &DynamicImage) -> Result<Bytes> {
+    let mut out = Cursor::new(Vec::new());
+    DynamicImage::ImageRgb8(image.to_rgb8())
+        .write_to(&mut out, ImageFormat::Jpeg)
+        .map_err(Error::wrap)?;
+    Ok(out.into_inner().into())
+}
+
+/// Where a file of an image is kept in storage.
+#[must_use]
+pub fn path(id: &str, file: &str) -> PathBuf {
+    PathBuf::from("designs").join(id).join(file)
+}
+
+/// Whether `id` and `file` name a file [`store`] may have written, so they can be
+/// safely joined into a storage path.
+#[must_use]
+pub fn is_valid_path(id: &str, file: &str) -> bool {
+    uuid::Uuid::try_parse(id).is_ok()
+        && !file.is_empty()
+        && file
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+        && !file.starts_with('.')
+}
+
+fn base_url(ctx: &AppContext) -> String {
+    let base = storage::Settings::from_context(ctx)
+        .ok()
+        .and_then(|settings| settings.public_url)
+        .unwrap_or_else(|| "/api/submissions/image".to_string());
+    base.trim_end_matches('/').to_string()
+}
+
+/// The URL a file of an image is served from.
+#[must_use]
+pub fn url(ctx: &AppContext, id: &str, file: &str) -> String {
+    format!("{}/{id}/{file}", base_url(ctx))
+}
+
+/// The image id and file a URL built by [`url`] points to, `None` for URLs of
+/// images stored elsewhere.
+#[must_use]
+pub fn parse_url(ctx: &AppContext, url: &str) -> Option<(String, String)> {
+    let (id, file) = url
+        .strip_prefix(&base_url(ctx))?
+        .strip_prefix('/')?
+        .split_once('/')?;
+    is_valid_path(id, file).then(|| (id.to_string(), file.to_string()))
+}
+
+/// A stored image, as returned to the uploader.
+#[derive(Debug, Serialize)]
+pub struct StoredImage {
+    pub id: String,
+    pub url: String,
+    pub thumbnail_url: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Stores a validated upload along with its thumbnail.
+///
+/// # Errors
+///
+/// When the thumbnail cannot be generated or storing fails
+pub async fn store(ctx: &AppContext, upload: Upload) -> Result<StoredImage> {
+    let id = uuid::Uuid::new_v4().to_string();
+    let file = upload.file_name();
+    let (width, height) = (upload.image.width(), upload.image.height());
+
+    let image = upload.image;
+    let thumbnail = tokio::task::spawn_blocking(move || thumbnail(&image))
+        .await
+        .map_err(Error::wrap)??;
+
+    ctx.storage.upload(&path(&id, &file), &upload.bytes).await?;
+    ctx.storage
+        .upload(&path(&id, THUMBNAIL), &thumbnail)
+        .await?;
+
+    Ok(StoredImage {
+        url: url(ctx, &id, &file),
+        thumbnail_url: url(ctx, &id, THUMBNAIL),
+        id,
+        width,
+        height,
+    })
+}
+
+/// The content type a stored file is served with.
+#[must_use]
+pub fn content_type(file: &str) -> &'static str {
+    ImageFormat::from_path(file).map_or("application/octet-stream", |format| format.to_mime_type())
+}
@@ -0,0 +1,142 @@
+//! Where uploaded images are kept, configured under `settings.storage`:
+//!
+//! ```yaml
+//! settings:
+//!   storage:
+//!     driver: local
+//!     path: uploads
+//! ```
+//!
+//! or, for an S3-compatible bucket:
+//!
+//! ```yaml
+//! settings:
+//!   storage:
+//!     driver: s3
+//!     bucket: designs
+//!     region: auto
+//!     endpoint: https://<account>.r2.cloudflarestorage.com
+//!     access_key_id: ...
+//!     secret_access_key: ...
+//!     public_url: https://designs.example.com/designs
+//! ```
+//!
+//! Without `public_url`, images are served by the app itself.
+use std::path::PathBuf;
+
+use loco_rs::{
+    app::AppContext,
+    config::Config,
+    storage::{
+        drivers::{aws, local, StoreDriver},
+        Storage,
+    },
+    Error, Result,
+};
+use serde::Deserialize;
+
+/// The storage backend.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "driver", rename_all = "lowercase")]
+pub enum Driver {
+    /// A directory on the local disk
+    Local { path: PathBuf },
+    /// An S3-compatible bucket, credentials are taken from the environment when not
+    /// given
+    S3 {
+        bucket: String,
+        region: String,
+        endpoint: Option<String>,
+        access_key_id: Option<String>,
+        secret_access_key: Option<String>,
+    },
+}
+
+/// The `settings.storage` section of the configuration.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Settings {
+    #[serde(flatten)]
+    pub driver: Driver,
+    /// The URL stored files are publicly reachable under, if not served by the app
+    pub public_url: Option<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            driver: Driver::Local {
+                path: PathBuf::from("uploads"),
+            },
+            public_url: None,
+        }
+    }
+}
+
+impl Settings {
+    /// Reads the storage settings, defaulting to an `uploads` directory.
+    ///
+    /// # Errors
+    ///
+    /// When the settings are malformed
+    pub fn from_config(config: &Config) -> Result<Self> {
+        match config
+            .settings
+            .as_ref()
+            .and_then(|settings| settings.get("storage"))
+        {
+            Some(storage) => Ok(serde_json::from_value(storage.clone())?),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// See [`Settings::from_config`].
+    ///
+    /// # Errors
+    ///
+    /// When the settings are malformed
+    pub fn from_context(ctx: &AppContext) -> Result<Self> {
+        Self::from_config(&ctx.config)
+    }
+
+    /// Builds the storage driver.
+    ///
+    /// # Errors
+    ///
+    /// When the driver cannot be initialized
+    pub fn driver(&self) -> Result<Box<dyn StoreDriver>> {
+        Ok(match &self.driver {
+            Driver::Local { path } => {
+                std::fs::create_dir_all(path).map_err(Error::wrap)?;
+                local::new_with_prefix(path)?
+            }
+            Driver::S3 {
+                bucket,
+                region,
+                endpoint,
+                access_key_id,
+                secret_access_key,
+            } => match (access_key_id, secret_access_key) {
+                (Some(key_id), Some(secret_key)) => aws::with_credentials_and_endpoint(
+                    bucket,
+                    region,
+                    endpoint.as_deref().unwrap_or("https://s3.amazonaws.com"),
+                    aws::Credential {
+                        key_id: key_id.clone(),
+                        secret_key: secret_key.clone(),
+                        token: None,
+                    },
+                )?,
+                _ => aws::new(bucket, region)?,
+            },
+        })
+    }
+
+    /// The storage images are kept in.
+    ///
+    /// # Errors
+    ///
+    /// When the driver cannot be initialized
+    pub fn storage(&self) -> Result<Storage> {
+        Ok(Storage::single(self.driver()?))
+    }
+}
@@ -0,0 +1,25 @@
+//! Installs the [`RateLimiter`] routes opt into with [`crate::rate_limit::limit`].
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use axum::{Extension, Router as AxumRouter};
+use loco_rs::{
+    app::{AppContext, Initializer},
+    Result,
+};
+
+use crate::rate_limit::RateLimiter;
+
+pub struct RateLimitInitializer;
+
+#[async_trait]
+impl Initializer for RateLimitInitializer {
+    fn name(&self) -> String {
+        "rate-limit".to_string()
+    }
+
+    async fn after_routes(&self, router: AxumRouter, ctx: &AppContext) -> Result<AxumRouter> {
+        let limiter = RateLimiter::from_context(ctx)?;
+        Ok(router.layer(Extension(Arc::new(limiter))))
+    }
+}
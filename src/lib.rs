@@ -1,9 +1,17 @@
+pub mod aggregators;
 pub mod app;
+pub mod assignment;
 pub mod controllers;
 pub mod data;
+pub mod extractors;
+pub mod figma;
+pub mod images;
 pub mod initializers;
+pub mod login;
 pub mod mailers;
 pub mod models;
+pub mod rate_limit;
 pub mod tasks;
+pub mod validation;
 pub mod views;
 pub mod workers;
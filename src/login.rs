@@ -0,0 +1,100 @@
+//! Signing in with one-time passwords mailed to the user, configured under
+//! `settings.login`:
+//!
+//! ```yaml
+//! settings:
+//!   login:
+//!     # How long a mailed OTP can be used
+//!     otp_expiration_minutes: 10
+//!     # Failed attempts after which the email is locked out and its OTP invalidated
+//!     otp_max_attempts: 5
+//!     # How long a locked out email stays locked
+//!     lockout_minutes: 15
+//!     # Domains whose emails can register without an invite code
+//!     allowed_email_domains: []
+//!     # How long a session lasts without being refreshed
+//!     refresh_token_days: 30
+//! ```
+//!
+//! Every setting is optional, defaulting to the values above.
+use axum::http::StatusCode;
+use chrono::TimeDelta;
+use loco_rs::{config::Config, controller::ErrorDetail, Error, Result};
+use serde::Deserialize;
+
+/// The `settings.login` section of the configuration.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub otp_expiration_minutes: u32,
+    pub otp_max_attempts: u32,
+    pub lockout_minutes: u32,
+    /// Domains whose emails can register without an invite code, e.g. `example.com`.
+    /// Subdomains are not included.
+    pub allowed_email_domains: Vec<String>,
+    pub refresh_token_days: u32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            otp_expiration_minutes: 10,
+            otp_max_attempts: 5,
+            lockout_minutes: 15,
+            allowed_email_domains: Vec::new(),
+            refresh_token_days: 30,
+        }
+    }
+}
+
+impl Settings {
+    /// Reads the login settings, defaulting every missing one.
+    ///
+    /// # Errors
+    ///
+    /// When the settings are malformed
+    pub fn from_config(config: &Config) -> Result<Self> {
+        match config
+            .settings
+            .as_ref()
+            .and_then(|settings| settings.get("login"))
+        {
+            Some(login) => Ok(serde_json::from_value(login.clone())?),
+            None => Ok(Self::default()),
+        }
+    }
+
+    #[must_use]
+    pub fn otp_expiration(&self) -> TimeDelta {
+        TimeDelta::minutes(self.otp_expiration_minutes.into())
+    }
+
+    #[must_use]
+    pub fn lockout(&self) -> TimeDelta {
+        TimeDelta::minutes(self.lockout_minutes.into())
+    }
+
+    #[must_use]
+    pub fn refresh_token_lifetime(&self) -> TimeDelta {
+        TimeDelta::days(self.refresh_token_days.into())
+    }
+
+    /// Whether `email` can register without an invite code.
+    #[must_use]
+    pub fn allows_email_domain(&self, email: &str) -> bool {
+        email.rsplit_once('@').is_some_and(|(_, domain)| {
+            self.allowed_email_domains
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(domain.trim()))
+        })
+    }
+}
+
+/// The error for a caller that has to wait before trying again.
+#[must_use]
+pub fn too_many_requests(description: &str) -> Error {
+    Error::CustomError(
+        StatusCode::TOO_MANY_REQUESTS,
+        ErrorDetail::new("too_many_requests", description),
+    )
+}
@@ -7,6 +7,7 @@ use serde_json::json;
 use crate::models::users;
 
 static otp: Dir<'_> = include_dir!("src/mailers/auth/otp");
+static magic_link: Dir<'_> = include_dir!("src/mailers/auth/magic_link");
 
 #[allow(clippy::module_name_repetitions)]
 pub struct AuthMailer {}
@@ -19,15 +20,35 @@ impl Mailer for AuthMailer {
     }
 }
 impl AuthMailer {
-    ///Sending OTP
-    pub async fn send_otp(ctx: &AppContext, user: &users::Model) -> Result<()> {
+    ///Sending OTP, `code` being the plaintext of the one stored hashed on `user`
+    pub async fn send_otp(ctx: &AppContext, user: &users::Model, code: &str) -> Result<()> {
         Self::mail_template(
             ctx,
             &otp,
             mailer::Args {
                 to: user.email.to_string(),
                 locals: json!({
-                  "OTP": user.otp,
+                  "OTP": code,
+                }),
+                ..Default::default()
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Sending the link to login with the magic link `token` of `user`
+    pub async fn send_magic_link(ctx: &AppContext, user: &users::Model, token: &str) -> Result<()> {
+        Self::mail_template(
+            ctx,
+            &magic_link,
+            mailer::Args {
+                to: user.email.to_string(),
+                locals: json!({
+                  "token": token,
+                  "domain": ctx.config.server.host,
+                  "minutes": users::MAGIC_LINK_EXPIRATION_MIN,
                 }),
                 ..Default::default()
             },
@@ -0,0 +1,7 @@
+<html>
+<body>
+<p>Follow this link to login into Bit By Design</p>
+<p><a href="{{domain}}/login?token={{token}}">Login</a></p>
+<p>It expires in {{minutes}} minutes.</p>
+</body>
+</html>
@@ -0,0 +1 @@
+Your login link
\ No newline at end of file
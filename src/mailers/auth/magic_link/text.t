@@ -0,0 +1,2 @@
+Follow this link to login into Bit By Design, it expires in {{minutes}} minutes:
+{{domain}}/login?token={{token}}
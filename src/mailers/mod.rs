@@ -1 +1,3 @@
 pub mod auth;
+pub mod submissions;
+pub mod teams;
@@ -0,0 +1,70 @@
+// submissions mailer
+#![allow(non_upper_case_globals)]
+
+use loco_rs::{mailer::MailerOpts, prelude::*};
+use serde_json::json;
+
+use crate::models::{competitions, users};
+
+static withdrawn: Dir<'_> = include_dir!("src/mailers/submissions/withdrawn");
+static disqualified: Dir<'_> = include_dir!("src/mailers/submissions/disqualified");
+
+#[allow(clippy::module_name_repetitions)]
+pub struct SubmissionMailer {}
+impl Mailer for SubmissionMailer {
+    fn opts() -> MailerOpts {
+        MailerOpts {
+            from: "comp@siegproject.com".to_string(),
+            ..Default::default()
+        }
+    }
+}
+impl SubmissionMailer {
+    ///Confirming a withdrawal to its author
+    pub async fn send_withdrawn(
+        ctx: &AppContext,
+        user: &users::Model,
+        competition: &competitions::Model,
+    ) -> Result<()> {
+        Self::mail_template(
+            ctx,
+            &withdrawn,
+            mailer::Args {
+                to: user.email.to_string(),
+                locals: json!({
+                  "name": user.name,
+                  "competition": competition.name,
+                }),
+                ..Default::default()
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    ///Telling the author why their submission was disqualified
+    pub async fn send_disqualified(
+        ctx: &AppContext,
+        user: &users::Model,
+        competition: &competitions::Model,
+        reason: &str,
+    ) -> Result<()> {
+        Self::mail_template(
+            ctx,
+            &disqualified,
+            mailer::Args {
+                to: user.email.to_string(),
+                locals: json!({
+                  "name": user.name,
+                  "competition": competition.name,
+                  "reason": reason,
+                }),
+                ..Default::default()
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
+}
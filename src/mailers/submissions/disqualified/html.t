@@ -0,0 +1,7 @@
+<html>
+<body>
+<p>Hi {{name}},</p>
+<p>your submission to {{competition}} was disqualified and will not be ranked.</p>
+<p>Reason: {{reason}}</p>
+</body>
+</html>
@@ -0,0 +1 @@
+Your submission to {{competition}} was disqualified
\ No newline at end of file
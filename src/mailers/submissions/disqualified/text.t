@@ -0,0 +1,5 @@
+Hi {{name}},
+
+your submission to {{competition}} was disqualified and will not be ranked.
+
+Reason: {{reason}}
\ No newline at end of file
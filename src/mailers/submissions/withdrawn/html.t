@@ -0,0 +1,6 @@
+<html>
+<body>
+<p>Hi {{name}},</p>
+<p>your submission to {{competition}} was withdrawn and will not be reviewed.</p>
+</body>
+</html>
@@ -0,0 +1 @@
+Your submission to {{competition}} was withdrawn
\ No newline at end of file
@@ -0,0 +1,3 @@
+Hi {{name}},
+
+your submission to {{competition}} was withdrawn and will not be reviewed.
\ No newline at end of file
@@ -0,0 +1,49 @@
+// teams mailer
+#![allow(non_upper_case_globals)]
+
+use loco_rs::{mailer::MailerOpts, prelude::*};
+use serde_json::json;
+
+use crate::models::{competitions, team_invites, teams, users};
+
+static invite: Dir<'_> = include_dir!("src/mailers/teams/invite");
+
+#[allow(clippy::module_name_repetitions)]
+pub struct TeamMailer {}
+impl Mailer for TeamMailer {
+    fn opts() -> MailerOpts {
+        MailerOpts {
+            from: "comp@siegproject.com".to_string(),
+            ..Default::default()
+        }
+    }
+}
+impl TeamMailer {
+    ///Inviting someone to join a team
+    pub async fn send_invite(
+        ctx: &AppContext,
+        team_invite: &team_invites::Model,
+        team: &teams::Model,
+        competition: &competitions::Model,
+        inviter: &users::Model,
+    ) -> Result<()> {
+        Self::mail_template(
+            ctx,
+            &invite,
+            mailer::Args {
+                to: team_invite.email.to_string(),
+                locals: json!({
+                  "inviter": inviter.name,
+                  "team": team.name,
+                  "competition": competition.name,
+                  "token": team_invite.token,
+                  "domain": ctx.config.server.host,
+                }),
+                ..Default::default()
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,6 @@
+<html>
+<body>
+<p>{{inviter}} invited you to submit to {{competition}} as part of {{team}}.</p>
+<p>Sign in with this email address and <a href="{{domain}}/teams/join/{{token}}">accept the invite</a>.</p>
+</body>
+</html>
@@ -0,0 +1 @@
+Join {{team}} in {{competition}}
\ No newline at end of file
@@ -0,0 +1,3 @@
+{{inviter}} invited you to submit to {{competition}} as part of {{team}}.
+
+Sign in with this email address and accept the invite: {{domain}}/teams/join/{{token}}
\ No newline at end of file
@@ -4,19 +4,21 @@ use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
-#[sea_orm(table_name = "configs")]
+#[sea_orm(table_name = "audit_events")]
 pub struct Model {
     pub created_at: DateTimeWithTimeZone,
     pub updated_at: DateTimeWithTimeZone,
     #[sea_orm(primary_key)]
     pub id: i32,
-    pub submission_start: Option<DateTimeWithTimeZone>,
-    pub submission_end: Option<DateTimeWithTimeZone>,
-    pub voting_start: Option<DateTimeWithTimeZone>,
-    pub voting_end: Option<DateTimeWithTimeZone>,
-    pub show_leaderboard: bool,
-    pub assigned: bool,
-    pub created_scores: bool,
+    pub actor_id: Option<i32>,
+    pub action: String,
+    pub target_type: String,
+    pub target_id: Option<i32>,
+    pub competition_id: Option<i32>,
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    pub before: Option<Json>,
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    pub after: Option<Json>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -3,9 +3,22 @@
 pub mod prelude;
 
 pub mod admins;
-pub mod configs;
+pub mod audit_events;
+pub mod competitions;
+pub mod conflicts;
+pub mod criteria;
+pub mod criterion_scores;
+pub mod deadline_extensions;
+pub mod invite_codes;
+pub mod rate_limit_buckets;
+pub mod refresh_tokens;
 pub mod scores;
+pub mod submission_revisions;
 pub mod submissions;
+pub mod team_invites;
+pub mod team_members;
+pub mod teams;
 pub mod users;
 pub mod vote_assignments;
+pub mod vote_scores;
 pub mod votes;
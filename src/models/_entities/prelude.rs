@@ -1,9 +1,22 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.19
 
 pub use super::admins::Entity as Admins;
-pub use super::configs::Entity as Configs;
+pub use super::audit_events::Entity as AuditEvents;
+pub use super::competitions::Entity as Competitions;
+pub use super::conflicts::Entity as Conflicts;
+pub use super::criteria::Entity as Criteria;
+pub use super::criterion_scores::Entity as CriterionScores;
+pub use super::deadline_extensions::Entity as DeadlineExtensions;
+pub use super::invite_codes::Entity as InviteCodes;
+pub use super::rate_limit_buckets::Entity as RateLimitBuckets;
+pub use super::refresh_tokens::Entity as RefreshTokens;
 pub use super::scores::Entity as Scores;
+pub use super::submission_revisions::Entity as SubmissionRevisions;
 pub use super::submissions::Entity as Submissions;
+pub use super::team_invites::Entity as TeamInvites;
+pub use super::team_members::Entity as TeamMembers;
+pub use super::teams::Entity as Teams;
 pub use super::users::Entity as Users;
 pub use super::vote_assignments::Entity as VoteAssignments;
+pub use super::vote_scores::Entity as VoteScores;
 pub use super::votes::Entity as Votes;
@@ -10,17 +10,23 @@ pub struct Model {
     pub updated_at: DateTimeWithTimeZone,
     #[sea_orm(primary_key)]
     pub id: i32,
-    pub problem_fit_score: i32,
-    pub visual_clarity_score: i32,
-    pub style_interpretation_score: i32,
-    pub originality_score: i32,
-    pub overall_quality_score: i32,
     pub final_score: i32,
     pub submission_id: i32,
+    pub competition_id: i32,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::competitions::Entity",
+        from = "Column::CompetitionId",
+        to = "super::competitions::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Competitions,
+    #[sea_orm(has_many = "super::criterion_scores::Entity")]
+    CriterionScores,
     #[sea_orm(
         belongs_to = "super::submissions::Entity",
         from = "Column::SubmissionId",
@@ -31,6 +37,18 @@ pub enum Relation {
     Submissions,
 }
 
+impl Related<super::competitions::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Competitions.def()
+    }
+}
+
+impl Related<super::criterion_scores::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::CriterionScores.def()
+    }
+}
+
 impl Related<super::submissions::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Submissions.def()
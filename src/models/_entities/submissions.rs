@@ -20,12 +20,42 @@ pub struct Model {
     pub template_compliance_confirmed: bool,
     pub future_improvements: Option<String>,
     pub user_id: i32,
+    pub competition_id: i32,
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    pub gallery_images: Option<Json>,
+    pub placeholder_image: Option<String>,
+    pub figma_file_key: Option<String>,
+    pub figma_node_id: Option<String>,
+    pub status: String,
+    pub removed_at: Option<DateTimeWithTimeZone>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub removal_reason: Option<String>,
+    pub team_id: Option<i32>,
+    pub late: bool,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::competitions::Entity",
+        from = "Column::CompetitionId",
+        to = "super::competitions::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Competitions,
     #[sea_orm(has_many = "super::scores::Entity")]
     Scores,
+    #[sea_orm(has_many = "super::submission_revisions::Entity")]
+    SubmissionRevisions,
+    #[sea_orm(
+        belongs_to = "super::teams::Entity",
+        from = "Column::TeamId",
+        to = "super::teams::Column::Id",
+        on_update = "Cascade",
+        on_delete = "SetNull"
+    )]
+    Teams,
     #[sea_orm(
         belongs_to = "super::users::Entity",
         from = "Column::UserId",
@@ -40,12 +70,30 @@ pub enum Relation {
     Votes,
 }
 
+impl Related<super::competitions::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Competitions.def()
+    }
+}
+
 impl Related<super::scores::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Scores.def()
     }
 }
 
+impl Related<super::submission_revisions::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SubmissionRevisions.def()
+    }
+}
+
+impl Related<super::teams::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Teams.def()
+    }
+}
+
 impl Related<super::users::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Users.def()
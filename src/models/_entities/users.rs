@@ -16,14 +16,26 @@ pub struct Model {
     pub name: String,
     pub otp: Option<String>,
     pub otp_sent_at: Option<DateTimeWithTimeZone>,
+    pub otp_failed_attempts: i32,
+    pub otp_locked_until: Option<DateTimeWithTimeZone>,
+    pub magic_link_token: Option<String>,
+    pub magic_link_expiration: Option<DateTimeWithTimeZone>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
     #[sea_orm(has_many = "super::admins::Entity")]
     Admins,
+    #[sea_orm(has_many = "super::refresh_tokens::Entity")]
+    RefreshTokens,
+    #[sea_orm(has_many = "super::submission_revisions::Entity")]
+    SubmissionRevisions,
     #[sea_orm(has_many = "super::submissions::Entity")]
     Submissions,
+    #[sea_orm(has_many = "super::team_invites::Entity")]
+    TeamInvites,
+    #[sea_orm(has_many = "super::team_members::Entity")]
+    TeamMembers,
     #[sea_orm(has_many = "super::vote_assignments::Entity")]
     VoteAssignments,
     #[sea_orm(has_many = "super::votes::Entity")]
@@ -36,12 +48,36 @@ impl Related<super::admins::Entity> for Entity {
     }
 }
 
+impl Related<super::refresh_tokens::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::RefreshTokens.def()
+    }
+}
+
+impl Related<super::submission_revisions::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SubmissionRevisions.def()
+    }
+}
+
 impl Related<super::submissions::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Submissions.def()
     }
 }
 
+impl Related<super::team_invites::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TeamInvites.def()
+    }
+}
+
+impl Related<super::team_members::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TeamMembers.def()
+    }
+}
+
 impl Related<super::vote_assignments::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::VoteAssignments.def()
@@ -12,10 +12,19 @@ pub struct Model {
     pub id: i32,
     pub user_id: i32,
     pub submission_id: i32,
+    pub competition_id: i32,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::competitions::Entity",
+        from = "Column::CompetitionId",
+        to = "super::competitions::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Competitions,
     #[sea_orm(
         belongs_to = "super::submissions::Entity",
         from = "Column::SubmissionId",
@@ -34,6 +43,12 @@ pub enum Relation {
     Users,
 }
 
+impl Related<super::competitions::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Competitions.def()
+    }
+}
+
 impl Related<super::submissions::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Submissions.def()
@@ -10,17 +10,21 @@ pub struct Model {
     pub updated_at: DateTimeWithTimeZone,
     #[sea_orm(primary_key)]
     pub id: i32,
-    pub problem_fit_score: i32,
-    pub clarity_score: i32,
-    pub style_interpretation_score: i32,
-    pub originality_score: i32,
-    pub overall_quality_score: i32,
     pub user_id: i32,
     pub submission_id: i32,
+    pub competition_id: i32,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::competitions::Entity",
+        from = "Column::CompetitionId",
+        to = "super::competitions::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Competitions,
     #[sea_orm(
         belongs_to = "super::submissions::Entity",
         from = "Column::SubmissionId",
@@ -37,6 +41,14 @@ pub enum Relation {
         on_delete = "Cascade"
     )]
     Users,
+    #[sea_orm(has_many = "super::vote_scores::Entity")]
+    VoteScores,
+}
+
+impl Related<super::competitions::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Competitions.def()
+    }
 }
 
 impl Related<super::submissions::Entity> for Entity {
@@ -50,3 +62,9 @@ impl Related<super::users::Entity> for Entity {
         Relation::Users.def()
     }
 }
+
+impl Related<super::vote_scores::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::VoteScores.def()
+    }
+}
@@ -0,0 +1,147 @@
+use std::sync::Arc;
+
+use loco_rs::model::{ModelError, ModelResult};
+use sea_orm::{entity::prelude::*, QueryOrder, Set};
+use serde::Serialize;
+
+pub use super::_entities::audit_events::{ActiveModel, Column, Entity, Model};
+pub type AuditEvents = Entity;
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+/// Something that changed, to be written to the audit log:
+///
+/// ```ignore
+/// Event::new("competition.update", "competition", Some(competition.id))
+///     .by(admin.user.id)
+///     .competition(competition.id)
+///     .before(&before)
+///     .after(&competition)
+///     .record(&ctx.db)
+///     .await?;
+/// ```
+///
+/// Events without an actor were caused by a task or the scheduler.
+#[derive(Clone, Debug, Default)]
+pub struct Event {
+    pub actor_id: Option<i32>,
+    /// What happened, as `<target type>.<verb>`
+    pub action: String,
+    pub target_type: String,
+    pub target_id: Option<i32>,
+    pub competition_id: Option<i32>,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+    /// Why `before` or `after` could not be serialized, reported by `record`
+    unserializable: Option<Arc<serde_json::Error>>,
+}
+
+impl Event {
+    #[must_use]
+    pub fn new(action: &str, target_type: &str, target_id: Option<i32>) -> Self {
+        Self {
+            action: action.to_string(),
+            target_type: target_type.to_string(),
+            target_id,
+            ..Default::default()
+        }
+    }
+
+    /// The user who did it.
+    #[must_use]
+    pub const fn by(mut self, actor_id: i32) -> Self {
+        self.actor_id = Some(actor_id);
+        self
+    }
+
+    /// The competition it happened in.
+    #[must_use]
+    pub const fn competition(mut self, competition_id: i32) -> Self {
+        self.competition_id = Some(competition_id);
+        self
+    }
+
+    /// The target as it was before.
+    #[must_use]
+    pub fn before(mut self, before: &impl Serialize) -> Self {
+        self.before = self.serialize(before);
+        self
+    }
+
+    /// The target as it is now, or what was done to it.
+    #[must_use]
+    pub fn after(mut self, after: &impl Serialize) -> Self {
+        self.after = self.serialize(after);
+        self
+    }
+
+    fn serialize(&mut self, value: &impl Serialize) -> Option<serde_json::Value> {
+        serde_json::to_value(value)
+            .map_err(|err| self.unserializable = Some(Arc::new(err)))
+            .ok()
+    }
+
+    /// Writes the event to the audit log.
+    ///
+    /// # Errors
+    ///
+    /// When DB query error, or when `before` or `after` could not be serialized
+    pub async fn record<C: ConnectionTrait>(self, db: &C) -> ModelResult<Model> {
+        if let Some(err) = self.unserializable {
+            return Err(ModelError::wrap(err));
+        }
+        Ok(ActiveModel {
+            actor_id: Set(self.actor_id),
+            action: Set(self.action),
+            target_type: Set(self.target_type),
+            target_id: Set(self.target_id),
+            competition_id: Set(self.competition_id),
+            before: Set(self.before),
+            after: Set(self.after),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?)
+    }
+}
+
+// implement your read-oriented logic here
+impl Model {
+    /// The events of a competition with one of the given actions, most recent first.
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_by_competition<C: ConnectionTrait>(
+        db: &C,
+        competition_id: i32,
+        actions: &[String],
+    ) -> ModelResult<Vec<Self>> {
+        Ok(Entity::find()
+            .filter(Column::CompetitionId.eq(competition_id))
+            .filter(Column::Action.is_in(actions))
+            .order_by_desc(Column::Id)
+            .all(db)
+            .await?)
+    }
+}
+
+// implement your write-oriented logic here
+impl ActiveModel {}
+
+// implement your custom finders, selectors oriented logic here
+impl Entity {}
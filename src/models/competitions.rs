@@ -0,0 +1,439 @@
+use std::fmt;
+
+use loco_rs::model::{self, ModelError, ModelResult};
+use sea_orm::{entity::prelude::*, IntoActiveModel, QueryOrder, Set};
+use serde::{Deserialize, Serialize};
+
+use super::{criteria, deadline_extensions};
+
+pub use super::_entities::competitions::{ActiveModel, Column, Entity, Model};
+pub type Competitions = Entity;
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+
+    async fn after_save<C>(model: Model, db: &C, insert: bool) -> std::result::Result<Model, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        // every round starts out judged on the default rubric
+        if insert {
+            criteria::ActiveModel::create_defaults(db, model.id).await?;
+        }
+        Ok(model)
+    }
+}
+
+/// Turns a competition name into a url friendly slug, e.g. `"March Round"` -> `"march-round"`.
+#[must_use]
+pub fn slugify(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Where a competition is in its lifecycle. Phases are passed in this order; the
+/// submission and voting phases follow from the timings, the others from running
+/// the assignment, scoring and publishing. The phase last entered is stored with
+/// the competition, see [`Model::entered`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    Draft,
+    SubmissionsOpen,
+    SubmissionsClosed,
+    Assigned,
+    VotingOpen,
+    VotingClosed,
+    Scored,
+    Published,
+}
+
+impl Phase {
+    /// The phase following this one, if any.
+    #[must_use]
+    pub const fn next(self) -> Option<Self> {
+        match self {
+            Self::Draft => Some(Self::SubmissionsOpen),
+            Self::SubmissionsOpen => Some(Self::SubmissionsClosed),
+            Self::SubmissionsClosed => Some(Self::Assigned),
+            Self::Assigned => Some(Self::VotingOpen),
+            Self::VotingOpen => Some(Self::VotingClosed),
+            Self::VotingClosed => Some(Self::Scored),
+            Self::Scored => Some(Self::Published),
+            Self::Published => None,
+        }
+    }
+
+    /// Whether the phase is entered by running an action rather than by time
+    /// passing. Such phases may be entered again, e.g. to redo the assignment.
+    #[must_use]
+    pub const fn is_action(self) -> bool {
+        matches!(self, Self::Assigned | Self::Scored | Self::Published)
+    }
+
+    /// The name the phase is stored and serialized as.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Draft => "draft",
+            Self::SubmissionsOpen => "submissions_open",
+            Self::SubmissionsClosed => "submissions_closed",
+            Self::Assigned => "assigned",
+            Self::VotingOpen => "voting_open",
+            Self::VotingClosed => "voting_closed",
+            Self::Scored => "scored",
+            Self::Published => "published",
+        }
+    }
+
+    /// Looks up a phase by the name returned from [`Phase::as_str`].
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        std::iter::successors(Some(Self::Draft), |phase| phase.next())
+            .find(|phase| phase.as_str() == name)
+    }
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The submission and voting windows of a competition.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Timings {
+    pub submission_start: Option<DateTimeWithTimeZone>,
+    pub submission_end: Option<DateTimeWithTimeZone>,
+    pub voting_start: Option<DateTimeWithTimeZone>,
+    pub voting_end: Option<DateTimeWithTimeZone>,
+}
+
+impl Timings {
+    /// Checks that the timings which are set are in order: submissions open before
+    /// they close, and voting only starts once submissions closed and before it
+    /// ends.
+    ///
+    /// # Errors
+    ///
+    /// Describes the first pair of timings out of order
+    pub fn validate(&self) -> Result<(), String> {
+        let timings = [
+            ("submission_start", self.submission_start),
+            ("submission_end", self.submission_end),
+            ("voting_start", self.voting_start),
+            ("voting_end", self.voting_end),
+        ];
+        let set: Vec<_> = timings
+            .iter()
+            .filter_map(|&(name, at)| at.map(|at| (name, at)))
+            .collect();
+        for pair in set.windows(2) {
+            let ((before, before_at), (after, after_at)) = (pair[0], pair[1]);
+            if before_at > after_at {
+                return Err(format!("{before} must not be after {after}"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Why a competition cannot move to a phase.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TransitionError {
+    /// The phase does not directly follow the current one
+    OutOfOrder { from: Phase, to: Phase },
+    /// Moving to the phase would leave the timings out of order
+    Timings(String),
+}
+
+impl fmt::Display for TransitionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OutOfOrder { from, to } => {
+                write!(f, "cannot move from {from} to {to}")
+            }
+            Self::Timings(reason) => f.write_str(reason),
+        }
+    }
+}
+
+// implement your read-oriented logic here
+impl Model {
+    /// finds a competition by its slug
+    ///
+    /// # Errors
+    ///
+    /// When could not find the competition or DB query error
+    pub async fn find_by_slug(db: &DatabaseConnection, slug: &str) -> ModelResult<Self> {
+        let competition = Entity::find()
+            .filter(model::query::condition().eq(Column::Slug, slug).build())
+            .one(db)
+            .await?;
+        competition.ok_or_else(|| ModelError::EntityNotFound)
+    }
+
+    /// The current competition is the most recently created one.
+    ///
+    /// # Errors
+    ///
+    /// When no competition exists yet or DB query error
+    pub async fn current(db: &DatabaseConnection) -> ModelResult<Self> {
+        let competition = Entity::find().order_by_desc(Column::Id).one(db).await?;
+        competition.ok_or_else(|| ModelError::EntityNotFound)
+    }
+
+    /// Resolves a competition reference taken from a route or a task argument.
+    /// The reference may be either the numeric id or the slug; `None` resolves to
+    /// the current competition.
+    ///
+    /// # Errors
+    ///
+    /// When the referenced competition does not exist or DB query error
+    pub async fn resolve(db: &DatabaseConnection, reference: Option<&str>) -> ModelResult<Self> {
+        match reference {
+            None => Self::current(db).await,
+            Some(reference) => {
+                if let Ok(id) = reference.parse::<i32>() {
+                    if let Some(competition) = Entity::find_by_id(id).one(db).await? {
+                        return Ok(competition);
+                    }
+                }
+                Self::find_by_slug(db, reference).await
+            }
+        }
+    }
+
+    /// Like [`Model::resolve`], but an absent reference with no competition created
+    /// yet resolves to `None` instead of an error. Used by tasks which should be
+    /// no-ops on an empty database.
+    ///
+    /// # Errors
+    ///
+    /// When an explicit reference does not exist or DB query error
+    pub async fn try_resolve(
+        db: &DatabaseConnection,
+        reference: Option<&str>,
+    ) -> ModelResult<Option<Self>> {
+        match Self::resolve(db, reference).await {
+            Ok(competition) => Ok(Some(competition)),
+            Err(ModelError::EntityNotFound) if reference.is_none() => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Whether `now` falls inside the submission window.
+    #[must_use]
+    pub fn is_submission_open(&self, now: DateTimeWithTimeZone) -> bool {
+        matches!(
+            (self.submission_start, self.submission_end),
+            (Some(ss), Some(se)) if ss <= now && now <= se
+        )
+    }
+
+    /// Until when a participant may still submit: the end of the submission window
+    /// plus the grace period, or their extension if that ends later. `None` while the
+    /// window has no end.
+    #[must_use]
+    pub fn submission_deadline(
+        &self,
+        extension: Option<DateTimeWithTimeZone>,
+    ) -> Option<DateTimeWithTimeZone> {
+        let grace = chrono::TimeDelta::minutes(i64::from(self.grace_period_minutes.max(0)));
+        let deadline = self.submission_end? + grace;
+        Some(extension.map_or(deadline, |extension| extension.max(deadline)))
+    }
+
+    /// Whether a participant with the given extension may submit at `now`: like
+    /// [`Model::is_submission_open`], but honouring the grace period and extension.
+    #[must_use]
+    pub fn accepts_submission(
+        &self,
+        now: DateTimeWithTimeZone,
+        extension: Option<DateTimeWithTimeZone>,
+    ) -> bool {
+        self.submission_start.is_some_and(|ss| ss <= now)
+            && self
+                .submission_deadline(extension)
+                .is_some_and(|deadline| now <= deadline)
+    }
+
+    /// Like [`Model::accepts_submission`], with the extension granted to `user_id`.
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn accepts_submission_from<C: ConnectionTrait>(
+        &self,
+        db: &C,
+        user_id: i32,
+        now: DateTimeWithTimeZone,
+    ) -> ModelResult<bool> {
+        let extension = deadline_extensions::Model::find_for_user(db, self.id, user_id).await?;
+        Ok(self.accepts_submission(now, extension.map(|extension| extension.extended_until)))
+    }
+
+    /// Whether a submission made at `now` is late, i.e. after the submission window
+    /// closed.
+    #[must_use]
+    pub fn is_late(&self, now: DateTimeWithTimeZone) -> bool {
+        self.submission_end.is_some_and(|se| now > se)
+    }
+
+    /// Whether `now` falls inside the voting window.
+    #[must_use]
+    pub fn is_voting_open(&self, now: DateTimeWithTimeZone) -> bool {
+        matches!(
+            (self.voting_start, self.voting_end),
+            (Some(vs), Some(ve)) if vs <= now && now <= ve
+        )
+    }
+
+    #[must_use]
+    pub const fn timings(&self) -> Timings {
+        Timings {
+            submission_start: self.submission_start,
+            submission_end: self.submission_end,
+            voting_start: self.voting_start,
+            voting_end: self.voting_end,
+        }
+    }
+
+    /// The phase the competition was last moved into by [`Model::transition`].
+    /// Unknown names are read as [`Phase::Draft`].
+    #[must_use]
+    pub fn entered(&self) -> Phase {
+        Phase::from_name(&self.entered_phase).unwrap_or(Phase::Draft)
+    }
+
+    /// Whether the leaderboard has been published.
+    #[must_use]
+    pub fn is_published(&self) -> bool {
+        self.entered() == Phase::Published
+    }
+
+    /// The phase the competition is in at `now`: the phase it last entered, moved
+    /// on by the submission or voting window it is in. Voting only counts as
+    /// started once submissions have been assigned.
+    #[must_use]
+    pub fn phase(&self, now: DateTimeWithTimeZone) -> Phase {
+        let passed = |at: Option<DateTimeWithTimeZone>| at.is_some_and(|at| now > at);
+        match self.entered() {
+            Phase::Draft | Phase::SubmissionsOpen | Phase::SubmissionsClosed => {
+                if passed(self.submission_end) {
+                    Phase::SubmissionsClosed
+                } else if self.is_submission_open(now) {
+                    Phase::SubmissionsOpen
+                } else {
+                    Phase::Draft
+                }
+            }
+            Phase::Assigned | Phase::VotingOpen | Phase::VotingClosed => {
+                if passed(self.voting_end) {
+                    Phase::VotingClosed
+                } else if self.is_voting_open(now) {
+                    Phase::VotingOpen
+                } else {
+                    Phase::Assigned
+                }
+            }
+            phase @ (Phase::Scored | Phase::Published) => phase,
+        }
+    }
+
+    /// Moves the competition to phase `to` at `now`, which has to follow the
+    /// current phase. Action phases may also be entered again. Phases that follow
+    /// from the timings are entered by starting or ending their window at `now`,
+    /// which must keep the timings in order.
+    ///
+    /// The returned model still has to be saved.
+    ///
+    /// # Errors
+    ///
+    /// When `to` does not follow the current phase, or the timings would be out of
+    /// order
+    pub fn transition(
+        &self,
+        to: Phase,
+        now: DateTimeWithTimeZone,
+    ) -> Result<ActiveModel, TransitionError> {
+        let from = self.phase(now);
+        if from.next() != Some(to) && !(from == to && to.is_action()) {
+            return Err(TransitionError::OutOfOrder { from, to });
+        }
+
+        let mut timings = self.timings();
+        if !to.is_action() {
+            match to {
+                Phase::SubmissionsOpen => timings.submission_start = Some(now),
+                Phase::SubmissionsClosed => timings.submission_end = Some(now),
+                Phase::VotingOpen => timings.voting_start = Some(now),
+                Phase::VotingClosed => timings.voting_end = Some(now),
+                _ => {}
+            }
+            // Entering a window must leave it open for a while
+            let end = match to {
+                Phase::SubmissionsOpen => Some(("submission_end", timings.submission_end)),
+                Phase::VotingOpen => Some(("voting_end", timings.voting_end)),
+                _ => None,
+            };
+            if let Some((name, end)) = end {
+                if end.is_none_or(|end| end <= now) {
+                    return Err(TransitionError::Timings(format!(
+                        "{name} must be set to a later time to move to {to}"
+                    )));
+                }
+            }
+            timings.validate().map_err(TransitionError::Timings)?;
+        }
+
+        let mut item = self.clone().into_active_model();
+        item.submission_start = Set(timings.submission_start);
+        item.submission_end = Set(timings.submission_end);
+        item.voting_start = Set(timings.voting_start);
+        item.voting_end = Set(timings.voting_end);
+        item.entered_phase = Set(to.as_str().to_string());
+        Ok(item)
+    }
+
+    /// Hides the published leaderboard again, moving the competition back to
+    /// [`Phase::Scored`].
+    ///
+    /// The returned model still has to be saved.
+    ///
+    /// # Errors
+    ///
+    /// When the leaderboard is not published
+    pub fn unpublish(&self) -> Result<ActiveModel, TransitionError> {
+        if !self.is_published() {
+            return Err(TransitionError::OutOfOrder {
+                from: self.entered(),
+                to: Phase::Scored,
+            });
+        }
+        let mut item = self.clone().into_active_model();
+        item.entered_phase = Set(Phase::Scored.as_str().to_string());
+        Ok(item)
+    }
+}
+
+// implement your write-oriented logic here
+impl ActiveModel {}
+
+// implement your custom finders, selectors oriented logic here
+impl Entity {}
@@ -0,0 +1,64 @@
+use std::collections::HashSet;
+
+use loco_rs::model::ModelResult;
+use sea_orm::entity::prelude::*;
+
+pub use super::_entities::conflicts::{ActiveModel, Column, Entity, Model};
+pub type Conflicts = Entity;
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+// implement your read-oriented logic here
+impl Model {
+    /// The conflicts a user declared, oldest first.
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_by_user<C: ConnectionTrait>(db: &C, user_id: i32) -> ModelResult<Vec<Self>> {
+        Ok(Entity::find()
+            .filter(Column::UserId.eq(user_id))
+            .all(db)
+            .await?)
+    }
+}
+
+// implement your write-oriented logic here
+impl ActiveModel {}
+
+// implement your custom finders, selectors oriented logic here
+impl Entity {
+    /// All declared conflicts as `(user_id, other_user_id)` pairs. A conflict goes
+    /// both ways, so each declaration is returned in both directions.
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn pairs<C: ConnectionTrait>(db: &C) -> ModelResult<HashSet<(i32, i32)>> {
+        Ok(Self::find()
+            .all(db)
+            .await?
+            .into_iter()
+            .flat_map(|conflict| {
+                [
+                    (conflict.user_id, conflict.other_user_id),
+                    (conflict.other_user_id, conflict.user_id),
+                ]
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,122 @@
+use loco_rs::model::ModelResult;
+use sea_orm::{entity::prelude::*, QueryOrder, Set};
+
+use crate::aggregators::Scale;
+
+pub use super::_entities::criteria::{ActiveModel, Column, Entity, Model};
+pub type Criteria = Entity;
+
+/// The rubric a new competition starts with: `(key, name, description, weight)`,
+/// each scored from 0 to 5.
+pub const DEFAULT_RUBRIC: [(&str, &str, &str, f32); 5] = [
+    (
+        "problem_fit",
+        "Problem Fit",
+        "How well does the design solve the stated problem?",
+        0.25,
+    ),
+    (
+        "clarity",
+        "Clarity",
+        "How clear and understandable is the design?",
+        0.20,
+    ),
+    (
+        "style_interpretation",
+        "Style Interpretation",
+        "How well does it interpret the design style?",
+        0.20,
+    ),
+    (
+        "originality",
+        "Originality",
+        "How creative and unique is the approach?",
+        0.15,
+    ),
+    (
+        "overall_quality",
+        "Overall Quality",
+        "Your overall impression of the design",
+        0.20,
+    ),
+];
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+// implement your read-oriented logic here
+impl Model {
+    /// The rubric of a competition, in display order.
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn for_competition<C: ConnectionTrait>(
+        db: &C,
+        competition_id: i32,
+    ) -> ModelResult<Vec<Self>> {
+        Ok(Entity::find()
+            .filter(Column::CompetitionId.eq(competition_id))
+            .order_by_asc(Column::Position)
+            .order_by_asc(Column::Id)
+            .all(db)
+            .await?)
+    }
+
+    /// Whether `score` is within the range of this criterion.
+    #[must_use]
+    pub fn accepts(&self, score: i32) -> bool {
+        (self.min_score..=self.max_score).contains(&score)
+    }
+
+    /// The range this criterion is scored in.
+    #[must_use]
+    pub fn scale(&self) -> Scale {
+        Scale::new(self.min_score as f32, self.max_score as f32)
+    }
+}
+
+// implement your write-oriented logic here
+impl ActiveModel {
+    /// Gives a competition the [`DEFAULT_RUBRIC`].
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn create_defaults<C: ConnectionTrait>(
+        db: &C,
+        competition_id: i32,
+    ) -> Result<(), DbErr> {
+        let criteria = DEFAULT_RUBRIC.iter().enumerate().map(
+            |(position, (key, name, description, weight))| Self {
+                competition_id: Set(competition_id),
+                key: Set((*key).to_string()),
+                name: Set((*name).to_string()),
+                description: Set(Some((*description).to_string())),
+                min_score: Set(0),
+                max_score: Set(5),
+                weight: Set(*weight),
+                position: Set(i32::try_from(position).unwrap_or_default()),
+                ..Default::default()
+            },
+        );
+        Entity::insert_many(criteria).exec(db).await?;
+        Ok(())
+    }
+}
+
+// implement your custom finders, selectors oriented logic here
+impl Entity {}
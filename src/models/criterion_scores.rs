@@ -0,0 +1,61 @@
+use std::collections::{BTreeMap, HashMap};
+
+use loco_rs::model::ModelResult;
+use sea_orm::entity::prelude::*;
+
+use super::_entities::criteria;
+pub use super::_entities::criterion_scores::{ActiveModel, Column, Entity, Model};
+pub type CriterionScores = Entity;
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+// implement your read-oriented logic here
+impl Model {}
+
+// implement your write-oriented logic here
+impl ActiveModel {}
+
+// implement your custom finders, selectors oriented logic here
+impl Entity {
+    /// Loads the per-criterion scores of the given scores, keyed by score id and
+    /// criterion key.
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_by_scores<C: ConnectionTrait>(
+        db: &C,
+        score_ids: Vec<i32>,
+    ) -> ModelResult<HashMap<i32, BTreeMap<String, i32>>> {
+        let rows = Self::find()
+            .filter(Column::ScoreId.is_in(score_ids))
+            .find_also_related(criteria::Entity)
+            .all(db)
+            .await?;
+
+        let mut by_score: HashMap<i32, BTreeMap<String, i32>> = HashMap::new();
+        for (row, criterion) in rows {
+            if let Some(criterion) = criterion {
+                by_score
+                    .entry(row.score_id)
+                    .or_default()
+                    .insert(criterion.key, row.score);
+            }
+        }
+        Ok(by_score)
+    }
+}
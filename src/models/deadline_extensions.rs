@@ -0,0 +1,47 @@
+use loco_rs::model::ModelResult;
+use sea_orm::entity::prelude::*;
+
+pub use super::_entities::deadline_extensions::{ActiveModel, Column, Entity, Model};
+pub type DeadlineExtensions = Entity;
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+// implement your read-oriented logic here
+impl Model {
+    /// The extension granted to a user in the given competition.
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_for_user<C: ConnectionTrait>(
+        db: &C,
+        competition_id: i32,
+        user_id: i32,
+    ) -> ModelResult<Option<Self>> {
+        Ok(Entity::find()
+            .filter(Column::CompetitionId.eq(competition_id))
+            .filter(Column::UserId.eq(user_id))
+            .one(db)
+            .await?)
+    }
+}
+
+// implement your write-oriented logic here
+impl ActiveModel {}
+
+// implement your custom finders, selectors oriented logic here
+impl Entity {}
@@ -0,0 +1,93 @@
+use chrono::Utc;
+use loco_rs::{hash, model::ModelResult};
+use sea_orm::{entity::prelude::*, sea_query::Expr, Set};
+
+pub use super::_entities::invite_codes::{ActiveModel, Column, Entity, Model};
+pub type InviteCodes = Entity;
+
+/// The length of generated codes.
+pub const CODE_LENGTH: usize = 10;
+
+/// Codes are handed out to be typed in, so they are compared ignoring case.
+#[must_use]
+pub fn normalise(code: &str) -> String {
+    code.trim().to_uppercase()
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+// implement your read-oriented logic here
+impl Model {
+    /// finds a code nobody registered with yet
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_unused<C: ConnectionTrait>(db: &C, code: &str) -> ModelResult<Option<Self>> {
+        Ok(Entity::find()
+            .filter(Column::Code.eq(normalise(code)))
+            .filter(Column::UsedAt.is_null())
+            .one(db)
+            .await?)
+    }
+
+    /// Marks the code as used by `user_id`, unless it was used in the meantime.
+    /// Returns whether it was.
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn redeem<C: ConnectionTrait>(&self, db: &C, user_id: i32) -> ModelResult<bool> {
+        let result = Entity::update_many()
+            .col_expr(Column::UsedById, Expr::value(user_id))
+            .col_expr(
+                Column::UsedAt,
+                Expr::value(DateTimeWithTimeZone::from(Utc::now())),
+            )
+            .filter(Column::Id.eq(self.id))
+            .filter(Column::UsedAt.is_null())
+            .exec(db)
+            .await?;
+        Ok(result.rows_affected == 1)
+    }
+}
+
+// implement your write-oriented logic here
+impl ActiveModel {
+    /// Generates a single-use code to register with.
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn generate<C: ConnectionTrait>(
+        db: &C,
+        created_by_id: i32,
+        note: Option<String>,
+    ) -> ModelResult<Model> {
+        Ok(Self {
+            code: Set(normalise(&hash::random_string(CODE_LENGTH))),
+            note: Set(note),
+            created_by_id: Set(created_by_id),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?)
+    }
+}
+
+// implement your custom finders, selectors oriented logic here
+impl Entity {}
@@ -0,0 +1,123 @@
+use chrono::{TimeDelta, Utc};
+use loco_rs::{hash, model::ModelResult};
+use sea_orm::{entity::prelude::*, sea_query::Expr, Set};
+use sha2::{Digest, Sha256};
+
+pub use super::_entities::refresh_tokens::{ActiveModel, Column, Entity, Model};
+pub type RefreshTokens = Entity;
+
+/// The length of issued tokens.
+pub const TOKEN_LENGTH: usize = 48;
+
+/// Tokens are long and random, so a fast hash is enough to keep a leaked table from
+/// handing out sessions, while still letting tokens be looked up by their hash.
+pub(crate) fn digest(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+// implement your read-oriented logic here
+impl Model {
+    /// finds the token, whether or not it can still be used
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_by_token<C: ConnectionTrait>(
+        db: &C,
+        token: &str,
+    ) -> ModelResult<Option<Self>> {
+        Ok(Entity::find()
+            .filter(Column::TokenHash.eq(digest(token)))
+            .one(db)
+            .await?)
+    }
+
+    /// Whether the token can be exchanged for a new one at `now`.
+    #[must_use]
+    pub fn is_active(&self, now: DateTimeWithTimeZone) -> bool {
+        self.revoked_at.is_none() && self.expires_at > now
+    }
+
+    /// Revokes the token, unless it was revoked in the meantime. Returns whether it
+    /// was.
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn revoke<C: ConnectionTrait>(&self, db: &C) -> ModelResult<bool> {
+        let result = Entity::update_many()
+            .col_expr(
+                Column::RevokedAt,
+                Expr::value(DateTimeWithTimeZone::from(Utc::now())),
+            )
+            .filter(Column::Id.eq(self.id))
+            .filter(Column::RevokedAt.is_null())
+            .exec(db)
+            .await?;
+        Ok(result.rows_affected == 1)
+    }
+}
+
+// implement your write-oriented logic here
+impl ActiveModel {
+    /// Issues a token for `user_id` usable for `lifetime`, returning it along with
+    /// the plaintext, which is not stored.
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn issue<C: ConnectionTrait>(
+        db: &C,
+        user_id: i32,
+        lifetime: TimeDelta,
+    ) -> ModelResult<(Model, String)> {
+        let token = hash::random_string(TOKEN_LENGTH);
+        let model = Self {
+            token_hash: Set(digest(&token)),
+            expires_at: Set((Utc::now() + lifetime).into()),
+            user_id: Set(user_id),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?;
+        Ok((model, token))
+    }
+}
+
+// implement your custom finders, selectors oriented logic here
+impl Entity {
+    /// Revokes every token of the user still in use, ending all their sessions once
+    /// their access tokens expire. Returns how many were.
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn revoke_all_for_user<C: ConnectionTrait>(db: &C, user_id: i32) -> ModelResult<u64> {
+        let result = Self::update_many()
+            .col_expr(
+                Column::RevokedAt,
+                Expr::value(DateTimeWithTimeZone::from(Utc::now())),
+            )
+            .filter(Column::UserId.eq(user_id))
+            .filter(Column::RevokedAt.is_null())
+            .exec(db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+}
@@ -0,0 +1,74 @@
+use loco_rs::model::{ModelError, ModelResult};
+use sea_orm::{entity::prelude::*, QueryOrder, Set};
+
+pub use super::_entities::submission_revisions::{ActiveModel, Column, Entity, Model};
+use super::submissions;
+pub type SubmissionRevisions = Entity;
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+// implement your read-oriented logic here
+impl Model {
+    /// The earlier versions of a submission, oldest first.
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_by_submission<C: ConnectionTrait>(
+        db: &C,
+        submission_id: i32,
+    ) -> ModelResult<Vec<Self>> {
+        Ok(Entity::find()
+            .filter(Column::SubmissionId.eq(submission_id))
+            .order_by_asc(Column::Revision)
+            .all(db)
+            .await?)
+    }
+}
+
+// implement your write-oriented logic here
+impl ActiveModel {
+    /// Keeps `submission` as it is now, before `editor_id` replaces it. The first
+    /// revision is the submission as it was originally submitted.
+    ///
+    /// # Errors
+    ///
+    /// When the submission cannot be serialized or DB query error
+    pub async fn snapshot<C: ConnectionTrait>(
+        db: &C,
+        submission: &submissions::Model,
+        editor_id: i32,
+    ) -> ModelResult<Model> {
+        let revisions = Entity::find()
+            .filter(Column::SubmissionId.eq(submission.id))
+            .count(db)
+            .await?;
+        let snapshot = serde_json::to_value(submission).map_err(ModelError::wrap)?;
+        Ok(Self {
+            submission_id: Set(submission.id),
+            editor_id: Set(editor_id),
+            revision: Set(i32::try_from(revisions).unwrap_or(i32::MAX - 1) + 1),
+            snapshot: Set(snapshot),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?)
+    }
+}
+
+// implement your custom finders, selectors oriented logic here
+impl Entity {}
@@ -1,16 +1,50 @@
-use crate::models::_entities::submissions::Column;
+use crate::models::{
+    _entities::{scores, submissions::Column, team_members, vote_assignments},
+    teams,
+};
 use loco_rs::model::{self, ModelResult};
-use sea_orm::entity::prelude::*;
+use sea_orm::{entity::prelude::*, Condition, IntoActiveModel, Set};
+use serde::{Deserialize, Serialize};
 
 pub use super::_entities::submissions::{ActiveModel, Entity, Model};
 pub type Submissions = Entity;
 
+/// Where a submission is at, stored in `status`. Drafts can be saved incomplete and
+/// are only reviewed once finalised. Withdrawn and disqualified submissions are kept
+/// along with their votes, but no longer reviewed or ranked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Draft,
+    Final,
+    /// Pulled out by its author
+    Withdrawn,
+    /// Pulled out by an admin, see `removal_reason`
+    Disqualified,
+}
+
+impl Status {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Draft => "draft",
+            Self::Final => "final",
+            Self::Withdrawn => "withdrawn",
+            Self::Disqualified => "disqualified",
+        }
+    }
+}
+
 #[async_trait::async_trait]
 impl ActiveModelBehavior for ActiveModel {
     async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
     where
         C: ConnectionTrait,
     {
+        // SQLite keeps the column nullable, see m20261017_102000
+        if insert && self.competition_id.is_not_set() {
+            return Err(DbErr::Custom("competition_id must be set".to_string()));
+        }
         if !insert && self.updated_at.is_unchanged() {
             let mut this = self;
             this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
@@ -23,14 +57,83 @@ impl ActiveModelBehavior for ActiveModel {
 
 // implement your read-oriented logic here
 impl Model {
+    #[must_use]
+    pub fn is_draft(&self) -> bool {
+        self.status == Status::Draft.as_str()
+    }
+
+    /// whether the submission was withdrawn or disqualified
+    #[must_use]
+    pub fn is_removed(&self) -> bool {
+        self.removed_at.is_some()
+    }
+
+    /// finds the submission a user made in the given competition, alone or with their
+    /// team, ignoring withdrawn ones so the user can submit again
     pub async fn find_by_userid(
         db: &DatabaseConnection,
+        competition_id: i32,
         user_id: i32,
     ) -> ModelResult<Option<Self>> {
+        let team = teams::Model::find_by_member(db, competition_id, user_id).await?;
+        let mut authors = Condition::any().add(Column::UserId.eq(user_id));
+        if let Some(team) = team {
+            authors = authors.add(Column::TeamId.eq(team.id));
+        }
         let submission = Entity::find()
             .filter(
                 model::query::condition()
-                    .eq(Column::UserId, user_id)
+                    .eq(Column::CompetitionId, competition_id)
+                    .ne(Column::Status, Status::Withdrawn.as_str())
+                    .build(),
+            )
+            .filter(authors)
+            .one(db)
+            .await?;
+        Ok(submission)
+    }
+
+    /// the users who made the submission: the members of its team, or whoever
+    /// submitted it alone
+    pub async fn author_ids<C: ConnectionTrait>(&self, db: &C) -> ModelResult<Vec<i32>> {
+        let Some(team_id) = self.team_id else {
+            return Ok(vec![self.user_id]);
+        };
+        let members: Vec<i32> = team_members::Entity::find()
+            .filter(team_members::Column::TeamId.eq(team_id))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|member| member.user_id)
+            .collect();
+        Ok(if members.is_empty() {
+            vec![self.user_id]
+        } else {
+            members
+        })
+    }
+
+    /// whether `user_id` is one of the authors of the submission, see
+    /// [`Model::author_ids`]
+    pub async fn is_author<C: ConnectionTrait>(&self, db: &C, user_id: i32) -> ModelResult<bool> {
+        Ok(self.user_id == user_id || self.author_ids(db).await?.contains(&user_id))
+    }
+
+    /// finds a finalised submission of another user in the given competition linking
+    /// the same Figma file
+    pub async fn find_same_figma_file(
+        db: &DatabaseConnection,
+        competition_id: i32,
+        user_id: i32,
+        file_key: &str,
+    ) -> ModelResult<Option<Self>> {
+        let submission = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(Column::CompetitionId, competition_id)
+                    .eq(Column::FigmaFileKey, file_key)
+                    .eq(Column::Status, Status::Final.as_str())
+                    .ne(Column::UserId, user_id)
                     .build(),
             )
             .one(db)
@@ -40,7 +143,44 @@ impl Model {
 }
 
 // implement your write-oriented logic here
-impl ActiveModel {}
+impl ActiveModel {
+    /// Soft-deletes a submission as `status`, one of [`Status::Withdrawn`] and
+    /// [`Status::Disqualified`]. Its votes are kept, while the assignments to review it
+    /// and its scores are dropped.
+    ///
+    /// # Errors
+    ///
+    /// When could not save the submission or DB query error
+    pub async fn remove<C: ConnectionTrait>(
+        db: &C,
+        item: Model,
+        status: Status,
+        reason: Option<String>,
+    ) -> ModelResult<Model> {
+        vote_assignments::Entity::delete_many()
+            .filter(vote_assignments::Column::SubmissionId.eq(item.id))
+            .exec(db)
+            .await?;
+        scores::Entity::delete_many()
+            .filter(scores::Column::SubmissionId.eq(item.id))
+            .exec(db)
+            .await?;
+
+        let mut item = item.into_active_model();
+        item.status = Set(status.as_str().to_string());
+        item.removed_at = Set(Some(chrono::Utc::now().into()));
+        item.removal_reason = Set(reason);
+        Ok(item.update(db).await?)
+    }
+}
 
 // implement your custom finders, selectors oriented logic here
-impl Entity {}
+impl Entity {
+    /// selects the finalised submissions of a competition, the ones to be reviewed
+    #[must_use]
+    pub fn find_finalised(competition_id: i32) -> Select<Self> {
+        Self::find()
+            .filter(Column::CompetitionId.eq(competition_id))
+            .filter(Column::Status.eq(Status::Final.as_str()))
+    }
+}
@@ -0,0 +1,84 @@
+use loco_rs::model::ModelResult;
+use sea_orm::{entity::prelude::*, Set};
+
+pub use super::_entities::team_invites::{ActiveModel, Column, Entity, Model};
+pub type TeamInvites = Entity;
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+// implement your read-oriented logic here
+impl Model {
+    /// finds an invite that was not accepted yet by its token
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_pending_by_token<C: ConnectionTrait>(
+        db: &C,
+        token: &str,
+    ) -> ModelResult<Option<Self>> {
+        Ok(Entity::find()
+            .filter(Column::Token.eq(token))
+            .filter(Column::AcceptedAt.is_null())
+            .one(db)
+            .await?)
+    }
+
+    /// The invites of a team that were not accepted yet.
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_pending_by_team<C: ConnectionTrait>(
+        db: &C,
+        team_id: i32,
+    ) -> ModelResult<Vec<Self>> {
+        Ok(Entity::find()
+            .filter(Column::TeamId.eq(team_id))
+            .filter(Column::AcceptedAt.is_null())
+            .all(db)
+            .await?)
+    }
+}
+
+// implement your write-oriented logic here
+impl ActiveModel {
+    /// Invites `email` to join a team, identified by a freshly generated token.
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn invite<C: ConnectionTrait>(
+        db: &C,
+        team_id: i32,
+        email: &str,
+        invited_by_id: i32,
+    ) -> ModelResult<Model> {
+        Ok(Self {
+            team_id: Set(team_id),
+            email: Set(email.trim().to_lowercase()),
+            token: Set(uuid::Uuid::new_v4().to_string()),
+            invited_by_id: Set(invited_by_id),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?)
+    }
+}
+
+// implement your custom finders, selectors oriented logic here
+impl Entity {}
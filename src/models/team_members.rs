@@ -1,6 +1,7 @@
-pub use super::_entities::configs::{ActiveModel, Entity, Model};
 use sea_orm::entity::prelude::*;
-pub type Configs = Entity;
+
+pub use super::_entities::team_members::{ActiveModel, Column, Entity, Model};
+pub type TeamMembers = Entity;
 
 #[async_trait::async_trait]
 impl ActiveModelBehavior for ActiveModel {
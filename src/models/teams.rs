@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+
+use loco_rs::model::ModelResult;
+use sea_orm::{entity::prelude::*, QueryOrder};
+
+pub use super::_entities::teams::{ActiveModel, Column, Entity, Model};
+use super::_entities::{team_members, users};
+pub type Teams = Entity;
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+// implement your read-oriented logic here
+impl Model {
+    /// The team a user is a member of in the given competition.
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_by_member<C: ConnectionTrait>(
+        db: &C,
+        competition_id: i32,
+        user_id: i32,
+    ) -> ModelResult<Option<Self>> {
+        Ok(Entity::find()
+            .inner_join(team_members::Entity)
+            .filter(Column::CompetitionId.eq(competition_id))
+            .filter(team_members::Column::UserId.eq(user_id))
+            .one(db)
+            .await?)
+    }
+
+    /// The members of the team, in the order they joined.
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn members<C: ConnectionTrait>(&self, db: &C) -> ModelResult<Vec<users::Model>> {
+        Ok(users::Entity::find()
+            .inner_join(team_members::Entity)
+            .filter(team_members::Column::TeamId.eq(self.id))
+            .order_by_asc(team_members::Column::Id)
+            .all(db)
+            .await?)
+    }
+}
+
+// implement your write-oriented logic here
+impl ActiveModel {}
+
+// implement your custom finders, selectors oriented logic here
+impl Entity {
+    /// The member ids of every team in a competition, by team id.
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn members_by_team<C: ConnectionTrait>(
+        db: &C,
+        competition_id: i32,
+    ) -> ModelResult<HashMap<i32, Vec<i32>>> {
+        let mut teams: HashMap<i32, Vec<i32>> = HashMap::new();
+        for member in team_members::Entity::find()
+            .inner_join(Self)
+            .filter(Column::CompetitionId.eq(competition_id))
+            .order_by_asc(team_members::Column::Id)
+            .all(db)
+            .await?
+        {
+            teams
+                .entry(member.team_id)
+                .or_default()
+                .push(member.user_id);
+        }
+        Ok(teams)
+    }
+}
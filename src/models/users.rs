@@ -1,13 +1,17 @@
-use chrono::offset::Local;
+use chrono::{offset::Local, TimeDelta};
 use loco_rs::{auth::jwt, hash, prelude::*};
+use sea_orm::sea_query::{Expr, Func};
 use serde::{Deserialize, Serialize};
 use serde_json::Map;
 use uuid::Uuid;
 
 pub use super::_entities::users::{self, ActiveModel, Entity, Model};
+use super::refresh_tokens::digest;
+use crate::login;
 
-pub const MAGIC_LINK_LENGTH: i8 = 32;
-pub const MAGIC_LINK_EXPIRATION_MIN: i8 = 5;
+pub const MAGIC_LINK_LENGTH: usize = 32;
+pub const MAGIC_LINK_EXPIRATION_MIN: i64 = 5;
+pub const OTP_LENGTH: usize = 5;
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct LoginParams {
@@ -15,11 +19,14 @@ pub struct LoginParams {
     pub otp: String,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, Validate)]
 pub struct RegisterParams {
+    #[validate(email(message = "must be an email address"))]
     pub email: String,
+    #[validate(length(min = 2, max = 100, message = "must be between 2 and 100 characters"))]
     pub name: String,
-    pub secret: String,
+    /// The invite code, not needed for emails of an allowed domain
+    pub secret: Option<String>,
 }
 
 #[derive(Debug, Validate, Deserialize)]
@@ -56,18 +63,24 @@ impl ActiveModelBehavior for super::_entities::users::ActiveModel {
     }
 }
 
+/// The form emails are stored and compared in, e.g. `" Alice@X.com"` ->
+/// `"alice@x.com"`.
+#[must_use]
+pub fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
 impl Model {
-    /// finds a user by the provided email
+    /// finds a user by the provided email, ignoring case
     ///
     /// # Errors
     ///
     /// When could not find user by the given token or DB query error
     pub async fn find_by_email(db: &DatabaseConnection, email: &str) -> ModelResult<Self> {
+        // users registered before emails were normalized may still have capitals
         let user = users::Entity::find()
             .filter(
-                model::query::condition()
-                    .eq(users::Column::Email, email)
-                    .build(),
+                Expr::expr(Func::lower(Expr::col(users::Column::Email))).eq(normalize_email(email)),
             )
             .one(db)
             .await?;
@@ -92,6 +105,55 @@ impl Model {
         user.ok_or_else(|| ModelError::EntityNotFound)
     }
 
+    /// Signs in the user with a magic link token that has not expired at `now`,
+    /// using up the token along with any OTP. Of concurrent sign-ins with the same
+    /// token only one succeeds.
+    ///
+    /// # Errors
+    ///
+    /// When no user has the token, it was used in the meantime or DB query error
+    pub async fn consume_magic_token(
+        db: &DatabaseConnection,
+        token: &str,
+        now: DateTimeWithTimeZone,
+    ) -> ModelResult<Self> {
+        let token = digest(token);
+        let user = users::Entity::find()
+            .filter(users::Column::MagicLinkToken.eq(&token))
+            .filter(users::Column::MagicLinkExpiration.gt(now))
+            .one(db)
+            .await?
+            .ok_or_else(|| ModelError::EntityNotFound)?;
+
+        let result = users::Entity::update_many()
+            .col_expr(users::Column::MagicLinkToken, Expr::value(None::<String>))
+            .col_expr(
+                users::Column::MagicLinkExpiration,
+                Expr::value(None::<DateTimeWithTimeZone>),
+            )
+            .col_expr(users::Column::Otp, Expr::value(None::<String>))
+            .col_expr(
+                users::Column::OtpSentAt,
+                Expr::value(None::<DateTimeWithTimeZone>),
+            )
+            .col_expr(users::Column::OtpFailedAttempts, Expr::value(0))
+            .col_expr(
+                users::Column::OtpLockedUntil,
+                Expr::value(None::<DateTimeWithTimeZone>),
+            )
+            .col_expr(users::Column::UpdatedAt, Expr::value(now))
+            .filter(users::Column::Id.eq(user.id))
+            .filter(users::Column::MagicLinkToken.eq(&token))
+            .filter(users::Column::MagicLinkExpiration.gt(now))
+            .exec(db)
+            .await?;
+        if result.rows_affected != 1 {
+            return Err(ModelError::EntityNotFound);
+        }
+        let user = users::Entity::find_by_id(user.id).one(db).await?;
+        user.ok_or_else(|| ModelError::EntityNotFound)
+    }
+
     /// Creates a JWT
     ///
     /// # Errors
@@ -102,20 +164,107 @@ impl Model {
             .generate_token(expiration, self.pid.to_string(), Map::new())
             .map_err(ModelError::from)
     }
+
+    /// Whether too many failed attempts locked the user out of logging in at `now`.
+    #[must_use]
+    pub fn is_otp_locked(&self, now: DateTimeWithTimeZone) -> bool {
+        self.otp_locked_until.is_some_and(|until| now < until)
+    }
+
+    /// Whether `otp` is the pending OTP of the user and was sent less than `expiration`
+    /// before `now`. The hashes are compared in constant time.
+    #[must_use]
+    pub fn verify_otp(&self, otp: &str, now: DateTimeWithTimeZone, expiration: TimeDelta) -> bool {
+        let (Some(hashed), Some(sent_at)) = (&self.otp, self.otp_sent_at) else {
+            return false;
+        };
+        now < sent_at + expiration && hash::verify_password(otp, hashed)
+    }
+
+    /// Counts a failed login attempt. Once `otp_max_attempts` failed in a row, the
+    /// pending OTP is invalidated and the user locked out.
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn record_failed_otp(
+        &self,
+        db: &DatabaseConnection,
+        settings: &login::Settings,
+        now: DateTimeWithTimeZone,
+    ) -> ModelResult<Self> {
+        // counted in the database, so concurrent attempts can't slip past the limit
+        let user = users::Entity::update_many()
+            .col_expr(
+                users::Column::OtpFailedAttempts,
+                Expr::col(users::Column::OtpFailedAttempts).add(1),
+            )
+            .filter(users::Column::Id.eq(self.id))
+            .exec_with_returning(db)
+            .await?
+            .pop()
+            .ok_or(ModelError::EntityNotFound)?;
+        if u32::try_from(user.otp_failed_attempts).unwrap_or(0) < settings.otp_max_attempts {
+            return Ok(user);
+        }
+
+        tracing::info!(
+            user_id = user.id,
+            "too many failed OTP attempts, locking out"
+        );
+        let mut user = user.into_active_model();
+        user.otp = ActiveValue::Set(None);
+        user.otp_sent_at = ActiveValue::Set(None);
+        user.otp_failed_attempts = ActiveValue::Set(0);
+        user.otp_locked_until = ActiveValue::Set(Some(now + settings.lockout()));
+        user.update(db).await.map_err(ModelError::from)
+    }
 }
 
 impl ActiveModel {
-    ///Create OTP
-    pub async fn create_otp(mut self, db: &DatabaseConnection) -> ModelResult<Model> {
-        self.otp = ActiveValue::Set(Some(hash::random_string(5)));
+    /// Creates an OTP, returning the user along with the OTP to mail them. Only its
+    /// hash is stored.
+    ///
+    /// # Errors
+    ///
+    /// When hashing fails or DB query error
+    pub async fn create_otp(mut self, db: &DatabaseConnection) -> ModelResult<(Model, String)> {
+        let otp = hash::random_string(OTP_LENGTH);
+        let hashed = hash::hash_password(&otp).map_err(|err| ModelError::Any(err.into()))?;
+        self.otp = ActiveValue::Set(Some(hashed));
         self.otp_sent_at = ActiveValue::set(Some(Local::now().into()));
-        self.update(db).await.map_err(ModelError::from)
+        let user = self.update(db).await.map_err(ModelError::from)?;
+        Ok((user, otp))
+    }
+
+    /// Creates a single-use token to sign in with by following a link, valid for
+    /// [`MAGIC_LINK_EXPIRATION_MIN`] minutes, returning the user along with the
+    /// token. Unlike the short OTP, it is long enough that it can't be guessed, so a
+    /// fast hash of it is stored to be looked up, like refresh tokens.
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn create_magic_link(
+        mut self,
+        db: &DatabaseConnection,
+    ) -> ModelResult<(Model, String)> {
+        let token = hash::random_string(MAGIC_LINK_LENGTH);
+        let expiration = Local::now() + TimeDelta::minutes(MAGIC_LINK_EXPIRATION_MIN);
+        self.magic_link_token = ActiveValue::Set(Some(digest(&token)));
+        self.magic_link_expiration = ActiveValue::Set(Some(expiration.into()));
+        let user = self.update(db).await.map_err(ModelError::from)?;
+        Ok((user, token))
     }
 
-    ///OTP is used, consume it
-    pub async fn consume_otp(mut self, db: &DatabaseConnection) -> ModelResult<Model> {
+    /// The user signed in, with the OTP or the magic link, neither can be used again
+    pub async fn consume_login(mut self, db: &DatabaseConnection) -> ModelResult<Model> {
         self.otp = ActiveValue::Set(None);
         self.otp_sent_at = ActiveValue::set(None);
+        self.magic_link_token = ActiveValue::Set(None);
+        self.magic_link_expiration = ActiveValue::Set(None);
+        self.otp_failed_attempts = ActiveValue::Set(0);
+        self.otp_locked_until = ActiveValue::Set(None);
         self.update(db).await.map_err(ModelError::from)
     }
 }
@@ -8,6 +8,10 @@ impl ActiveModelBehavior for ActiveModel {
     where
         C: ConnectionTrait,
     {
+        // SQLite keeps the column nullable, see m20261017_102000
+        if insert && self.competition_id.is_not_set() {
+            return Err(DbErr::Custom("competition_id must be set".to_string()));
+        }
         if !insert && self.updated_at.is_unchanged() {
             let mut this = self;
             this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
@@ -0,0 +1,108 @@
+use std::collections::{BTreeMap, HashMap};
+
+use loco_rs::model::ModelResult;
+use sea_orm::{entity::prelude::*, JoinType, QuerySelect};
+
+pub use super::_entities::vote_scores::{ActiveModel, Column, Entity, Model};
+use super::{
+    _entities::{criteria, submissions, votes},
+    submissions::Status,
+};
+use crate::aggregators::Rating;
+pub type VoteScores = Entity;
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+// implement your read-oriented logic here
+impl Model {}
+
+// implement your write-oriented logic here
+impl ActiveModel {}
+
+// implement your custom finders, selectors oriented logic here
+impl Entity {
+    /// Loads the per-criterion scores of the given votes, keyed by vote id and
+    /// criterion key.
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_by_votes<C: ConnectionTrait>(
+        db: &C,
+        vote_ids: Vec<i32>,
+    ) -> ModelResult<HashMap<i32, BTreeMap<String, i32>>> {
+        let rows = Self::find()
+            .filter(Column::VoteId.is_in(vote_ids))
+            .find_also_related(criteria::Entity)
+            .all(db)
+            .await?;
+
+        let mut by_vote: HashMap<i32, BTreeMap<String, i32>> = HashMap::new();
+        for (row, criterion) in rows {
+            if let Some(criterion) = criterion {
+                by_vote
+                    .entry(row.vote_id)
+                    .or_default()
+                    .insert(criterion.key, row.score);
+            }
+        }
+        Ok(by_vote)
+    }
+
+    /// Loads the ratings cast on the finalised submissions of a competition, grouped
+    /// by the criteria of its rubric in display order. Votes on withdrawn and
+    /// disqualified submissions are kept, but don't count towards anything.
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_ratings<C: ConnectionTrait>(
+        db: &C,
+        competition_id: i32,
+    ) -> ModelResult<Vec<(criteria::Model, Vec<Rating>)>> {
+        let rubric = super::criteria::Model::for_competition(db, competition_id).await?;
+        let rows = Self::find()
+            .inner_join(votes::Entity)
+            .join(JoinType::InnerJoin, votes::Relation::Submissions.def())
+            .filter(votes::Column::CompetitionId.eq(competition_id))
+            .filter(submissions::Column::Status.eq(Status::Final.as_str()))
+            .select_also(votes::Entity)
+            .all(db)
+            .await?;
+
+        let mut by_criterion: HashMap<i32, Vec<Rating>> = HashMap::new();
+        for (row, vote) in rows {
+            if let Some(vote) = vote {
+                by_criterion
+                    .entry(row.criterion_id)
+                    .or_default()
+                    .push(Rating {
+                        voter_id: vote.user_id,
+                        submission_id: vote.submission_id,
+                        score: row.score as f32,
+                    });
+            }
+        }
+        Ok(rubric
+            .into_iter()
+            .map(|criterion| {
+                let ratings = by_criterion.remove(&criterion.id).unwrap_or_default();
+                (criterion, ratings)
+            })
+            .collect())
+    }
+}
@@ -1,5 +1,10 @@
+use std::collections::BTreeMap;
+
+use loco_rs::model::ModelResult;
+use sea_orm::{entity::prelude::*, Set};
+
 pub use super::_entities::votes::{ActiveModel, Entity, Model};
-use sea_orm::entity::prelude::*;
+use super::{criteria, vote_scores};
 pub type Votes = Entity;
 
 #[async_trait::async_trait]
@@ -8,6 +13,10 @@ impl ActiveModelBehavior for ActiveModel {
     where
         C: ConnectionTrait,
     {
+        // SQLite keeps the column nullable, see m20261017_102000
+        if insert && self.competition_id.is_not_set() {
+            return Err(DbErr::Custom("competition_id must be set".to_string()));
+        }
         if !insert && self.updated_at.is_unchanged() {
             let mut this = self;
             this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
@@ -19,7 +28,43 @@ impl ActiveModelBehavior for ActiveModel {
 }
 
 // implement your read-oriented logic here
-impl Model {}
+impl Model {
+    /// Replaces the per-criterion scores of this vote with `scores`, keyed by the
+    /// criterion keys of `rubric`.
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn set_scores<C: ConnectionTrait>(
+        &self,
+        db: &C,
+        rubric: &[criteria::Model],
+        scores: &BTreeMap<String, i32>,
+    ) -> ModelResult<()> {
+        vote_scores::Entity::delete_many()
+            .filter(vote_scores::Column::VoteId.eq(self.id))
+            .exec(db)
+            .await?;
+
+        let rows: Vec<_> = rubric
+            .iter()
+            .filter_map(|criterion| {
+                scores
+                    .get(&criterion.key)
+                    .map(|score| vote_scores::ActiveModel {
+                        vote_id: Set(self.id),
+                        criterion_id: Set(criterion.id),
+                        score: Set(*score),
+                        ..Default::default()
+                    })
+            })
+            .collect();
+        if !rows.is_empty() {
+            vote_scores::Entity::insert_many(rows).exec(db).await?;
+        }
+        Ok(())
+    }
+}
 
 // implement your write-oriented logic here
 impl ActiveModel {}
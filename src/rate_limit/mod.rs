@@ -0,0 +1,342 @@
+//! Rate limiting of endpoints that can be abused to spam inboxes or guess OTPs,
+//! configured under `settings.rate_limit`:
+//!
+//! ```yaml
+//! settings:
+//!   rate_limit:
+//!     # `memory`, or `database` to share the limits between instances
+//!     store: memory
+//!     rules:
+//!       send_otp:
+//!         per_ip: { capacity: 10, refill_per_minute: 5 }
+//!         per_email: { capacity: 3, refill_per_minute: 1 }
+//! ```
+//!
+//! Every rule is a token bucket per client IP and per email in the request body:
+//! a request takes a token, `capacity` being how many can be taken in a burst and
+//! `refill_per_minute` how fast they come back. Requests finding the bucket empty are
+//! answered with 429 and a `Retry-After` header. Routes opt in with [`limit`], rules
+//! missing from the configuration fall back to [`Settings::default`].
+use std::{collections::HashMap, net::SocketAddr, sync::Arc, time::Duration};
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::{ConnectInfo, Request, State},
+    http::header::RETRY_AFTER,
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Extension,
+};
+use chrono::{DateTime, Utc};
+use loco_rs::{
+    app::AppContext, config::Config, controller::middleware::remote_ip::RemoteIP, Result,
+};
+use serde::Deserialize;
+
+use crate::login::too_many_requests;
+
+pub mod store;
+
+pub use store::{DatabaseStore, MemoryStore, Store};
+
+/// The largest body read to find the email of a request.
+const MAX_BODY: usize = 64 * 1024;
+
+/// A token bucket.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+pub struct Limit {
+    /// The most requests allowed in a burst
+    pub capacity: u32,
+    /// How many requests are allowed again per minute
+    pub refill_per_minute: u32,
+}
+
+impl Limit {
+    #[must_use]
+    pub const fn new(capacity: u32, refill_per_minute: u32) -> Self {
+        Self {
+            capacity,
+            refill_per_minute,
+        }
+    }
+
+    fn refill_per_second(&self) -> f64 {
+        f64::from(self.refill_per_minute) / 60.0
+    }
+}
+
+/// The limits of a route.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct Rule {
+    pub per_ip: Option<Limit>,
+    pub per_email: Option<Limit>,
+}
+
+/// Where buckets are kept.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum StoreKind {
+    /// In the memory of this instance
+    #[default]
+    Memory,
+    /// In the database, shared by every instance
+    Database,
+}
+
+/// The `settings.rate_limit` section of the configuration.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub store: StoreKind,
+    /// Rules by name, see [`limit`]
+    pub rules: HashMap<String, Rule>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            store: StoreKind::Memory,
+            rules: HashMap::from([
+                (
+                    "send_otp".to_string(),
+                    Rule {
+                        per_ip: Some(Limit::new(10, 5)),
+                        per_email: Some(Limit::new(3, 1)),
+                    },
+                ),
+                (
+                    "login".to_string(),
+                    Rule {
+                        per_ip: Some(Limit::new(20, 10)),
+                        per_email: Some(Limit::new(10, 5)),
+                    },
+                ),
+                (
+                    "register".to_string(),
+                    Rule {
+                        per_ip: Some(Limit::new(5, 1)),
+                        per_email: Some(Limit::new(3, 1)),
+                    },
+                ),
+            ]),
+        }
+    }
+}
+
+impl Settings {
+    /// Reads the rate limit settings, configured rules replacing the default ones of
+    /// the same name.
+    ///
+    /// # Errors
+    ///
+    /// When the settings are malformed
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let Some(rate_limit) = config
+            .settings
+            .as_ref()
+            .and_then(|settings| settings.get("rate_limit"))
+        else {
+            return Ok(Self::default());
+        };
+        let configured: Self = serde_json::from_value(rate_limit.clone())?;
+        let mut rules = Self::default().rules;
+        rules.extend(configured.rules);
+        Ok(Self {
+            store: configured.store,
+            rules,
+        })
+    }
+}
+
+/// The state of a bucket.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bucket {
+    pub tokens: f64,
+    pub refilled_at: DateTime<Utc>,
+}
+
+/// Whether a request may pass.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Decision {
+    Allowed,
+    Limited { retry_after: Duration },
+}
+
+impl Bucket {
+    /// A full bucket.
+    #[must_use]
+    pub fn full(limit: &Limit, now: DateTime<Utc>) -> Self {
+        Self {
+            tokens: f64::from(limit.capacity),
+            refilled_at: now,
+        }
+    }
+
+    /// Takes a token at `now`, returning the bucket afterwards.
+    #[must_use]
+    pub fn take(self, limit: &Limit, now: DateTime<Utc>) -> (Self, Decision) {
+        let elapsed = (now - self.refilled_at).to_std().unwrap_or_default();
+        let tokens = limit
+            .refill_per_second()
+            .mul_add(elapsed.as_secs_f64(), self.tokens)
+            .min(f64::from(limit.capacity));
+        let bucket = Self {
+            tokens,
+            refilled_at: now,
+        };
+        if tokens >= 1.0 {
+            return (
+                Self {
+                    tokens: tokens - 1.0,
+                    ..bucket
+                },
+                Decision::Allowed,
+            );
+        }
+
+        let retry_after = if limit.refill_per_minute == 0 {
+            Duration::MAX
+        } else {
+            Duration::from_secs_f64((1.0 - tokens) / limit.refill_per_second())
+        };
+        (bucket, Decision::Limited { retry_after })
+    }
+
+    /// When the bucket will be full again, never for a limit without refill.
+    #[must_use]
+    pub fn full_at(&self, limit: &Limit) -> DateTime<Utc> {
+        let missing = f64::from(limit.capacity) - self.tokens;
+        if missing <= 0.0 {
+            return self.refilled_at;
+        }
+        if limit.refill_per_minute == 0 {
+            return DateTime::<Utc>::MAX_UTC;
+        }
+        let refill = Duration::from_secs_f64(missing / limit.refill_per_second());
+        chrono::Duration::from_std(refill)
+            .ok()
+            .and_then(|refill| self.refilled_at.checked_add_signed(refill))
+            .unwrap_or(DateTime::<Utc>::MAX_UTC)
+    }
+}
+
+/// The configured rules along with where their buckets are kept, shared by every
+/// route through a request extension.
+pub struct RateLimiter {
+    pub settings: Settings,
+    pub store: Box<dyn Store>,
+}
+
+impl RateLimiter {
+    /// Builds the limiter configured for the app.
+    ///
+    /// # Errors
+    ///
+    /// When the settings are malformed
+    pub fn from_context(ctx: &AppContext) -> Result<Self> {
+        let settings = Settings::from_config(&ctx.config)?;
+        let store: Box<dyn Store> = match settings.store {
+            StoreKind::Memory => Box::new(MemoryStore::default()),
+            StoreKind::Database => Box::new(DatabaseStore::new(ctx.db.clone())),
+        };
+        Ok(Self { settings, store })
+    }
+
+    /// Takes a token from every bucket of `rule` the request falls into, stopping at
+    /// the first empty one.
+    ///
+    /// # Errors
+    ///
+    /// When the store fails
+    pub async fn check(
+        &self,
+        rule: &str,
+        ip: Option<&str>,
+        email: Option<&str>,
+        now: DateTime<Utc>,
+    ) -> Result<Decision> {
+        let Some(limits) = self.settings.rules.get(rule) else {
+            return Ok(Decision::Allowed);
+        };
+        let buckets = [
+            (limits.per_ip.as_ref(), "ip", ip),
+            (limits.per_email.as_ref(), "email", email),
+        ];
+        for (limit, kind, value) in buckets {
+            let (Some(limit), Some(value)) = (limit, value) else {
+                continue;
+            };
+            let key = format!("{rule}:{kind}:{value}");
+            let decision = self.store.take(&key, limit, now).await?;
+            if decision != Decision::Allowed {
+                tracing::info!(rule, kind, value, "rate limited");
+                return Ok(decision);
+            }
+        }
+        Ok(Decision::Allowed)
+    }
+}
+
+#[derive(Deserialize)]
+struct EmailBody {
+    email: Option<String>,
+}
+
+fn client_ip(request: &Request) -> Option<String> {
+    let extensions = request.extensions();
+    match extensions.get::<RemoteIP>() {
+        Some(RemoteIP::Forwarded(ip) | RemoteIP::Socket(ip)) => Some(ip.to_string()),
+        _ => extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip().to_string()),
+    }
+}
+
+/// The middleware limiting a route by the rule given as its state:
+///
+/// ```ignore
+/// .add("/login", post(login).layer(from_fn_with_state("login", rate_limit::limit)))
+/// ```
+///
+/// Routes are not limited if no [`RateLimiter`] was installed, see the `rate_limit`
+/// initializer.
+pub async fn limit(
+    State(rule): State<&'static str>,
+    limiter: Option<Extension<Arc<RateLimiter>>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(Extension(limiter)) = limiter else {
+        return next.run(request).await;
+    };
+
+    let ip = client_ip(&request);
+    let (parts, body) = request.into_parts();
+    let Ok(bytes) = to_bytes(body, MAX_BODY).await else {
+        return loco_rs::Error::BadRequest("request body too large".to_string()).into_response();
+    };
+    let email = serde_json::from_slice::<EmailBody>(&bytes)
+        .ok()
+        .and_then(|body| body.email)
+        .map(|email| email.trim().to_lowercase());
+
+    match limiter
+        .check(rule, ip.as_deref(), email.as_deref(), Utc::now())
+        .await
+    {
+        Ok(Decision::Allowed) => {}
+        Ok(Decision::Limited { retry_after }) => {
+            let mut response =
+                too_many_requests("too many requests, try again later").into_response();
+            let seconds = retry_after.as_secs_f64().ceil().min(f64::from(u32::MAX));
+            response
+                .headers_mut()
+                .insert(RETRY_AFTER, (seconds as u64).max(1).into());
+            return response;
+        }
+        // rather let requests through than lock everyone out while the store is down
+        Err(err) => tracing::error!(rule, error = %err, "rate limit store failed"),
+    }
+    next.run(Request::from_parts(parts, Body::from(bytes)))
+        .await
+}
@@ -0,0 +1,135 @@
+//! Where the buckets of the [`RateLimiter`](super::RateLimiter) are kept.
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use loco_rs::{Error, Result};
+use sea_orm::{
+    sea_query::OnConflict, ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection,
+    EntityTrait, IntoActiveModel, QueryFilter, QuerySelect, TransactionTrait,
+};
+
+use super::{Bucket, Decision, Limit};
+use crate::models::rate_limit_buckets::{ActiveModel, Column, Entity};
+
+/// Buckets kept by key, e.g. `login:ip:127.0.0.1`.
+#[async_trait]
+pub trait Store: Send + Sync {
+    /// Takes a token from the bucket of `key`, a missing bucket starting out full.
+    async fn take(&self, key: &str, limit: &Limit, now: DateTime<Utc>) -> Result<Decision>;
+}
+
+/// How many buckets the [`MemoryStore`] keeps before forgetting full ones.
+const PRUNE_ABOVE: usize = 10_000;
+
+/// Buckets in the memory of this instance, lost on restart.
+#[derive(Default)]
+pub struct MemoryStore {
+    buckets: Mutex<HashMap<String, (Bucket, DateTime<Utc>)>>,
+}
+
+#[async_trait]
+impl Store for MemoryStore {
+    async fn take(&self, key: &str, limit: &Limit, now: DateTime<Utc>) -> Result<Decision> {
+        let mut buckets = self
+            .buckets
+            .lock()
+            .map_err(|_| Error::string("rate limit buckets poisoned"))?;
+        if buckets.len() > PRUNE_ABOVE {
+            // a full bucket is the same as a missing one
+            buckets.retain(|_, (_, full_at)| *full_at > now);
+        }
+
+        let bucket = buckets
+            .get(key)
+            .map_or_else(|| Bucket::full(limit, now), |(bucket, _)| *bucket);
+        let (bucket, decision) = bucket.take(limit, now);
+        buckets.insert(key.to_string(), (bucket, bucket.full_at(limit)));
+        Ok(decision)
+    }
+}
+
+/// How many takes the [`DatabaseStore`] serves between deleting full buckets.
+const PRUNE_EVERY: u64 = 1_000;
+
+/// Buckets in the database, shared by every instance of the app.
+pub struct DatabaseStore {
+    db: DatabaseConnection,
+    takes: AtomicU64,
+}
+
+impl DatabaseStore {
+    #[must_use]
+    pub const fn new(db: DatabaseConnection) -> Self {
+        Self {
+            db,
+            takes: AtomicU64::new(0),
+        }
+    }
+
+    /// Deletes the buckets which are full again at `now`, returning how many.
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn prune(&self, now: DateTime<Utc>) -> Result<u64> {
+        // a full bucket is the same as a missing one
+        let result = Entity::delete_many()
+            .filter(Column::FullAt.lte(now))
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+}
+
+#[async_trait]
+impl Store for DatabaseStore {
+    async fn take(&self, key: &str, limit: &Limit, now: DateTime<Utc>) -> Result<Decision> {
+        if self
+            .takes
+            .fetch_add(1, Ordering::Relaxed)
+            .is_multiple_of(PRUNE_EVERY)
+        {
+            self.prune(now).await?;
+        }
+
+        let txn = self.db.begin().await?;
+        let full = Bucket::full(limit, now);
+        Entity::insert(ActiveModel {
+            key: Set(key.to_string()),
+            tokens: Set(full.tokens),
+            refilled_at: Set(full.refilled_at.into()),
+            full_at: Set(now.into()),
+            ..Default::default()
+        })
+        .on_conflict(OnConflict::column(Column::Key).do_nothing().to_owned())
+        .exec_without_returning(&txn)
+        .await?;
+        // locked until committed, so concurrent requests take tokens one after another
+        let row = Entity::find()
+            .filter(Column::Key.eq(key))
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or(Error::NotFound)?;
+
+        let (bucket, decision) = Bucket {
+            tokens: row.tokens,
+            refilled_at: row.refilled_at.into(),
+        }
+        .take(limit, now);
+        let mut row = row.into_active_model();
+        row.tokens = Set(bucket.tokens);
+        row.refilled_at = Set(bucket.refilled_at.into());
+        row.full_at = Set(bucket.full_at(limit).into());
+        row.update(&txn).await?;
+        txn.commit().await?;
+        Ok(decision)
+    }
+}
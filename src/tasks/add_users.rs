@@ -1,6 +1,9 @@
 use loco_rs::prelude::*;
 
-use crate::models::users;
+use crate::{
+    models::{audit_events::Event, users},
+    views::users::UserResponse,
+};
 
 pub struct AddUsers;
 #[async_trait]
@@ -34,7 +37,11 @@ impl Task for AddUsers {
                                 name: Set(name),
                                 ..Default::default()
                             };
-                            user.insert(&ctx.db).await?;
+                            let user = user.insert(&ctx.db).await?;
+                            Event::new("user.create", "user", Some(user.id))
+                                .after(&UserResponse::new(&user, false))
+                                .record(&ctx.db)
+                                .await?;
                             println!("Added user: {:?}", &record[1]);
                         }
                     } else {
@@ -55,7 +62,11 @@ impl Task for AddUsers {
                 name: Set(name.to_string()),
                 ..Default::default()
             };
-            user.insert(&ctx.db).await?;
+            let user = user.insert(&ctx.db).await?;
+            Event::new("user.create", "user", Some(user.id))
+                .after(&UserResponse::new(&user, false))
+                .record(&ctx.db)
+                .await?;
             println!("Added user: {:?}", name);
         }
         println!("Task AddUsers generated");
@@ -1,9 +1,57 @@
-use crate::models::_entities::configs;
+use crate::models::{
+    audit_events::Event,
+    competitions::{self, Phase},
+};
 use crate::tasks::{assign_submissions::AssignSubmissions, gen_leaderboard::GenLeaderboard};
 use chrono::Utc;
 use loco_rs::prelude::*;
 
 pub struct AssignAndGen;
+
+impl AssignAndGen {
+    /// Assigns the submissions of `competition` and moves it to [`Phase::Assigned`].
+    async fn assign(
+        ctx: &AppContext,
+        competition: &competitions::Model,
+        now: DateTimeWithTimeZone,
+    ) -> Result<competitions::Model> {
+        let item = competition
+            .transition(Phase::Assigned, now)
+            .map_err(|err| Error::string(&err.to_string()))?;
+        let distribution = AssignSubmissions::assign(ctx, competition).await?;
+        let competition = item.update(&ctx.db).await?;
+        Event::new("competition.assign", "competition", Some(competition.id))
+            .competition(competition.id)
+            .after(&distribution)
+            .record(&ctx.db)
+            .await?;
+        Ok(competition)
+    }
+
+    /// Generates the scores of `competition` and moves it to [`Phase::Scored`].
+    async fn generate(
+        ctx: &AppContext,
+        competition: &competitions::Model,
+        now: DateTimeWithTimeZone,
+    ) -> Result<competitions::Model> {
+        let item = competition
+            .transition(Phase::Scored, now)
+            .map_err(|err| Error::string(&err.to_string()))?;
+        let leaderboard = GenLeaderboard::generate(ctx, competition).await?;
+        let competition = item.update(&ctx.db).await?;
+        Event::new(
+            "competition.generate_scores",
+            "competition",
+            Some(competition.id),
+        )
+        .competition(competition.id)
+        .after(&leaderboard)
+        .record(&ctx.db)
+        .await?;
+        Ok(competition)
+    }
+}
+
 #[async_trait]
 impl Task for AssignAndGen {
     fn task(&self) -> TaskInfo {
@@ -13,34 +61,47 @@ impl Task for AssignAndGen {
         }
     }
     async fn run(&self, ctx: &AppContext, _vars: &task::Vars) -> Result<()> {
-        // Fetch config
-        let config = configs::Entity::find().one(&ctx.db).await?;
-        let now = Utc::now();
-        if let Some(config) = config {
-            // Only run assign_submissions if submission period has ended and we haven't assigned yet
-            if let Some(sub_end) = config.submission_end {
-                if now > sub_end.to_utc() && !config.assigned {
-                    println!("[assign_and_gen] Running assign_submissions...");
-                    let assign_task = AssignSubmissions;
-                    let _ = assign_task.run(ctx, _vars).await;
-                    let mut config_update = config.clone().into_active_model();
-                    config_update.assigned = Set(true);
-                    config_update.save(&ctx.db).await?;
+        // Every competition moves through its phases independently, one failing
+        // does not hold up the others
+        let all_competitions = competitions::Entity::find().all(&ctx.db).await?;
+        let now = Utc::now().into();
+        if all_competitions.is_empty() {
+            println!("[assign_and_gen] No competition found, skipping.");
+        }
+        for competition in all_competitions {
+            let mut competition = competition;
+            // Only run assign_submissions once the submission period has ended
+            if competition.phase(now) == Phase::SubmissionsClosed {
+                println!(
+                    "[assign_and_gen] Running assign_submissions for {}...",
+                    competition.slug
+                );
+                match Self::assign(ctx, &competition, now).await {
+                    Ok(assigned) => competition = assigned,
+                    Err(err) => {
+                        tracing::error!(
+                            competition_id = competition.id,
+                            error = %err,
+                            "could not assign submissions"
+                        );
+                        continue;
+                    }
                 }
             }
-            // Only run gen_leaderboard if voting period has ended and we haven't generated yet
-            if let Some(vote_end) = config.voting_end {
-                if now > vote_end.to_utc() && !config.created_scores {
-                    println!("[assign_and_gen] Running gen_leaderboard...");
-                    let gen_task = GenLeaderboard;
-                    let _ = gen_task.run(ctx, _vars).await;
-                    let mut config_update = config.into_active_model();
-                    config_update.created_scores = Set(true);
-                    config_update.save(&ctx.db).await?;
+            // Only run gen_leaderboard once the voting period has ended
+            if competition.phase(now) == Phase::VotingClosed {
+                println!(
+                    "[assign_and_gen] Running gen_leaderboard for {}...",
+                    competition.slug
+                );
+                if let Err(err) = Self::generate(ctx, &competition, now).await {
+                    tracing::error!(
+                        competition_id = competition.id,
+                        error = %err,
+                        "could not generate scores"
+                    );
                 }
             }
-        } else {
-            println!("[assign_and_gen] No config found, skipping.");
         }
         Ok(())
     }
@@ -1,67 +1,214 @@
-use std::cmp::min;
+use std::collections::HashSet;
 
 use loco_rs::prelude::*;
-use rand::seq::SliceRandom;
 use tracing::debug;
 
-use crate::models::{submissions, users, vote_assignments};
+use crate::{
+    assignment::{self, Constraints, Distribution, Submission},
+    models::{
+        _entities::{admins, submissions, users, vote_assignments},
+        audit_events::Event,
+        competitions::{self, Phase},
+        conflicts, teams,
+    },
+};
 
 pub struct AssignSubmissions;
-#[async_trait]
-impl Task for AssignSubmissions {
-    fn task(&self) -> TaskInfo {
-        TaskInfo {
-            name: "assign_submissions".to_string(),
-            detail: "Assigns submissions to users so they can vote on them.".to_string(),
+
+impl AssignSubmissions {
+    /// Assigns the finalised submissions of `competition` to reviewers as configured
+    /// on the competition, replacing any assignments the competition already had.
+    pub async fn assign(
+        ctx: &AppContext,
+        competition: &competitions::Model,
+    ) -> Result<Distribution> {
+        let constraints = Self::constraints(ctx, competition).await?;
+        Self::assign_with(ctx, competition, &constraints).await
+    }
+
+    /// The constraints configured on `competition`, along with all declared
+    /// conflicts of interest.
+    pub async fn constraints(
+        ctx: &AppContext,
+        competition: &competitions::Model,
+    ) -> Result<Constraints> {
+        let mut conflicts = conflicts::Entity::pairs(&ctx.db).await?;
+        Self::add_team_conflicts(ctx, competition, &mut conflicts).await?;
+        Ok(Constraints {
+            reviewers_per_submission: usize::try_from(competition.reviewers_per_submission)
+                .unwrap_or_default(),
+            max_per_reviewer: competition
+                .max_reviews_per_user
+                .and_then(|max| usize::try_from(max).ok()),
+            conflicts,
+        })
+    }
+
+    /// A team's submission is filed under the member who made it, so the other
+    /// members, and whoever declared a conflict with any of them, are recorded as in
+    /// conflict with that member.
+    async fn add_team_conflicts(
+        ctx: &AppContext,
+        competition: &competitions::Model,
+        conflicts: &mut HashSet<(i32, i32)>,
+    ) -> Result<()> {
+        let teams = teams::Entity::members_by_team(&ctx.db, competition.id).await?;
+        let declared: Vec<(i32, i32)> = conflicts.iter().copied().collect();
+        for submission in submissions::Entity::find_finalised(competition.id)
+            .all(&ctx.db)
+            .await?
+        {
+            let Some(members) = submission.team_id.and_then(|team| teams.get(&team)) else {
+                continue;
+            };
+            for member in members {
+                conflicts.insert((*member, submission.user_id));
+                for (reviewer_id, _) in declared.iter().filter(|(_, other)| other == member) {
+                    conflicts.insert((*reviewer_id, submission.user_id));
+                }
+            }
         }
+        Ok(())
     }
-    async fn run(&self, ctx: &AppContext, _vars: &task::Vars) -> Result<()> {
-        let mut all_submissions = submissions::Entity::find().all(&ctx.db).await?;
-        let all_users = submissions::Entity::find()
-            .inner_join(users::Entity)
+
+    /// Everyone who reviews in `competition`: all users, except admins who did not
+    /// submit themselves, alone or in a team, as they organise rather than take part.
+    pub async fn reviewers(
+        ctx: &AppContext,
+        competition: &competitions::Model,
+    ) -> Result<Vec<i32>> {
+        let teams = teams::Entity::members_by_team(&ctx.db, competition.id).await?;
+        let mut submitters = HashSet::new();
+        for submission in submissions::Entity::find_finalised(competition.id)
             .all(&ctx.db)
-            .await?;
-        all_submissions.shuffle(&mut rand::rng());
+            .await?
+        {
+            submitters.insert(submission.user_id);
+            if let Some(members) = submission.team_id.and_then(|team| teams.get(&team)) {
+                submitters.extend(members);
+            }
+        }
+        let admins: HashSet<i32> = admins::Entity::find()
+            .all(&ctx.db)
+            .await?
+            .into_iter()
+            .map(|admin| admin.user_id)
+            .collect();
+        Ok(users::Entity::find()
+            .all(&ctx.db)
+            .await?
+            .into_iter()
+            .map(|user| user.id)
+            .filter(|id| !admins.contains(id) || submitters.contains(id))
+            .collect())
+    }
+
+    /// Like [`AssignSubmissions::assign`], with the given constraints.
+    pub async fn assign_with(
+        ctx: &AppContext,
+        competition: &competitions::Model,
+        constraints: &Constraints,
+    ) -> Result<Distribution> {
+        let all_submissions: Vec<Submission> = submissions::Entity::find_finalised(competition.id)
+            .all(&ctx.db)
+            .await?
+            .into_iter()
+            .map(|submission| Submission {
+                id: submission.id,
+                author_id: submission.user_id,
+            })
+            .collect();
+        let reviewers = Self::reviewers(ctx, competition).await?;
 
-        //Delete old assignments if they exist.
+        let (assignments, distribution) =
+            assignment::assign(&all_submissions, &reviewers, constraints, &mut rand::rng());
+
+        let txn = ctx.db.begin().await?;
+        // Delete old assignments if they exist.
         vote_assignments::Entity::delete_many()
-            .exec(&ctx.db)
+            .filter(vote_assignments::Column::CompetitionId.eq(competition.id))
+            .exec(&txn)
             .await?;
+        for assignment in assignments {
+            let new_assignment = vote_assignments::ActiveModel {
+                user_id: Set(assignment.reviewer_id),
+                submission_id: Set(assignment.submission_id),
+                competition_id: Set(competition.id),
+                ..Default::default()
+            };
+            debug!("{:?}", new_assignment);
+            new_assignment.insert(&txn).await?;
+        }
+        txn.commit().await?;
 
-        let n_submissions = all_submissions.len();
+        tracing::info!(competition = competition.slug, "{distribution}");
+        if !distribution.is_complete() {
+            tracing::warn!(
+                competition = competition.slug,
+                understaffed = ?distribution.understaffed,
+                "not every submission could get {} reviewers",
+                distribution.reviewers_per_submission
+            );
+        }
+        Ok(distribution)
+    }
+}
 
-        // Handle edge case: no submissions to assign
-        if n_submissions == 0 {
-            tracing::info!("No submissions to assign");
-            return Ok(());
+#[async_trait]
+impl Task for AssignSubmissions {
+    fn task(&self) -> TaskInfo {
+        TaskInfo {
+            name: "assign_submissions".to_string(),
+            detail: "Assigns submissions to users so they can vote on them. Accepts \
+                     reviewers=<per submission> and max_load=<per user> to override the \
+                     competition's settings."
+                .to_string(),
         }
+    }
+    async fn run(&self, ctx: &AppContext, vars: &task::Vars) -> Result<()> {
+        let competition = vars.cli_arg("competition").ok().map(String::as_str);
+        let Some(competition) = competitions::Model::try_resolve(&ctx.db, competition).await?
+        else {
+            println!("No competition found, skipping.");
+            return Ok(());
+        };
+        // run by hand the task may be used out of order, the phase then stays as is
+        let item = match competition.transition(Phase::Assigned, chrono::Utc::now().into()) {
+            Ok(item) => Some(item),
+            Err(err) => {
+                println!("Not moving the competition on: {err}");
+                None
+            }
+        };
 
-        // let n_users = all_submissions.iter().count();
-        let k = min(6, n_submissions - 1);
-        let txn = ctx.db.begin().await?;
+        let mut constraints = Self::constraints(ctx, &competition).await?;
+        if let Ok(reviewers) = vars.cli_arg("reviewers") {
+            constraints.reviewers_per_submission = reviewers
+                .parse()
+                .map_err(|_| Error::string(&format!("invalid reviewers: {reviewers}")))?;
+        }
+        if let Ok(max_load) = vars.cli_arg("max_load") {
+            constraints.max_per_reviewer = Some(
+                max_load
+                    .parse()
+                    .map_err(|_| Error::string(&format!("invalid max_load: {max_load}")))?,
+            );
+        }
 
-        for (u_i, user) in all_users.iter().enumerate() {
-            let mut reviews_assigned = 0;
-            let mut offset = 0;
-            while reviews_assigned < k {
-                let target_i = (u_i + offset) % n_submissions;
-                let target_submission = all_submissions[target_i].clone();
-                // user.user_id is the submitting user's ID (since user is a submission model from the join)
-                if target_submission.user_id != user.user_id {
-                    let new_assignment = vote_assignments::ActiveModel {
-                        user_id: Set(user.user_id),
-                        submission_id: Set(target_submission.id),
-                        ..Default::default()
-                    };
-                    debug!("{:?}", new_assignment);
-                    new_assignment.insert(&txn).await?;
-                    reviews_assigned += 1;
-                }
-                offset += 1;
-            }
+        let distribution = Self::assign_with(ctx, &competition, &constraints).await?;
+        let competition = match item {
+            Some(item) => item.update(&ctx.db).await?,
+            None => competition,
+        };
+        Event::new("competition.assign", "competition", Some(competition.id))
+            .competition(competition.id)
+            .after(&distribution)
+            .record(&ctx.db)
+            .await?;
+        println!("Assigned submissions successfully: {distribution}");
+        if !distribution.is_complete() {
+            println!("Understaffed submissions: {:?}", distribution.understaffed);
         }
-        txn.commit().await?;
-        println!("Assigned submissions successfully.");
         Ok(())
     }
 }
@@ -1,6 +1,6 @@
 use loco_rs::prelude::*;
 
-use crate::models::_entities::vote_assignments;
+use crate::models::{_entities::vote_assignments, audit_events::Event, competitions};
 
 pub struct ClearAssignments;
 #[async_trait]
@@ -11,10 +11,26 @@ impl Task for ClearAssignments {
             detail: "Task generator".to_string(),
         }
     }
-    async fn run(&self, ctx: &AppContext, _vars: &task::Vars) -> Result<()> {
-        vote_assignments::Entity::delete_many()
+    async fn run(&self, ctx: &AppContext, vars: &task::Vars) -> Result<()> {
+        let competition = vars.cli_arg("competition").ok().map(String::as_str);
+        let Some(competition) = competitions::Model::try_resolve(&ctx.db, competition).await?
+        else {
+            println!("No competition found, skipping.");
+            return Ok(());
+        };
+        let cleared = vote_assignments::Entity::delete_many()
+            .filter(vote_assignments::Column::CompetitionId.eq(competition.id))
             .exec(&ctx.db)
             .await?;
+        Event::new(
+            "competition.clear_assignments",
+            "competition",
+            Some(competition.id),
+        )
+        .competition(competition.id)
+        .after(&serde_json::json!({ "cleared": cleared.rows_affected }))
+        .record(&ctx.db)
+        .await?;
         println!("Assignments Cleared");
         Ok(())
     }
@@ -1,181 +1,259 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
 use loco_rs::prelude::*;
+use serde::Serialize;
 
-use crate::models::{_entities::votes, scores, submissions};
+use crate::{
+    aggregators::{self, Aggregator, Calibration},
+    models::{
+        _entities::{criterion_scores, scores, submissions, votes},
+        audit_events::Event,
+        competitions::{self, Phase},
+        vote_scores,
+    },
+};
 
-#[derive(Default)]
-struct CriterionScores<T> {
-    problem_fit: T,
-    clarity: T,
-    style_interpretation: T,
-    originality: T,
-    overall_quality: T,
+/// Convert a normalized 0-1 score to 0-1000 scale
+fn scale_to_1000(score: f32) -> i32 {
+    (score * 1000.0) as i32
 }
 
-/// Calculate median of a slice. Returns None if empty.
-fn median(values: &mut [i32]) -> Option<f32> {
-    if values.is_empty() {
-        return None;
-    }
-    values.sort();
-    let n = values.len();
-    if n % 2 == 1 {
-        Some(values[n / 2] as f32)
-    } else {
-        Some((values[n / 2 - 1] + values[n / 2]) as f32 / 2.0)
-    }
+/// Scale final score with higher precision to avoid collisions (0-10000)
+fn scale_final(score: f32) -> i32 {
+    (score * 10000.0) as i32
 }
 
-/// Convert a 1-5 score to 0-1000 scale (1→200, 5→1000)
-fn scale_to_1000(score: f32) -> i32 {
-    (score * 200.0) as i32
-}
+pub struct GenLeaderboard;
 
-/// Scale final score with higher precision to avoid collisions (1→2000, 5→10000)
-fn scale_final(score: f32) -> i32 {
-    (score * 2000.0) as i32
+/// What generating the leaderboard produced.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Leaderboard {
+    /// Number of scores the competition had before, which were replaced
+    pub replaced: u64,
+    /// Number of scored submissions
+    pub scores: usize,
+    /// Number of votes the scores are based on
+    pub votes: usize,
+    /// Submissions left without a score as nobody voted on them
+    pub unvoted: Vec<i32>,
 }
 
-// Weights for each criterion (must sum to 1.0)
-const WEIGHT_PROBLEM_FIT: f32 = 0.25;
-const WEIGHT_CLARITY: f32 = 0.20;
-const WEIGHT_STYLE: f32 = 0.20;
-const WEIGHT_ORIGINALITY: f32 = 0.15;
-const WEIGHT_OVERALL: f32 = 0.20;
+impl fmt::Display for Leaderboard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} submissions scored from {} votes, {} submissions without votes, {} \
+             previous scores replaced",
+            self.scores,
+            self.votes,
+            self.unvoted.len(),
+            self.replaced
+        )
+    }
+}
 
-pub struct GenLeaderboard;
-#[async_trait]
-impl Task for GenLeaderboard {
-    fn task(&self) -> TaskInfo {
-        TaskInfo {
-            name: "gen_leaderboard".to_string(),
-            detail: "Task generator".to_string(),
-        }
+impl GenLeaderboard {
+    /// Generates the scores of every submission in `competition` from its votes using
+    /// the aggregator and calibration configured for the competition.
+    pub async fn generate(
+        ctx: &AppContext,
+        competition: &competitions::Model,
+    ) -> Result<Leaderboard> {
+        let aggregator = aggregators::from_name(&competition.aggregator).ok_or_else(|| {
+            Error::string(&format!("unknown aggregator {}", competition.aggregator))
+        })?;
+        Self::generate_with(
+            ctx,
+            competition,
+            aggregator.as_ref(),
+            competition.calibrate_voters,
+        )
+        .await
     }
-    async fn run(&self, ctx: &AppContext, _vars: &task::Vars) -> Result<()> {
+
+    /// Generates the scores of every finalised submission in `competition` from its
+    /// votes, replacing any scores the competition already had. Each criterion of the
+    /// competition's rubric is aggregated separately and weighted into the final score.
+    /// With `calibrate`, every voter's scores are first corrected for their
+    /// harshness/leniency, see [`Calibration`].
+    pub async fn generate_with(
+        ctx: &AppContext,
+        competition: &competitions::Model,
+        aggregator: &dyn Aggregator,
+        calibrate: bool,
+    ) -> Result<Leaderboard> {
         // clear scores if they exist already
-        scores::Entity::delete_many().exec(&ctx.db).await?;
-
-        let all_votes = votes::Entity::find().all(&ctx.db).await?;
-
-        // Calculate global means for Bayesian smoothing
-        let mut global_problem_fit = Vec::new();
-        let mut global_clarity = Vec::new();
-        let mut global_style = Vec::new();
-        let mut global_originality = Vec::new();
-        let mut global_overall = Vec::new();
-
-        for vote in &all_votes {
-            global_problem_fit.push(vote.problem_fit_score);
-            global_clarity.push(vote.clarity_score);
-            global_style.push(vote.style_interpretation_score);
-            global_originality.push(vote.originality_score);
-            global_overall.push(vote.overall_quality_score);
-        }
+        let replaced = scores::Entity::delete_many()
+            .filter(scores::Column::CompetitionId.eq(competition.id))
+            .exec(&ctx.db)
+            .await?;
 
-        let global_means = CriterionScores::<f32> {
-            problem_fit: mean(&global_problem_fit).unwrap_or(0.0),
-            clarity: mean(&global_clarity).unwrap_or(0.0),
-            style_interpretation: mean(&global_style).unwrap_or(0.0),
-            originality: mean(&global_originality).unwrap_or(0.0),
-            overall_quality: mean(&global_overall).unwrap_or(0.0),
-        };
+        // withdrawn and disqualified submissions are left out, along with their votes
+        let all_submissions = submissions::Entity::find_finalised(competition.id)
+            .all(&ctx.db)
+            .await?;
+        let ranked: HashSet<i32> = all_submissions.iter().map(|item| item.id).collect();
 
-        let all_submissions = submissions::Entity::find().all(&ctx.db).await?;
-        for submission in all_submissions {
-            let submission_votes: Vec<&votes::Model> = all_votes
-                .iter()
-                .filter(|v| v.submission_id == submission.id)
-                .collect();
+        let ratings = vote_scores::Entity::find_ratings(&ctx.db, competition.id).await?;
+        let rubric: Vec<_> = ratings.iter().map(|(criterion, _)| criterion).collect();
+        let total_weight: f32 = rubric.iter().map(|criterion| criterion.weight).sum();
+
+        let calibration = calibrate.then(|| {
+            Calibration::estimate(
+                &ratings
+                    .iter()
+                    .map(|(criterion, ratings)| (criterion.scale(), ratings.clone()))
+                    .collect::<Vec<_>>(),
+            )
+        });
+
+        // Aggregate each criterion on its own; the results are normalized to 0-1 so
+        // that criteria with different ranges can be weighted together
+        let aggregated: HashMap<i32, HashMap<i32, f32>> = ratings
+            .iter()
+            .map(|(criterion, criterion_ratings)| {
+                let scale = criterion.scale();
+                let scores = match &calibration {
+                    Some(calibration) => aggregator
+                        .aggregate(&calibration.calibrate(scale, criterion_ratings), scale),
+                    None => aggregator.aggregate(criterion_ratings, scale),
+                };
+                (criterion.id, scores)
+            })
+            .collect();
+
+        let all_votes: Vec<_> = votes::Entity::find()
+            .filter(votes::Column::CompetitionId.eq(competition.id))
+            .all(&ctx.db)
+            .await?
+            .into_iter()
+            .filter(|vote| ranked.contains(&vote.submission_id))
+            .collect();
 
+        let voted: HashSet<i32> = all_votes.iter().map(|vote| vote.submission_id).collect();
+        let mut leaderboard = Leaderboard {
+            replaced: replaced.rows_affected,
+            votes: all_votes.len(),
+            ..Default::default()
+        };
+        for submission in all_submissions {
             // Skip submissions with no votes
-            if submission_votes.is_empty() {
+            if !voted.contains(&submission.id) {
                 println!("Skipping submission {} - no votes received", submission.id);
+                leaderboard.unvoted.push(submission.id);
                 continue;
             }
 
-            let mut aggregate_scores: CriterionScores<Vec<i32>> = Default::default();
-            for vote in &submission_votes {
-                aggregate_scores.problem_fit.push(vote.problem_fit_score);
-                aggregate_scores.clarity.push(vote.clarity_score);
-                aggregate_scores
-                    .style_interpretation
-                    .push(vote.style_interpretation_score);
-                aggregate_scores.originality.push(vote.originality_score);
-                aggregate_scores
-                    .overall_quality
-                    .push(vote.overall_quality_score);
+            let mut weighted_sum = 0.0;
+            let mut criterion_results = Vec::with_capacity(rubric.len());
+            for criterion in &rubric {
+                let score = aggregated
+                    .get(&criterion.id)
+                    .and_then(|scores| scores.get(&submission.id))
+                    .copied()
+                    .unwrap_or(0.0);
+                weighted_sum += score * criterion.weight;
+                criterion_results.push((criterion.id, score));
             }
-
-            // Calculate raw median scores (1-5 scale)
-            let raw_medians = CriterionScores::<f32> {
-                problem_fit: median(&mut aggregate_scores.problem_fit).unwrap_or(0.0),
-                clarity: median(&mut aggregate_scores.clarity).unwrap_or(0.0),
-                style_interpretation: median(&mut aggregate_scores.style_interpretation)
-                    .unwrap_or(0.0),
-                originality: median(&mut aggregate_scores.originality).unwrap_or(0.0),
-                overall_quality: median(&mut aggregate_scores.overall_quality).unwrap_or(0.0),
-            };
-
-            // Apply Bayesian smoothing
-            // Score = (v / (v + m)) * R + (m / (v + m)) * C
-            // v = number of votes
-            // m = smoothing factor (dummy votes)
-            // R = local median
-            // C = global mean
-            let v = submission_votes.len() as f32;
-            const M: f32 = 2.0; // Smoothing factor
-
-            let bayesian_score = |local_median: f32, global_mean: f32| -> f32 {
-                (v / (v + M)) * local_median + (M / (v + M)) * global_mean
-            };
-
-            let smoothed_scores = CriterionScores::<f32> {
-                problem_fit: bayesian_score(raw_medians.problem_fit, global_means.problem_fit),
-                clarity: bayesian_score(raw_medians.clarity, global_means.clarity),
-                style_interpretation: bayesian_score(
-                    raw_medians.style_interpretation,
-                    global_means.style_interpretation,
-                ),
-                originality: bayesian_score(raw_medians.originality, global_means.originality),
-                overall_quality: bayesian_score(
-                    raw_medians.overall_quality,
-                    global_means.overall_quality,
-                ),
+            let weighted_average = if total_weight > 0.0 {
+                weighted_sum / total_weight
+            } else {
+                0.0
             };
 
-            // Calculate weighted average (still 1-5 scale) using SMOOTHED scores
-            let weighted_average = smoothed_scores.problem_fit * WEIGHT_PROBLEM_FIT
-                + smoothed_scores.clarity * WEIGHT_CLARITY
-                + smoothed_scores.style_interpretation * WEIGHT_STYLE
-                + smoothed_scores.originality * WEIGHT_ORIGINALITY
-                + smoothed_scores.overall_quality * WEIGHT_OVERALL;
-
-            // Store scores scaled to 0-1000 (so 5/5 = 1000, 1/5 = 200)
+            // Store criterion scores scaled to 0-1000 (so max = 1000)
             // Final score uses higher precision (0-10000) to reduce ranking collisions
             let score = scores::ActiveModel {
                 submission_id: Set(submission.id),
-                problem_fit_score: Set(scale_to_1000(smoothed_scores.problem_fit)),
-                visual_clarity_score: Set(scale_to_1000(smoothed_scores.clarity)),
-                style_interpretation_score: Set(scale_to_1000(
-                    smoothed_scores.style_interpretation,
-                )),
-                originality_score: Set(scale_to_1000(smoothed_scores.originality)),
-                overall_quality_score: Set(scale_to_1000(smoothed_scores.overall_quality)),
+                competition_id: Set(competition.id),
                 final_score: Set(scale_final(weighted_average)),
                 ..Default::default()
             };
-            score.insert(&ctx.db).await?;
+            let score = score.insert(&ctx.db).await?;
+            leaderboard.scores += 1;
+
+            let rows: Vec<_> = criterion_results
+                .into_iter()
+                .map(
+                    |(criterion_id, score_value)| criterion_scores::ActiveModel {
+                        score_id: Set(score.id),
+                        criterion_id: Set(criterion_id),
+                        score: Set(scale_to_1000(score_value)),
+                        ..Default::default()
+                    },
+                )
+                .collect();
+            if !rows.is_empty() {
+                criterion_scores::Entity::insert_many(rows)
+                    .exec(&ctx.db)
+                    .await?;
+            }
         }
-        println!("Generated Leaderboard successfully.");
-        Ok(())
+        leaderboard.unvoted.sort_unstable();
+        tracing::info!(competition = competition.slug, "{leaderboard}");
+        Ok(leaderboard)
     }
 }
 
-/// Calculate mean of a slice. Returns None if empty.
-fn mean(values: &[i32]) -> Option<f32> {
-    if values.is_empty() {
-        return None;
+#[async_trait]
+impl Task for GenLeaderboard {
+    fn task(&self) -> TaskInfo {
+        TaskInfo {
+            name: "gen_leaderboard".to_string(),
+            detail: "Task generator".to_string(),
+        }
+    }
+    async fn run(&self, ctx: &AppContext, vars: &task::Vars) -> Result<()> {
+        let competition = vars.cli_arg("competition").ok().map(String::as_str);
+        let Some(competition) = competitions::Model::try_resolve(&ctx.db, competition).await?
+        else {
+            println!("No competition found, skipping.");
+            return Ok(());
+        };
+        // run by hand the task may be used out of order, the phase then stays as is
+        let item = match competition.transition(Phase::Scored, chrono::Utc::now().into()) {
+            Ok(item) => Some(item),
+            Err(err) => {
+                println!("Not moving the competition on: {err}");
+                None
+            }
+        };
+
+        // the competition's settings can be overridden to compare rankings
+        let name = vars
+            .cli_arg("aggregator")
+            .map_or(competition.aggregator.as_str(), String::as_str);
+        let aggregator = aggregators::from_name(name).ok_or_else(|| {
+            Error::string(&format!(
+                "unknown aggregator {name}, expected one of {}",
+                aggregators::NAMES.join(", ")
+            ))
+        })?;
+        let calibrate = vars
+            .cli_arg("calibrate")
+            .map_or(competition.calibrate_voters, |calibrate| {
+                calibrate == "true"
+            });
+
+        let leaderboard =
+            Self::generate_with(ctx, &competition, aggregator.as_ref(), calibrate).await?;
+        let competition = match item {
+            Some(item) => item.update(&ctx.db).await?,
+            None => competition,
+        };
+        Event::new(
+            "competition.generate_scores",
+            "competition",
+            Some(competition.id),
+        )
+        .competition(competition.id)
+        .after(&leaderboard)
+        .record(&ctx.db)
+        .await?;
+        println!("Generated Leaderboard successfully: {leaderboard}");
+        Ok(())
     }
-    let sum: i32 = values.iter().sum();
-    Some(sum as f32 / values.len() as f32)
 }
@@ -2,8 +2,9 @@ pub mod add_users;
 pub mod assign_submissions;
 pub mod clear_assignments;
 pub mod gen_leaderboard;
+pub mod rebalance_assignments;
 
 pub mod show_leaderboard;
 pub mod update_timings;
 
-pub mod assign_and_gen;
\ No newline at end of file
+pub mod assign_and_gen;
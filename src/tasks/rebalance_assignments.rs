@@ -0,0 +1,225 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+use chrono::{DateTime, Duration, Utc};
+use loco_rs::prelude::*;
+use serde::Serialize;
+
+use crate::{
+    assignment::{self, Assignment, Constraints, Reviewer, Submission},
+    models::{
+        _entities::{submissions, vote_assignments, votes},
+        audit_events::Event,
+        competitions,
+    },
+    tasks::assign_submissions::AssignSubmissions,
+};
+
+pub struct RebalanceAssignments;
+
+/// What a rebalance found and changed.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Rebalance {
+    /// Assignments nobody voted on in time, kept in case the reviewer still votes
+    pub stale: usize,
+    /// Reviewers with stale assignments, who are not given any more
+    pub inactive_reviewers: Vec<i32>,
+    /// The assignments added to make up for stale ones
+    pub added: Vec<Assignment>,
+    /// Submissions that still lack reviewers
+    pub understaffed: Vec<i32>,
+}
+
+impl fmt::Display for Rebalance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} stale assignments of {} inactive reviewers, {} assignments added, {} \
+             submissions understaffed",
+            self.stale,
+            self.inactive_reviewers.len(),
+            self.added.len(),
+            self.understaffed.len()
+        )
+    }
+}
+
+impl RebalanceAssignments {
+    /// Tops up submissions of `competition` whose reviewers have not voted within
+    /// the competition's `reassign_after_hours`.
+    pub async fn rebalance(
+        ctx: &AppContext,
+        competition: &competitions::Model,
+    ) -> Result<Rebalance> {
+        let constraints = AssignSubmissions::constraints(ctx, competition).await?;
+        let after = Duration::hours(i64::from(competition.reassign_after_hours));
+        Self::rebalance_with(ctx, competition, &constraints, after, Utc::now()).await
+    }
+
+    /// Like [`RebalanceAssignments::rebalance`], with the given constraints and
+    /// time after which an assignment without vote counts as stale. Assignments
+    /// only start aging once voting started, and nothing changes outside of the
+    /// voting period.
+    pub async fn rebalance_with(
+        ctx: &AppContext,
+        competition: &competitions::Model,
+        constraints: &Constraints,
+        after: Duration,
+        now: DateTime<Utc>,
+    ) -> Result<Rebalance> {
+        let Some(voting_start) = competition.voting_start.map(|start| start.to_utc()) else {
+            return Ok(Rebalance::default());
+        };
+        let voting_over = competition.voting_end.is_some_and(|end| now > end.to_utc());
+        if now < voting_start || voting_over {
+            return Ok(Rebalance::default());
+        }
+
+        let assignments = vote_assignments::Entity::find()
+            .filter(vote_assignments::Column::CompetitionId.eq(competition.id))
+            .all(&ctx.db)
+            .await?;
+        let voted: HashSet<(i32, i32)> = votes::Entity::find()
+            .filter(votes::Column::CompetitionId.eq(competition.id))
+            .all(&ctx.db)
+            .await?
+            .into_iter()
+            .map(|vote| (vote.user_id, vote.submission_id))
+            .collect();
+
+        let mut rebalance = Rebalance::default();
+        let mut inactive = HashSet::new();
+        let mut active_reviews: HashMap<i32, usize> = HashMap::new();
+        let mut loads: HashMap<i32, usize> = HashMap::new();
+        for assignment in &assignments {
+            *loads.entry(assignment.user_id).or_default() += 1;
+            let has_voted = voted.contains(&(assignment.user_id, assignment.submission_id));
+            let assigned_at = assignment.created_at.to_utc().max(voting_start);
+            if has_voted || now < assigned_at + after {
+                *active_reviews.entry(assignment.submission_id).or_default() += 1;
+            } else {
+                rebalance.stale += 1;
+                inactive.insert(assignment.user_id);
+            }
+        }
+
+        let all_submissions = submissions::Entity::find_finalised(competition.id)
+            .all(&ctx.db)
+            .await?;
+        let needs: Vec<(Submission, usize)> = all_submissions
+            .iter()
+            .filter_map(|submission| {
+                let active = active_reviews.get(&submission.id).copied().unwrap_or(0);
+                let missing = constraints.reviewers_per_submission.saturating_sub(active);
+                (missing > 0).then_some((
+                    Submission {
+                        id: submission.id,
+                        author_id: submission.user_id,
+                    },
+                    missing,
+                ))
+            })
+            .collect();
+        let reviewers: Vec<Reviewer> = AssignSubmissions::reviewers(ctx, competition)
+            .await?
+            .into_iter()
+            .filter(|id| !inactive.contains(id))
+            .map(|id| Reviewer {
+                id,
+                load: loads.get(&id).copied().unwrap_or(0),
+            })
+            .collect();
+        let existing: HashSet<Assignment> = assignments
+            .iter()
+            .map(|assignment| Assignment {
+                reviewer_id: assignment.user_id,
+                submission_id: assignment.submission_id,
+            })
+            .collect();
+
+        rebalance.added =
+            assignment::top_up(&needs, &reviewers, &existing, constraints, &mut rand::rng());
+
+        let txn = ctx.db.begin().await?;
+        for assignment in &rebalance.added {
+            vote_assignments::ActiveModel {
+                user_id: Set(assignment.reviewer_id),
+                submission_id: Set(assignment.submission_id),
+                competition_id: Set(competition.id),
+                ..Default::default()
+            }
+            .insert(&txn)
+            .await?;
+        }
+        txn.commit().await?;
+
+        let mut added: HashMap<i32, usize> = HashMap::new();
+        for assignment in &rebalance.added {
+            *added.entry(assignment.submission_id).or_default() += 1;
+        }
+        rebalance.understaffed = needs
+            .iter()
+            .filter(|(submission, missing)| {
+                added.get(&submission.id).copied().unwrap_or(0) < *missing
+            })
+            .map(|(submission, _)| submission.id)
+            .collect();
+        rebalance.understaffed.sort_unstable();
+        rebalance.inactive_reviewers = inactive.into_iter().collect();
+        rebalance.inactive_reviewers.sort_unstable();
+
+        if !rebalance.added.is_empty() {
+            Event::new("competition.rebalance", "competition", Some(competition.id))
+                .competition(competition.id)
+                .after(&rebalance)
+                .record(&ctx.db)
+                .await?;
+        }
+        tracing::info!(competition = competition.slug, "{rebalance}");
+        Ok(rebalance)
+    }
+}
+
+#[async_trait]
+impl Task for RebalanceAssignments {
+    fn task(&self) -> TaskInfo {
+        TaskInfo {
+            name: "rebalance_assignments".to_string(),
+            detail: "Tops up submissions whose reviewers have not voted in time with extra \
+                     reviewers, keeping all existing assignments. Accepts after=<hours> to \
+                     override the competition's reassign_after_hours."
+                .to_string(),
+        }
+    }
+    async fn run(&self, ctx: &AppContext, vars: &task::Vars) -> Result<()> {
+        let competition = vars.cli_arg("competition").ok().map(String::as_str);
+        let Some(competition) = competitions::Model::try_resolve(&ctx.db, competition).await?
+        else {
+            println!("No competition found, skipping.");
+            return Ok(());
+        };
+
+        let constraints = AssignSubmissions::constraints(ctx, &competition).await?;
+        let hours = match vars.cli_arg("after") {
+            Ok(after) => after
+                .parse()
+                .map_err(|_| Error::string(&format!("invalid after: {after}")))?,
+            Err(_) => competition.reassign_after_hours,
+        };
+        let rebalance = Self::rebalance_with(
+            ctx,
+            &competition,
+            &constraints,
+            Duration::hours(i64::from(hours)),
+            Utc::now(),
+        )
+        .await?;
+        println!("Rebalanced assignments: {rebalance}");
+        if !rebalance.understaffed.is_empty() {
+            println!("Understaffed submissions: {:?}", rebalance.understaffed);
+        }
+        Ok(())
+    }
+}
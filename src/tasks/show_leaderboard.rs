@@ -1,8 +1,30 @@
 use loco_rs::prelude::*;
 
-use crate::models::configs;
+use crate::models::{
+    audit_events::Event,
+    competitions::{self, Phase},
+};
 
 pub struct ShowLeaderboard;
+
+impl ShowLeaderboard {
+    /// Shows or hides the leaderboard of `competition` on the frontend by moving it
+    /// to or back from the published phase.
+    pub async fn set(
+        ctx: &AppContext,
+        competition: competitions::Model,
+        show: bool,
+    ) -> Result<competitions::Model> {
+        let item = if show {
+            competition.transition(Phase::Published, chrono::Utc::now().into())
+        } else {
+            competition.unpublish()
+        };
+        let item = item.map_err(|err| Error::string(&err.to_string()))?;
+        Ok(item.update(&ctx.db).await?)
+    }
+}
+
 #[async_trait]
 impl Task for ShowLeaderboard {
     fn task(&self) -> TaskInfo {
@@ -13,25 +35,38 @@ impl Task for ShowLeaderboard {
     }
     async fn run(&self, ctx: &AppContext, vars: &task::Vars) -> Result<()> {
         let show = vars.cli_arg("show");
-        let config = configs::Entity::find().one(&ctx.db).await?;
-        if let Some(config) = config {
-            let mut config = config.into_active_model();
-            if let Ok(show) = show {
-                match show.to_lowercase().as_str() {
-                    "true" => {
-                        config.show_leaderboard = Set(true);
-                    }
-                    "false" => {
-                        config.show_leaderboard = Set(false);
-                    }
-                    _ => tracing::error!("Invalid value for show argument."),
-                };
-            } else {
-                config.show_leaderboard = Set(true);
+        let competition = vars.cli_arg("competition").ok().map(String::as_str);
+        let competition = competitions::Model::try_resolve(&ctx.db, competition).await?;
+        if let Some(competition) = competition {
+            let show = match show.map(|show| show.to_lowercase()) {
+                Ok(show) if show == "true" => Some(true),
+                Ok(show) if show == "false" => Some(false),
+                Ok(_) => {
+                    tracing::error!("Invalid value for show argument.");
+                    None
+                }
+                Err(_) => Some(true),
+            };
+            if let Some(show) = show {
+                let before = competition.is_published();
+                if before == show {
+                    println!("Show Leaderboard unchanged.");
+                    return Ok(());
+                }
+                let competition = Self::set(ctx, competition, show).await?;
+                Event::new(
+                    "competition.show_leaderboard",
+                    "competition",
+                    Some(competition.id),
+                )
+                .competition(competition.id)
+                .before(&serde_json::json!({ "show_leaderboard": before }))
+                .after(&serde_json::json!({ "show_leaderboard": show }))
+                .record(&ctx.db)
+                .await?;
             }
-            config.save(&ctx.db).await?;
         } else {
-            tracing::error!("Configs is empty.");
+            tracing::error!("No competition found.");
         }
         println!("Show Leaderboard updated.");
         Ok(())
@@ -2,9 +2,9 @@ use std::str::FromStr;
 
 use chrono::{Local, TimeDelta};
 use loco_rs::prelude::*;
-use sea_orm::prelude::ChronoDateTimeWithTimeZone;
+use sea_orm::{prelude::ChronoDateTimeWithTimeZone, TryIntoModel};
 
-use crate::models::configs;
+use crate::models::{audit_events::Event, competitions};
 
 pub struct UpdateTimings;
 #[async_trait]
@@ -16,6 +16,8 @@ impl Task for UpdateTimings {
         }
     }
     /// ss = submission_start, se = submission_end, vs = voting_start, ve = voting_end, auto = autogen
+    /// competition = id or slug of the competition to update (defaults to the current one),
+    /// name = start a new competition with this name instead
     async fn run(&self, ctx: &AppContext, vars: &task::Vars) -> Result<()> {
         let submission_start = vars.cli_arg("ss");
         let submission_end = vars.cli_arg("se");
@@ -24,14 +26,21 @@ impl Task for UpdateTimings {
         let auto_gen = vars.cli_arg("auto");
         let period = vars.cli_arg("p"); //Period for autogen
 
-        let config = configs::Entity::find().one(&ctx.db).await?;
-        let mut config = if let Some(config) = config {
+        let competition = vars.cli_arg("competition").ok().map(String::as_str);
+        let name = vars.cli_arg("name");
+
+        let before = if name.is_ok() {
+            None
+        } else {
+            competitions::Model::try_resolve(&ctx.db, competition).await?
+        };
+        let mut config = if let Some(config) = before.clone() {
             config.into_active_model()
         } else {
-            configs::ActiveModel {
-                show_leaderboard: Set(false),
-                assigned: Set(false),
-                created_scores: Set(false),
+            let name = name.map_or("Bit by Design", String::as_str);
+            competitions::ActiveModel {
+                name: Set(name.to_string()),
+                slug: Set(competitions::slugify(name)),
                 ..Default::default()
             }
         };
@@ -70,7 +79,27 @@ impl Task for UpdateTimings {
                 ));
             }
         }
-        config.save(&ctx.db).await?;
+        // refuse to e.g. start voting before submissions have closed
+        let timings = competitions::Timings {
+            submission_start: config.submission_start.try_as_ref().copied().flatten(),
+            submission_end: config.submission_end.try_as_ref().copied().flatten(),
+            voting_start: config.voting_start.try_as_ref().copied().flatten(),
+            voting_end: config.voting_end.try_as_ref().copied().flatten(),
+        };
+        timings.validate().map_err(|err| Error::string(&err))?;
+        let config = config.save(&ctx.db).await?.try_into_model()?;
+        let event = match &before {
+            Some(before) => {
+                Event::new("competition.update_timings", "competition", Some(config.id))
+                    .before(before)
+            }
+            None => Event::new("competition.create", "competition", Some(config.id)),
+        };
+        event
+            .competition(config.id)
+            .after(&config)
+            .record(&ctx.db)
+            .await?;
         println!("Updated competition timings.");
         Ok(())
     }
@@ -0,0 +1,128 @@
+//! Validation of request params, reported per field so clients can show every
+//! problem next to the input it belongs to:
+//!
+//! ```json
+//! {"errors": {"layout_explanation": [{"code": "length", "message": "..."}]}}
+//! ```
+//!
+//! Params deriving [`validator::Validate`] get this through loco's
+//! `JsonValidateWithMessage` extractor, checks that need the database report the same
+//! way through [`FieldErrors`].
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+};
+
+use loco_rs::{
+    validation::{ModelValidationErrors, ValidationError as FieldError},
+    Error, Result,
+};
+use validator::ValidationError;
+
+/// Field level errors collected while validating params.
+#[derive(Debug, Default)]
+pub struct FieldErrors {
+    errors: BTreeMap<String, Vec<FieldError>>,
+}
+
+impl FieldErrors {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records that `field` failed the check named `code`.
+    pub fn add(&mut self, field: impl Into<String>, code: &str, message: impl Into<String>) {
+        self.add_with_params(field, code, message, HashMap::new());
+    }
+
+    /// Like [`FieldErrors::add`], along with the values the check involved.
+    pub fn add_with_params(
+        &mut self,
+        field: impl Into<String>,
+        code: &str,
+        message: impl Into<String>,
+        params: HashMap<String, serde_json::Value>,
+    ) {
+        self.errors
+            .entry(field.into())
+            .or_default()
+            .push(FieldError {
+                code: code.to_string(),
+                message: Some(message.into()),
+                params,
+            });
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// A single failing field.
+    #[must_use]
+    pub fn single(field: impl Into<String>, code: &str, message: impl Into<String>) -> Error {
+        let mut errors = Self::new();
+        errors.add(field, code, message);
+        errors.into()
+    }
+
+    /// Fails with the collected errors, if any.
+    ///
+    /// # Errors
+    ///
+    /// When any field failed
+    pub fn into_result(self) -> Result<()> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self.into())
+        }
+    }
+}
+
+impl From<FieldErrors> for Error {
+    fn from(errors: FieldErrors) -> Self {
+        Self::Validation(ModelValidationErrors {
+            errors: errors.errors,
+        })
+    }
+}
+
+/// A [`ValidationError`] with a message, for `custom` validators.
+#[must_use]
+pub fn error(code: &'static str, message: impl Into<Cow<'static, str>>) -> ValidationError {
+    ValidationError::new(code).with_message(message.into())
+}
+
+/// Confirmations that have to be given.
+///
+/// # Errors
+///
+/// When `value` is false
+pub fn must_be_true(value: &bool) -> std::result::Result<(), ValidationError> {
+    if *value {
+        Ok(())
+    } else {
+        Err(error("must_be_true", "must be confirmed"))
+    }
+}
+
+/// Links either to another site over http(s) or to a path of this app, like the
+/// URLs of uploaded images.
+///
+/// # Errors
+///
+/// When `value` is neither
+pub fn http_url_or_path(value: &str) -> std::result::Result<(), ValidationError> {
+    let valid = if value.starts_with('/') {
+        !value.starts_with("//") && !value.contains(char::is_whitespace)
+    } else {
+        url::Url::parse(value).is_ok_and(|url| matches!(url.scheme(), "http" | "https"))
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(error("url", "must be an http(s) URL"))
+    }
+}
@@ -0,0 +1,27 @@
+use serde::Serialize;
+
+use crate::models::competitions::{Model, Phase};
+
+/// A competition along with the phase it is in, so clients don't have to work it
+/// out from the timings
+#[derive(Debug, Serialize)]
+pub struct CompetitionResponse {
+    #[serde(flatten)]
+    pub competition: Model,
+    pub phase: Phase,
+    /// Whether the leaderboard is published, kept for clients reading the old flag
+    pub show_leaderboard: bool,
+}
+
+impl CompetitionResponse {
+    #[must_use]
+    pub fn new(competition: Model, now: sea_orm::prelude::DateTimeWithTimeZone) -> Self {
+        let phase = competition.phase(now);
+        let show_leaderboard = competition.is_published();
+        Self {
+            competition,
+            phase,
+            show_leaderboard,
+        }
+    }
+}
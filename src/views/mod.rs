@@ -1 +1,4 @@
 pub mod auth;
+pub mod competitions;
+pub mod transitions;
+pub mod users;
@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::_entities::audit_events;
+
+/// A phase transition of a competition, read from its audit event
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TransitionResponse {
+    pub id: i32,
+    /// The action run, e.g. `assign`
+    pub action: String,
+    pub competition_id: Option<i32>,
+    /// The admin who ran it, `None` for the scheduler and tasks
+    pub user_id: Option<i32>,
+    pub summary: Option<serde_json::Value>,
+    pub created_at: String,
+}
+
+impl TransitionResponse {
+    #[must_use]
+    pub fn new(event: audit_events::Model) -> Self {
+        let action = event
+            .action
+            .strip_prefix("competition.")
+            .unwrap_or(&event.action)
+            .to_string();
+        Self {
+            id: event.id,
+            action,
+            competition_id: event.competition_id,
+            user_id: event.actor_id,
+            summary: event.after,
+            created_at: event.created_at.to_rfc3339(),
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::_entities::users;
+
+/// A user as seen by admins, without their login secrets
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UserResponse {
+    pub id: i32,
+    pub pid: String,
+    pub name: String,
+    pub email: String,
+    pub is_admin: bool,
+    pub created_at: String,
+}
+
+impl UserResponse {
+    #[must_use]
+    pub fn new(user: &users::Model, is_admin: bool) -> Self {
+        Self {
+            id: user.id,
+            pid: user.pid.to_string(),
+            name: user.name.clone(),
+            email: user.email.clone(),
+            is_admin,
+            created_at: user.created_at.to_rfc3339(),
+        }
+    }
+}
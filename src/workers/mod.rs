@@ -1 +1 @@
-pub mod downloader;
+pub mod previews;
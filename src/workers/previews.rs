@@ -0,0 +1,116 @@
+//! Generates the gallery thumbnails and blurred placeholder of a submission's
+//! design image, so the voting gallery doesn't load full-size images for every card.
+use std::time::Duration;
+
+use loco_rs::prelude::*;
+use sea_orm::sea_query::Expr;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    images,
+    models::{_entities::submissions::Column, submissions},
+};
+
+/// How often generating the previews is attempted before giving up.
+pub const MAX_ATTEMPTS: u32 = 3;
+/// The delay before the first retry, doubled for every further one.
+const RETRY_DELAY: Duration = Duration::from_secs(2);
+
+pub struct PreviewWorker {
+    pub ctx: AppContext,
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+pub struct PreviewWorkerArgs {
+    pub submission_id: i32,
+}
+
+/// A gallery thumbnail as recorded on the submission.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct GalleryImage {
+    pub width: u32,
+    pub url: String,
+}
+
+#[async_trait]
+impl BackgroundWorker<PreviewWorkerArgs> for PreviewWorker {
+    fn build(ctx: &AppContext) -> Self {
+        Self { ctx: ctx.clone() }
+    }
+
+    async fn perform(&self, args: PreviewWorkerArgs) -> Result<()> {
+        let mut attempt = 1;
+        loop {
+            match self.generate(args.submission_id).await {
+                Ok(()) => return Ok(()),
+                Err(err) if attempt < MAX_ATTEMPTS => {
+                    tracing::warn!(
+                        submission_id = args.submission_id,
+                        attempt,
+                        error = %err,
+                        "generating previews failed, retrying"
+                    );
+                    tokio::time::sleep(RETRY_DELAY * 2u32.pow(attempt - 1)).await;
+                    attempt += 1;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+impl PreviewWorker {
+    async fn generate(&self, submission_id: i32) -> Result<()> {
+        let Some(submission) = submissions::Entity::find_by_id(submission_id)
+            .one(&self.ctx.db)
+            .await?
+        else {
+            return Ok(());
+        };
+        let Some((id, file)) = images::parse_url(&self.ctx, &submission.design_image) else {
+            tracing::debug!(submission_id, "design image was not uploaded, no previews");
+            return Ok(());
+        };
+
+        let original: Vec<u8> = self.ctx.storage.download(&images::path(&id, &file)).await?;
+        let previews = tokio::task::spawn_blocking(move || images::previews(&original))
+            .await
+            .map_err(Error::wrap)??;
+
+        let mut gallery = Vec::new();
+        for (width, content) in previews.gallery {
+            let file = images::gallery_file(width);
+            self.ctx
+                .storage
+                .upload(&images::path(&id, &file), &content)
+                .await?;
+            gallery.push(GalleryImage {
+                width,
+                url: images::url(&self.ctx, &id, &file),
+            });
+        }
+        self.ctx
+            .storage
+            .upload(
+                &images::path(&id, images::PLACEHOLDER),
+                &previews.placeholder,
+            )
+            .await?;
+
+        // the design image may have been replaced while generating its previews
+        submissions::Entity::update_many()
+            .col_expr(
+                Column::GalleryImages,
+                Expr::value(serde_json::to_value(gallery)?),
+            )
+            .col_expr(
+                Column::PlaceholderImage,
+                Expr::value(images::url(&self.ctx, &id, images::PLACEHOLDER)),
+            )
+            .filter(Column::Id.eq(submission_id))
+            .filter(Column::DesignImage.eq(submission.design_image))
+            .exec(&self.ctx.db)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,238 @@
+use std::collections::HashMap;
+
+use bit_by_design::aggregators::{
+    self, Aggregator, BradleyTerry, Calibration, MedianBayesian, Rating, Scale, TrimmedMean, ZScore,
+};
+
+const SCALE: Scale = Scale { min: 0.0, max: 5.0 };
+
+/// Builds ratings from `(voter_id, submission_id, score)` triples.
+fn ratings(votes: &[(i32, i32, f32)]) -> Vec<Rating> {
+    votes
+        .iter()
+        .map(|&(voter_id, submission_id, score)| Rating {
+            voter_id,
+            submission_id,
+            score,
+        })
+        .collect()
+}
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!(
+        (actual - expected).abs() < 1e-4,
+        "expected {expected}, got {actual}"
+    );
+}
+
+/// Submission ids ordered from best to worst.
+fn ranking(scores: &HashMap<i32, f32>) -> Vec<i32> {
+    let mut ids: Vec<i32> = scores.keys().copied().collect();
+    ids.sort_by(|a, b| scores[b].total_cmp(&scores[a]).then(a.cmp(b)));
+    ids
+}
+
+#[test]
+fn looks_up_aggregators_by_name() {
+    for name in aggregators::NAMES {
+        let aggregator = aggregators::from_name(name).expect("known aggregator");
+        assert_eq!(aggregator.name(), name);
+    }
+    assert_eq!(aggregators::DEFAULT, MedianBayesian::NAME);
+    assert!(aggregators::from_name("coin_flip").is_none());
+}
+
+#[test]
+fn median_bayesian_smooths_towards_global_mean() {
+    // submission 1: 4 and 5 (median 4.5), submission 2: a single 1
+    let ratings = ratings(&[(10, 1, 4.0), (11, 1, 5.0), (12, 2, 1.0)]);
+
+    let scores = MedianBayesian::default().aggregate(&ratings, SCALE);
+
+    // global mean = 10 / 3
+    // submission 1: (2 / 4) * 4.5 + (2 / 4) * 10/3 = 3.9167 -> 0.7833
+    // submission 2: (1 / 3) * 1 + (2 / 3) * 10/3 = 2.5556 -> 0.5111
+    assert_close(scores[&1], 3.916_666_7 / 5.0);
+    assert_close(scores[&2], 2.555_555_6 / 5.0);
+}
+
+#[test]
+fn median_bayesian_without_smoothing_is_the_median() {
+    let ratings = ratings(&[(10, 1, 1.0), (11, 1, 5.0), (12, 1, 4.0)]);
+
+    let scores = MedianBayesian { smoothing: 0.0 }.aggregate(&ratings, SCALE);
+
+    assert_close(scores[&1], 0.8);
+}
+
+#[test]
+fn trimmed_mean_drops_outliers() {
+    // one voter tanks an otherwise consistently good submission
+    let ratings = ratings(&[
+        (10, 1, 4.0),
+        (11, 1, 4.0),
+        (12, 1, 5.0),
+        (13, 1, 4.0),
+        (14, 1, 0.0),
+    ]);
+
+    let scores = TrimmedMean::default().aggregate(&ratings, SCALE);
+
+    // 20% of 5 ratings = 1 dropped at each end: mean(4, 4, 4) = 4
+    assert_close(scores[&1], 0.8);
+}
+
+#[test]
+fn trimmed_mean_keeps_a_single_rating() {
+    let ratings = ratings(&[(10, 1, 3.0)]);
+
+    let scores = TrimmedMean { trim: 0.49 }.aggregate(&ratings, SCALE);
+
+    assert_close(scores[&1], 0.6);
+}
+
+#[test]
+fn z_score_cancels_out_harsh_voters() {
+    // voter 10 is harsh and voter 11 generous, but both prefer 1 over 2 by the same
+    // margin relative to their own spread, while submission 3 is rated in the middle
+    let ratings = ratings(&[
+        (10, 1, 2.0),
+        (10, 2, 0.0),
+        (10, 3, 1.0),
+        (11, 1, 5.0),
+        (11, 2, 3.0),
+        (11, 3, 4.0),
+    ]);
+
+    let scores = ZScore.aggregate(&ratings, SCALE);
+
+    assert_eq!(ranking(&scores), vec![1, 3, 2]);
+    assert_close(scores[&3], 0.5);
+    assert_close(scores[&1] + scores[&2], 1.0);
+}
+
+#[test]
+fn z_score_ignores_voters_without_preference() {
+    let ratings = ratings(&[(10, 1, 3.0), (10, 2, 3.0)]);
+
+    let scores = ZScore.aggregate(&ratings, SCALE);
+
+    assert_close(scores[&1], 0.5);
+    assert_close(scores[&2], 0.5);
+}
+
+#[test]
+fn bradley_terry_ranks_by_pairwise_wins() {
+    // every voter prefers 1 over 2 over 3, on different parts of the scale
+    let ratings = ratings(&[
+        (10, 1, 5.0),
+        (10, 2, 4.0),
+        (10, 3, 3.0),
+        (11, 1, 2.0),
+        (11, 2, 1.0),
+        (11, 3, 0.0),
+        (12, 1, 4.0),
+        (12, 3, 1.0),
+    ]);
+
+    let scores = BradleyTerry::default().aggregate(&ratings, SCALE);
+
+    assert_eq!(ranking(&scores), vec![1, 2, 3]);
+    assert!(scores[&1] > 0.5 && scores[&3] < 0.5);
+    for score in scores.values() {
+        assert!((0.0..=1.0).contains(score));
+    }
+}
+
+#[test]
+fn bradley_terry_treats_equal_scores_as_draws() {
+    let ratings = ratings(&[(10, 1, 3.0), (10, 2, 3.0), (11, 1, 4.0), (11, 2, 4.0)]);
+
+    let scores = BradleyTerry::default().aggregate(&ratings, SCALE);
+
+    assert_close(scores[&1], 0.5);
+    assert_close(scores[&2], 0.5);
+}
+
+#[test]
+fn aggregators_agree_on_a_clear_ranking() {
+    // four voters, each rating all three submissions, with a clear winner
+    let ratings = ratings(&[
+        (10, 1, 5.0),
+        (10, 2, 3.0),
+        (10, 3, 1.0),
+        (11, 1, 4.0),
+        (11, 2, 3.0),
+        (11, 3, 2.0),
+        (12, 1, 5.0),
+        (12, 2, 4.0),
+        (12, 3, 2.0),
+        (13, 1, 4.0),
+        (13, 2, 2.0),
+        (13, 3, 1.0),
+    ]);
+
+    for name in aggregators::NAMES {
+        let scores = aggregators::from_name(name)
+            .unwrap()
+            .aggregate(&ratings, SCALE);
+        assert_eq!(ranking(&scores), vec![1, 2, 3], "{name} ranking");
+    }
+}
+
+#[test]
+fn calibration_lifts_a_harsh_voters_best_above_a_lenient_voters_worst() {
+    // Voter 1 only uses the bottom of the scale, voter 2 only the top
+    let ratings = ratings(&[
+        (1, 1, 0.0),
+        (1, 2, 1.0),
+        (1, 3, 2.0),
+        (2, 4, 3.0),
+        (2, 5, 4.0),
+        (2, 6, 5.0),
+    ]);
+    let calibration = Calibration::estimate(&[(SCALE, ratings.clone())]);
+
+    assert_close(calibration.global_mean, 0.5);
+    assert!(calibration.voters[&1].offset < 0.0, "voter 1 is harsh");
+    assert!(calibration.voters[&2].offset > 0.0, "voter 2 is lenient");
+
+    let calibrated = calibration.calibrate(SCALE, &ratings);
+    let best_of_harsh = calibrated.iter().find(|r| r.submission_id == 3).unwrap();
+    let worst_of_lenient = calibrated.iter().find(|r| r.submission_id == 4).unwrap();
+    assert!(
+        best_of_harsh.score > worst_of_lenient.score,
+        "{} should beat {}",
+        best_of_harsh.score,
+        worst_of_lenient.score
+    );
+}
+
+#[test]
+fn calibration_trusts_voters_with_more_ratings() {
+    let mut votes = Vec::new();
+    for submission_id in 0..10 {
+        votes.push((1, submission_id, 1.0));
+        votes.push((2, submission_id, 4.0));
+    }
+    // Voter 3 is as harsh as voter 1, but with a single rating
+    votes.push((3, 0, 1.0));
+    let calibration = Calibration::estimate(&[(SCALE, ratings(&votes))]);
+
+    let experienced = &calibration.voters[&1];
+    let newcomer = &calibration.voters[&3];
+    assert_eq!(newcomer.ratings, 1);
+    assert!(newcomer.offset < 0.0);
+    assert!(
+        newcomer.offset.abs() < experienced.offset.abs(),
+        "a single rating should only be partially corrected"
+    );
+}
+
+#[test]
+fn calibration_keeps_scores_of_unknown_voters() {
+    let calibration = Calibration::estimate(&[(SCALE, ratings(&[(1, 1, 1.0), (2, 1, 4.0)]))]);
+
+    assert_close(calibration.apply(99, 0.3), 0.3);
+    assert_close(Calibration::default().apply(1, 0.7), 0.7);
+}
@@ -0,0 +1,200 @@
+use std::collections::HashSet;
+
+use bit_by_design::assignment::{
+    self, Assignment, Constraints, Distribution, Reviewer, Submission,
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+/// One submission per author, the submission id being the author's id plus 100.
+fn submissions(authors: impl IntoIterator<Item = i32>) -> Vec<Submission> {
+    authors
+        .into_iter()
+        .map(|author_id| Submission {
+            id: author_id + 100,
+            author_id,
+        })
+        .collect()
+}
+
+fn run(
+    submissions: &[Submission],
+    reviewers: &[i32],
+    constraints: &Constraints,
+) -> (Vec<Assignment>, Distribution) {
+    assignment::assign(
+        submissions,
+        reviewers,
+        constraints,
+        &mut StdRng::seed_from_u64(7),
+    )
+}
+
+#[test]
+fn every_submission_gets_the_target_number_of_reviewers() {
+    let authors: Vec<i32> = (1..=20).collect();
+    let (assignments, distribution) = run(
+        &submissions(authors.clone()),
+        &authors,
+        &Constraints::new(6),
+    );
+
+    assert!(distribution.is_complete());
+    assert_eq!(distribution.review_range(), (6, 6));
+    assert_eq!(distribution.load_range(), (6, 6), "Load should be even");
+    assert_eq!(assignments.len(), 120);
+    assert_eq!(distribution.assignments, 120);
+
+    let unique: HashSet<_> = assignments.iter().collect();
+    assert_eq!(
+        unique.len(),
+        assignments.len(),
+        "No reviewer twice per submission"
+    );
+}
+
+#[test]
+fn nobody_reviews_their_own_submission() {
+    let authors: Vec<i32> = (1..=8).collect();
+    let (assignments, _) = run(
+        &submissions(authors.clone()),
+        &authors,
+        &Constraints::new(7),
+    );
+
+    assert_eq!(assignments.len(), 56, "Everyone reviews everyone else");
+    for assignment in assignments {
+        assert_ne!(assignment.submission_id, assignment.reviewer_id + 100);
+    }
+}
+
+#[test]
+fn conflicts_are_respected() {
+    let authors: Vec<i32> = (1..=10).collect();
+    let mut constraints = Constraints::new(4);
+    constraints.conflicts = HashSet::from([(1, 2), (2, 1), (3, 4)]);
+    let (assignments, distribution) = run(&submissions(authors.clone()), &authors, &constraints);
+
+    assert!(distribution.is_complete());
+    for assignment in assignments {
+        let author_id = assignment.submission_id - 100;
+        assert!(
+            !constraints
+                .conflicts
+                .contains(&(assignment.reviewer_id, author_id)),
+            "{} should not review {}",
+            assignment.reviewer_id,
+            author_id
+        );
+    }
+}
+
+#[test]
+fn voters_who_did_not_submit_share_the_load() {
+    let authors: Vec<i32> = (1..=4).collect();
+    let reviewers: Vec<i32> = (1..=8).collect();
+    let (_, distribution) = run(&submissions(authors), &reviewers, &Constraints::new(6));
+
+    assert!(distribution.is_complete());
+    assert_eq!(distribution.assignments, 24);
+    assert_eq!(distribution.load_range(), (3, 3));
+}
+
+#[test]
+fn load_is_capped_and_shortfall_reported() {
+    let authors: Vec<i32> = (1..=10).collect();
+    let mut constraints = Constraints::new(6);
+    constraints.max_per_reviewer = Some(3);
+    let (_, distribution) = run(&submissions(authors.clone()), &authors, &constraints);
+
+    assert_eq!(distribution.load_range(), (3, 3), "Nobody above the cap");
+    assert_eq!(distribution.assignments, 30);
+    assert!(!distribution.is_complete());
+    assert_eq!(
+        distribution.understaffed.len(),
+        10,
+        "60 reviews wanted, 30 given"
+    );
+    assert_eq!(
+        distribution.review_range(),
+        (3, 3),
+        "Shortfall spread evenly"
+    );
+}
+
+#[test]
+fn small_competitions_are_understaffed() {
+    let authors: Vec<i32> = (1..=3).collect();
+    let (_, distribution) = run(
+        &submissions(authors.clone()),
+        &authors,
+        &Constraints::new(6),
+    );
+
+    assert_eq!(distribution.review_range(), (2, 2), "Everyone else reviews");
+    assert_eq!(distribution.understaffed, vec![101, 102, 103]);
+    assert_eq!(
+        distribution.to_string(),
+        "6 assignments: 2-2 reviewers per submission (target 6), \
+         2-2 submissions per reviewer, 3 submissions understaffed"
+    );
+}
+
+#[test]
+fn no_submissions_means_no_assignments() {
+    let (assignments, distribution) = run(&[], &[1, 2, 3], &Constraints::new(6));
+
+    assert!(assignments.is_empty());
+    assert!(distribution.is_complete());
+    assert_eq!(distribution.load_range(), (0, 0));
+}
+
+#[test]
+fn top_up_skips_existing_reviewers_and_favours_idle_ones() {
+    let submission = Submission {
+        id: 101,
+        author_id: 1,
+    };
+    let reviewers = [
+        Reviewer { id: 2, load: 0 },
+        Reviewer { id: 3, load: 5 },
+        Reviewer { id: 4, load: 1 },
+        Reviewer { id: 5, load: 0 },
+    ];
+    let existing = HashSet::from([Assignment {
+        reviewer_id: 5,
+        submission_id: 101,
+    }]);
+
+    let added = assignment::top_up(
+        &[(submission, 2)],
+        &reviewers,
+        &existing,
+        &Constraints::new(6),
+        &mut StdRng::seed_from_u64(7),
+    );
+
+    let mut chosen: Vec<i32> = added.iter().map(|a| a.reviewer_id).collect();
+    chosen.sort_unstable();
+    assert_eq!(chosen, vec![2, 4], "Least busy reviewers not yet assigned");
+}
+
+#[test]
+fn top_up_counts_existing_load_towards_the_cap() {
+    let needs: Vec<(Submission, usize)> = submissions([1, 2, 3])
+        .into_iter()
+        .map(|submission| (submission, 1))
+        .collect();
+    let reviewers = [Reviewer { id: 9, load: 2 }];
+    let mut constraints = Constraints::new(6);
+    constraints.max_per_reviewer = Some(3);
+
+    let added = assignment::top_up(
+        &needs,
+        &reviewers,
+        &HashSet::new(),
+        &constraints,
+        &mut StdRng::seed_from_u64(7),
+    );
+
+    assert_eq!(added.len(), 1, "Only room for one more review");
+}
@@ -0,0 +1,74 @@
+use bit_by_design::figma::{FigmaLink, InvalidFigmaLink};
+
+const KEY: &str = "AbCdEfGhIjKlMnOpQrStUv";
+
+#[test]
+fn normalises_design_links() {
+    let link = FigmaLink::parse(&format!(
+        "https://figma.com/design/{KEY}/My-Design?node-id=12-34&t=tracking&fuzzy=1"
+    ))
+    .unwrap();
+
+    assert_eq!(link.kind, "design");
+    assert_eq!(link.file_key, KEY);
+    assert_eq!(link.node_id.as_deref(), Some("12:34"));
+    assert_eq!(
+        link.url,
+        format!("https://www.figma.com/design/{KEY}?node-id=12-34")
+    );
+}
+
+#[test]
+fn keeps_the_starting_point_of_prototypes() {
+    let link = FigmaLink::parse(&format!(
+        "https://www.figma.com/proto/{KEY}/Flow?node-id=1%3A2&starting-point-node-id=1-3&scaling=min-zoom"
+    ))
+    .unwrap();
+
+    assert_eq!(link.node_id.as_deref(), Some("1:2"));
+    assert_eq!(
+        link.url,
+        format!("https://www.figma.com/proto/{KEY}?node-id=1-2&starting-point-node-id=1-3")
+    );
+}
+
+#[test]
+fn links_without_node_point_at_the_file() {
+    let link = FigmaLink::parse(&format!("https://www.figma.com/file/{KEY}")).unwrap();
+
+    assert_eq!(link.node_id, None);
+    assert_eq!(link.url, format!("https://www.figma.com/file/{KEY}"));
+}
+
+#[test]
+fn rejects_other_links() {
+    for (link, error) in [
+        ("not a link", InvalidFigmaLink::Malformed),
+        (
+            &format!("https://example.com/design/{KEY}") as &str,
+            InvalidFigmaLink::NotFigma,
+        ),
+        (
+            &format!("ftp://www.figma.com/design/{KEY}"),
+            InvalidFigmaLink::NotFigma,
+        ),
+        (
+            "https://www.figma.com/files/recent",
+            InvalidFigmaLink::NotAFile,
+        ),
+        (
+            &format!("https://www.figma.com/board/{KEY}"),
+            InvalidFigmaLink::NotAFile,
+        ),
+        (
+            "https://www.figma.com/design/short",
+            InvalidFigmaLink::NotAFile,
+        ),
+        (
+            &format!("https://www.figma.com/design/{KEY}?node-id=frame"),
+            InvalidFigmaLink::NotAFile,
+        ),
+    ] {
+        assert_eq!(FigmaLink::parse(link), Err(error), "{link}");
+    }
+}
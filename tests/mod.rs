@@ -1,4 +1,8 @@
+mod aggregators;
+mod assignment;
+mod figma;
 mod models;
+mod rate_limit;
 mod requests;
 mod tasks;
 mod workers;
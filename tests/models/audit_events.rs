@@ -0,0 +1,31 @@
+use std::collections::HashMap;
+
+use bit_by_design::{
+    app::App,
+    models::{_entities::audit_events, audit_events::Event},
+};
+use loco_rs::prelude::*;
+use sea_orm::PaginatorTrait;
+use serial_test::serial;
+
+#[tokio::test]
+#[serial]
+async fn refuses_a_target_that_cannot_be_serialized() {
+    let boot = boot_test::<App>().await.unwrap();
+
+    // JSON objects only have string keys
+    let target = HashMap::from([((1, 2), "pair")]);
+    let res = Event::new("competition.update", "competition", None)
+        .after(&target)
+        .record(&boot.app_context.db)
+        .await;
+
+    assert!(res.is_err());
+    assert_eq!(
+        audit_events::Entity::find()
+            .count(&boot.app_context.db)
+            .await
+            .unwrap(),
+        0
+    );
+}
@@ -0,0 +1,233 @@
+use bit_by_design::{
+    app::App,
+    models::competitions::{self, Phase, Timings, TransitionError},
+};
+use chrono::{TimeDelta, Utc};
+use loco_rs::{
+    prelude::*,
+    testing::prelude::{boot_test, seed},
+};
+use serial_test::serial;
+
+macro_rules! configure_insta {
+    ($($expr:expr),*) => {
+        let mut settings = insta::Settings::clone_current();
+        settings.set_prepend_module_to_snapshot(false);
+        let _guard = settings.bind_to_scope();
+    };
+}
+
+#[tokio::test]
+#[serial]
+async fn test_model() {
+    configure_insta!();
+
+    let boot = boot_test::<App>().await.unwrap();
+    seed::<App>(&boot.app_context).await.unwrap();
+
+    // query your model, e.g.:
+    //
+    // let item = models::posts::Model::find_by_pid(
+    //     &boot.app_context.db,
+    //     "11111111-1111-1111-1111-111111111111",
+    // )
+    // .await;
+
+    // snapshot the result:
+    // assert_debug_snapshot!(item);
+}
+
+fn competition(now: DateTimeWithTimeZone) -> competitions::Model {
+    competitions::Model {
+        created_at: now,
+        updated_at: now,
+        id: 1,
+        name: "Bit by Design".to_string(),
+        slug: "bit-by-design".to_string(),
+        submission_start: Some(now - TimeDelta::hours(4)),
+        submission_end: Some(now - TimeDelta::hours(3)),
+        voting_start: Some(now - TimeDelta::hours(2)),
+        voting_end: Some(now - TimeDelta::hours(1)),
+        aggregator: "mean".to_string(),
+        calibrate_voters: false,
+        reviewers_per_submission: 6,
+        max_reviews_per_user: None,
+        reassign_after_hours: 48,
+        grace_period_minutes: 0,
+        entered_phase: "draft".to_string(),
+    }
+}
+
+#[test]
+fn phase_follows_timings_and_actions() {
+    let now = DateTimeWithTimeZone::from(Utc::now());
+    let mut item = competition(now);
+    assert_eq!(
+        item.phase(now - TimeDelta::hours(5)),
+        Phase::Draft,
+        "Before submissions open"
+    );
+    assert_eq!(
+        item.phase(now - TimeDelta::minutes(210)),
+        Phase::SubmissionsOpen
+    );
+    assert_eq!(
+        item.phase(now),
+        Phase::SubmissionsClosed,
+        "Voting does not start before the assignment"
+    );
+
+    item.entered_phase = "assigned".to_string();
+    assert_eq!(item.phase(now - TimeDelta::minutes(150)), Phase::Assigned);
+    assert_eq!(item.phase(now - TimeDelta::minutes(90)), Phase::VotingOpen);
+    assert_eq!(item.phase(now), Phase::VotingClosed);
+
+    item.entered_phase = "scored".to_string();
+    assert_eq!(item.phase(now), Phase::Scored);
+    item.entered_phase = "published".to_string();
+    assert_eq!(item.phase(now), Phase::Published);
+    assert!(item.is_published());
+}
+
+#[test]
+fn transitions_follow_the_lifecycle() {
+    let now = DateTimeWithTimeZone::from(Utc::now());
+    let item = competition(now);
+
+    assert_eq!(
+        item.transition(Phase::Scored, now),
+        Err(TransitionError::OutOfOrder {
+            from: Phase::SubmissionsClosed,
+            to: Phase::Scored
+        }),
+        "Cannot skip the assignment"
+    );
+    let assigned = item.transition(Phase::Assigned, now).unwrap();
+    assert_eq!(assigned.entered_phase, Set("assigned".to_string()));
+
+    let mut item = competition(now);
+    item.entered_phase = "assigned".to_string();
+    assert!(
+        item.transition(Phase::Assigned, now - TimeDelta::minutes(150))
+            .is_ok(),
+        "Actions can be run again"
+    );
+    assert!(
+        item.transition(Phase::VotingOpen, now).is_err(),
+        "Voting is over already"
+    );
+}
+
+#[test]
+fn transitions_store_the_phase_entered() {
+    let now = DateTimeWithTimeZone::from(Utc::now());
+    let mut item = competition(now);
+    assert_eq!(item.entered(), Phase::Draft);
+    assert_eq!(
+        item.unpublish(),
+        Err(TransitionError::OutOfOrder {
+            from: Phase::Draft,
+            to: Phase::Scored
+        }),
+        "Nothing published to hide"
+    );
+
+    for phase in [Phase::Assigned, Phase::Scored, Phase::Published] {
+        let moved = item.transition(phase, now).unwrap();
+        assert_eq!(moved.entered_phase, Set(phase.as_str().to_string()));
+        item.entered_phase = phase.as_str().to_string();
+        assert_eq!(item.entered(), phase);
+    }
+    assert_eq!(
+        item.transition(Phase::Assigned, now),
+        Err(TransitionError::OutOfOrder {
+            from: Phase::Published,
+            to: Phase::Assigned
+        }),
+        "Cannot go back to an earlier phase"
+    );
+
+    let hidden = item.unpublish().unwrap();
+    assert_eq!(hidden.entered_phase, Set("scored".to_string()));
+
+    item.entered_phase = "unknown".to_string();
+    assert_eq!(item.entered(), Phase::Draft);
+}
+
+#[test]
+fn transitions_keep_timings_in_order() {
+    let now = DateTimeWithTimeZone::from(Utc::now());
+    let mut item = competition(now);
+    item.submission_start = Some(now - TimeDelta::hours(1));
+    item.submission_end = Some(now + TimeDelta::hours(1));
+    item.voting_start = Some(now + TimeDelta::hours(2));
+    item.voting_end = Some(now + TimeDelta::hours(3));
+
+    let closed = item.transition(Phase::SubmissionsClosed, now).unwrap();
+    assert_eq!(closed.submission_end, Set(Some(now)), "Closes early");
+
+    let mut item = competition(now);
+    item.submission_start = None;
+    item.submission_end = None;
+    assert_eq!(item.phase(now), Phase::Draft);
+    assert_eq!(
+        item.transition(Phase::SubmissionsOpen, now),
+        Err(TransitionError::Timings(
+            "submission_end must be set to a later time to move to submissions_open".to_string()
+        ))
+    );
+}
+
+#[test]
+fn timings_must_be_in_order() {
+    let now = DateTimeWithTimeZone::from(Utc::now());
+    let timings = Timings {
+        submission_start: Some(now),
+        submission_end: Some(now + TimeDelta::hours(2)),
+        voting_start: Some(now + TimeDelta::hours(1)),
+        voting_end: None,
+    };
+    assert_eq!(
+        timings.validate(),
+        Err("submission_end must not be after voting_start".to_string())
+    );
+
+    let timings = Timings {
+        submission_start: Some(now),
+        voting_end: Some(now + TimeDelta::hours(1)),
+        ..Default::default()
+    };
+    assert!(timings.validate().is_ok(), "Unset timings are skipped");
+}
+
+#[test]
+fn grace_period_and_extensions_extend_the_deadline() {
+    let now = DateTimeWithTimeZone::from(Utc::now());
+    let mut item = competition(now);
+    let end = now - TimeDelta::hours(3);
+    assert!(!item.accepts_submission(end + TimeDelta::minutes(1), None));
+
+    item.grace_period_minutes = 10;
+    assert_eq!(
+        item.submission_deadline(None),
+        Some(end + TimeDelta::minutes(10))
+    );
+    assert!(item.accepts_submission(end + TimeDelta::minutes(5), None));
+    assert!(
+        item.is_late(end + TimeDelta::minutes(5)),
+        "Accepted, but late"
+    );
+    assert!(!item.accepts_submission(end + TimeDelta::minutes(11), None));
+
+    let extension = Some(end + TimeDelta::hours(1));
+    assert!(item.accepts_submission(end + TimeDelta::minutes(30), extension));
+    assert_eq!(
+        item.submission_deadline(Some(end + TimeDelta::minutes(5))),
+        Some(end + TimeDelta::minutes(10)),
+        "Extensions never shorten the grace period"
+    );
+    assert!(
+        !item.accepts_submission(now - TimeDelta::hours(5), extension),
+        "Not before submissions open"
+    );
+}
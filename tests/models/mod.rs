@@ -1,7 +1,8 @@
 mod users;
 
 mod admins;
-mod configs;
+mod audit_events;
+mod competitions;
 
 mod scores;
 mod submissions;
@@ -1,5 +1,5 @@
-use bit_by_design::app::App;
-use loco_rs::testing::prelude::*;
+use bit_by_design::{app::App, models::_entities::scores};
+use loco_rs::prelude::*;
 use serial_test::serial;
 
 macro_rules! configure_insta {
@@ -29,3 +29,22 @@ async fn test_model() {
     // snapshot the result:
     // assert_debug_snapshot!(item);
 }
+
+#[tokio::test]
+#[serial]
+async fn requires_a_competition() {
+    let boot = boot_test::<App>().await.unwrap();
+    seed::<App>(&boot.app_context).await.unwrap();
+
+    let score = scores::ActiveModel {
+        submission_id: Set(1),
+        final_score: Set(5000),
+        ..Default::default()
+    };
+    let err = score.insert(&boot.app_context.db).await.unwrap_err();
+    assert_eq!(
+        err,
+        DbErr::Custom("competition_id must be set".to_string()),
+        "Checked on every backend, not only where the column is NOT NULL"
+    );
+}
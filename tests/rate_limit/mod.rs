@@ -0,0 +1,113 @@
+use std::time::Duration;
+
+use bit_by_design::{
+    app::App,
+    models::_entities::rate_limit_buckets,
+    rate_limit::{Bucket, DatabaseStore, Decision, Limit, MemoryStore, Store},
+};
+use chrono::{TimeDelta, Utc};
+use loco_rs::testing::prelude::boot_test;
+use sea_orm::{EntityTrait, PaginatorTrait};
+use serial_test::serial;
+
+const LIMIT: Limit = Limit::new(2, 6);
+
+#[test]
+fn takes_tokens_until_empty() {
+    let now = Utc::now();
+    let (bucket, decision) = Bucket::full(&LIMIT, now).take(&LIMIT, now);
+    assert_eq!(decision, Decision::Allowed);
+    let (bucket, decision) = bucket.take(&LIMIT, now);
+    assert_eq!(decision, Decision::Allowed);
+
+    let (_, decision) = bucket.take(&LIMIT, now);
+    assert_eq!(
+        decision,
+        Decision::Limited {
+            retry_after: Duration::from_secs(10)
+        },
+        "Should tell when the next token is there"
+    );
+}
+
+#[test]
+fn refills_over_time_up_to_capacity() {
+    let now = Utc::now();
+    let (bucket, _) = Bucket::full(&LIMIT, now).take(&LIMIT, now);
+    let (bucket, _) = bucket.take(&LIMIT, now);
+
+    let later = now + TimeDelta::seconds(10);
+    let (bucket, decision) = bucket.take(&LIMIT, later);
+    assert_eq!(decision, Decision::Allowed, "A token should be back");
+    assert_eq!(bucket.full_at(&LIMIT), later + TimeDelta::seconds(20));
+
+    let (bucket, _) = bucket.take(&LIMIT, later + TimeDelta::hours(1));
+    assert!(
+        (bucket.tokens - 1.0).abs() < f64::EPSILON,
+        "Should not refill beyond capacity"
+    );
+}
+
+async fn exhausts_bucket(store: &dyn Store) {
+    let now = Utc::now();
+    for _ in 0..2 {
+        let decision = store.take("test:ip:127.0.0.1", &LIMIT, now).await.unwrap();
+        assert_eq!(decision, Decision::Allowed);
+    }
+    let decision = store.take("test:ip:127.0.0.1", &LIMIT, now).await.unwrap();
+    assert!(matches!(decision, Decision::Limited { .. }));
+
+    let decision = store.take("test:ip:127.0.0.2", &LIMIT, now).await.unwrap();
+    assert_eq!(
+        decision,
+        Decision::Allowed,
+        "Other keys have their own bucket"
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn database_store_forgets_full_buckets() {
+    let boot = boot_test::<App>().await.unwrap();
+    let db = boot.app_context.db.clone();
+    rate_limit_buckets::Entity::delete_many()
+        .exec(&db)
+        .await
+        .unwrap();
+    let store = DatabaseStore::new(db.clone());
+    let now = Utc::now();
+    store.take("test:ip:127.0.0.1", &LIMIT, now).await.unwrap();
+    store.take("test:ip:127.0.0.2", &LIMIT, now).await.unwrap();
+    let later = now + TimeDelta::minutes(1);
+    store
+        .take("test:ip:127.0.0.3", &LIMIT, later)
+        .await
+        .unwrap();
+    assert_eq!(
+        rate_limit_buckets::Entity::find().count(&db).await.unwrap(),
+        3,
+        "Not every take prunes"
+    );
+
+    assert_eq!(store.prune(later).await.unwrap(), 2);
+    let keys: Vec<_> = rate_limit_buckets::Entity::find()
+        .all(&db)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|row| row.key)
+        .collect();
+    assert_eq!(keys, ["test:ip:127.0.0.3"], "Refilled buckets are deleted");
+}
+
+#[tokio::test]
+async fn memory_store_keeps_buckets_by_key() {
+    exhausts_bucket(&MemoryStore::default()).await;
+}
+
+#[tokio::test]
+#[serial]
+async fn database_store_keeps_buckets_by_key() {
+    let boot = boot_test::<App>().await.unwrap();
+    exhausts_bucket(&DatabaseStore::new(boot.app_context.db.clone())).await;
+}
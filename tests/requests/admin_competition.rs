@@ -0,0 +1,203 @@
+use bit_by_design::{
+    app::App,
+    models::{
+        _entities::{audit_events, vote_assignments},
+        competitions::{self, Phase},
+    },
+};
+use loco_rs::prelude::*;
+use sea_orm::PaginatorTrait;
+use serial_test::serial;
+
+use super::prepare_data;
+
+#[tokio::test]
+#[serial]
+async fn admin_can_run_phase_transitions() {
+    request::<App, _, _>(|request, ctx| async move {
+        let users = prepare_data::create_test_users(&request, &ctx, 4).await;
+        prepare_data::make_admin(&ctx, users[0].user.id).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&users[0].token);
+        let submission = prepare_data::create_submission_for_user(&ctx, users[1].user.id).await;
+        prepare_data::create_submission_for_user(&ctx, users[2].user.id).await;
+        prepare_data::set_submission_period_closed(&ctx).await;
+
+        let res = request
+            .post("/api/admin/competition/assign")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        assert_eq!(res.status_code(), 200, "Admin should assign submissions");
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(body["action"], "assign");
+        assert_eq!(
+            body["user_id"], users[0].user.id,
+            "Records who triggered it"
+        );
+        assert_eq!(body["summary"]["assignments"], 4);
+        assert_eq!(
+            body["summary"]["warnings"][0], "2 submissions got fewer than 6 reviewers",
+            "Only two reviewers per submission are available"
+        );
+        let assignments = vote_assignments::Entity::find()
+            .filter(vote_assignments::Column::SubmissionId.eq(submission.id))
+            .all(&ctx.db)
+            .await
+            .unwrap();
+        assert_eq!(assignments.len(), 2);
+
+        prepare_data::create_vote(&ctx, users[3].user.id, submission.id).await;
+        prepare_data::set_voting_period_closed(&ctx).await;
+        let res = request
+            .post("/api/admin/competition/generate-scores")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        assert_eq!(res.status_code(), 200, "Admin should generate scores");
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(body["summary"]["scores"], 1);
+        assert_eq!(body["summary"]["votes"], 1);
+        assert_eq!(
+            body["summary"]["warnings"][0],
+            "1 submissions received no votes and are not scored"
+        );
+
+        let res = request
+            .post("/api/admin/competition/publish-leaderboard")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        assert_eq!(
+            res.status_code(),
+            200,
+            "Admin should publish the leaderboard"
+        );
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(body["summary"]["show_leaderboard"], true);
+        assert_eq!(body["summary"]["scores"], 1);
+
+        let competition = prepare_data::ensure_competition(&ctx).await;
+        assert_eq!(
+            competition.entered(),
+            Phase::Published,
+            "The scheduler won't assign or score again"
+        );
+
+        let res = request
+            .get(&format!(
+                "/api/admin/competitions/{}/transitions",
+                competition.slug
+            ))
+            .add_header(auth_key, auth_value)
+            .await;
+        let body: Vec<serde_json::Value> = serde_json::from_str(&res.text()).unwrap();
+        let actions: Vec<_> = body.iter().map(|t| t["action"].clone()).collect();
+        assert_eq!(
+            actions,
+            ["publish_leaderboard", "generate_scores", "assign"],
+            "Most recent first"
+        );
+        assert_eq!(body[0]["user_id"], users[0].user.id);
+
+        let events = audit_events::Entity::find()
+            .filter(audit_events::Column::CompetitionId.eq(competition.id))
+            .filter(audit_events::Column::Action.starts_with("competition."))
+            .count(&ctx.db)
+            .await
+            .unwrap();
+        assert_eq!(events, 3, "Each transition is recorded once");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn transitions_act_on_the_given_competition() {
+    request::<App, _, _>(|request, ctx| async move {
+        let admin = prepare_data::init_user_login(&request, &ctx).await;
+        prepare_data::make_admin(&ctx, admin.user.id).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&admin.token);
+        let past = prepare_data::create_competition(&ctx, "Round 1", "round-1").await;
+        let mut scored = past.clone().into_active_model();
+        scored.entered_phase = Set("scored".to_string());
+        scored.update(&ctx.db).await.unwrap();
+        prepare_data::create_competition(&ctx, "Round 2", "round-2").await;
+
+        let res = request
+            .post("/api/admin/competitions/round-1/publish-leaderboard")
+            .add_header(auth_key, auth_value)
+            .await;
+        assert_eq!(res.status_code(), 200);
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(body["competition_id"], past.id);
+        assert_eq!(
+            body["summary"]["warnings"][0],
+            "no scores have been generated yet"
+        );
+
+        let past = competitions::Entity::find_by_id(past.id)
+            .one(&ctx.db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(past.is_published());
+        let current = prepare_data::ensure_competition(&ctx).await;
+        assert!(!current.is_published(), "Other rounds are untouched");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn non_admin_cannot_run_phase_transitions() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::init_user_login(&request, &ctx).await;
+        prepare_data::ensure_competition(&ctx).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user.token);
+
+        for action in ["assign", "generate-scores", "publish-leaderboard"] {
+            let res = request
+                .post(&format!("/api/admin/competition/{action}"))
+                .add_header(auth_key.clone(), auth_value.clone())
+                .await;
+            assert_eq!(res.status_code(), 403, "Should reject non-admin");
+        }
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn admin_can_move_to_a_phase() {
+    request::<App, _, _>(|request, ctx| async move {
+        let admin = prepare_data::init_user_login(&request, &ctx).await;
+        prepare_data::make_admin(&ctx, admin.user.id).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&admin.token);
+        prepare_data::set_submission_period_active(&ctx).await;
+
+        let res = request
+            .post("/api/admin/competition/phase")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&serde_json::json!({ "phase": "submissions_closed" }))
+            .await;
+        assert_eq!(
+            res.status_code(),
+            200,
+            "Admin should close submissions early"
+        );
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(body["summary"]["from"], "submissions_open");
+        assert_eq!(body["summary"]["to"], "submissions_closed");
+
+        let res = request
+            .post("/api/admin/competition/phase")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&serde_json::json!({ "phase": "voting_open" }))
+            .await;
+        assert_eq!(res.status_code(), 400, "Submissions must be assigned first");
+
+        let res = request
+            .post("/api/admin/competition/generate-scores")
+            .add_header(auth_key, auth_value)
+            .await;
+        assert_eq!(res.status_code(), 400, "Voting has not happened yet");
+    })
+    .await;
+}
@@ -0,0 +1,234 @@
+use bit_by_design::app::App;
+use loco_rs::testing::prelude::*;
+use serial_test::serial;
+
+use super::prepare_data;
+
+#[tokio::test]
+#[serial]
+async fn admin_can_list_and_search_users() {
+    request::<App, _, _>(|request, ctx| async move {
+        let users = prepare_data::create_test_users(&request, &ctx, 3).await;
+        prepare_data::make_admin(&ctx, users[0].user.id).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&users[0].token);
+
+        let res = request
+            .get("/api/admin/users?page=1&page_size=2")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        assert_eq!(res.status_code(), 200, "Admin should list users");
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(body["results"].as_array().unwrap().len(), 2, "Page size");
+        assert_eq!(body["pagination"]["total_items"], 3);
+        assert_eq!(body["pagination"]["total_pages"], 2);
+        assert_eq!(body["results"][0]["is_admin"], true);
+        assert!(body["results"][0].get("otp").is_none(), "No login secrets");
+
+        let res = request
+            .get("/api/admin/users?q=USER2")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        let results = body["results"].as_array().unwrap();
+        assert_eq!(results.len(), 1, "Search ignores case");
+        assert_eq!(results[0]["email"], "user2@test.com");
+
+        prepare_data::init_user_login_with_email(&request, &ctx, "first_last@test.com", "First")
+            .await;
+        let res = request
+            .get("/api/admin/users?q=t_")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        let results = body["results"].as_array().unwrap();
+        assert_eq!(results.len(), 1, "An underscore is not a wildcard");
+        assert_eq!(results[0]["email"], "first_last@test.com");
+
+        let res = request
+            .get("/api/admin/users?q=%25")
+            .add_header(auth_key, auth_value)
+            .await;
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(
+            body["pagination"]["total_items"], 0,
+            "Nor is a percent sign"
+        );
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn non_admin_cannot_manage_users() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::init_user_login(&request, &ctx).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user.token);
+
+        let res = request
+            .get("/api/admin/users")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        assert_eq!(res.status_code(), 403, "Should reject non-admin");
+
+        let res = request
+            .post(&format!("/api/admin/users/{}/admin", user.user.id))
+            .add_header(auth_key, auth_value)
+            .await;
+        assert_eq!(
+            res.status_code(),
+            403,
+            "Non-admin cannot promote themselves"
+        );
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn admin_can_create_update_and_delete_user() {
+    request::<App, _, _>(|request, ctx| async move {
+        let admin = prepare_data::init_user_login(&request, &ctx).await;
+        prepare_data::make_admin(&ctx, admin.user.id).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&admin.token);
+
+        let params = serde_json::json!({ "name": "Late Participant", "email": "late@test.com" });
+        let res = request
+            .post("/api/admin/users")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&params)
+            .await;
+        assert_eq!(res.status_code(), 200, "Admin should create user");
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(body["email"], "late@test.com");
+        assert_eq!(body["is_admin"], false);
+        let id = body["id"].as_i64().unwrap();
+
+        let res = request
+            .post("/api/admin/users")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&params)
+            .await;
+        assert_eq!(res.status_code(), 400, "Email must be unique");
+
+        let params = serde_json::json!({ "name": "Late Person", "email": "late@example.com" });
+        let res = request
+            .put(&format!("/api/admin/users/{id}"))
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&params)
+            .await;
+        assert_eq!(res.status_code(), 200, "Admin should update user");
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(body["name"], "Late Person");
+        assert_eq!(body["email"], "late@example.com");
+
+        let res = request
+            .delete(&format!("/api/admin/users/{id}"))
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        assert_eq!(res.status_code(), 200, "Admin should delete user");
+
+        let res = request
+            .get(&format!("/api/admin/users/{id}"))
+            .add_header(auth_key, auth_value)
+            .await;
+        assert_eq!(res.status_code(), 404, "User should be gone");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn rejects_invalid_user() {
+    request::<App, _, _>(|request, ctx| async move {
+        let admin = prepare_data::init_user_login(&request, &ctx).await;
+        prepare_data::make_admin(&ctx, admin.user.id).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&admin.token);
+
+        let params = serde_json::json!({ "name": "Late Participant", "email": "not-an-email" });
+        let res = request
+            .post("/api/admin/users")
+            .add_header(auth_key, auth_value)
+            .json(&params)
+            .await;
+
+        assert_eq!(res.status_code(), 400, "Should reject invalid email");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn admin_can_promote_and_demote() {
+    request::<App, _, _>(|request, ctx| async move {
+        let users = prepare_data::create_test_users(&request, &ctx, 2).await;
+        prepare_data::make_admin(&ctx, users[0].user.id).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&users[0].token);
+        let url = format!("/api/admin/users/{}/admin", users[1].user.id);
+
+        let res = request
+            .post(&url)
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        assert_eq!(res.status_code(), 200, "Admin should promote");
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(body["is_admin"], true);
+
+        // The promoted user can now manage users too
+        let (other_key, other_value) = prepare_data::auth_header(&users[1].token);
+        let res = request
+            .get("/api/admin/users")
+            .add_header(other_key, other_value)
+            .await;
+        assert_eq!(res.status_code(), 200, "Promoted user is an admin");
+
+        let res = request
+            .delete(&url)
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        assert_eq!(res.status_code(), 200, "Admin should demote");
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(body["is_admin"], false);
+
+        let res = request
+            .delete(&format!("/api/admin/users/{}/admin", users[0].user.id))
+            .add_header(auth_key, auth_value)
+            .await;
+        assert_eq!(res.status_code(), 400, "Admins cannot demote themselves");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn admin_can_revoke_sessions() {
+    request::<App, _, _>(|request, ctx| async move {
+        let users = prepare_data::create_test_users(&request, &ctx, 2).await;
+        prepare_data::make_admin(&ctx, users[0].user.id).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&users[0].token);
+        let url = format!("/api/admin/users/{}/sessions", users[1].user.id);
+
+        let (other_key, other_value) = prepare_data::auth_header(&users[1].token);
+        let res = request
+            .delete(&url)
+            .add_header(other_key, other_value)
+            .await;
+        assert_eq!(res.status_code(), 403, "Only admins revoke sessions");
+
+        let res = request.delete(&url).add_header(auth_key, auth_value).await;
+        assert_eq!(res.status_code(), 200, "Admin should revoke sessions");
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(body["revoked"], 1);
+
+        let res = request
+            .post("/api/auth/refresh")
+            .json(&serde_json::json!({"refresh_token": users[1].refresh_token}))
+            .await;
+        assert_eq!(res.status_code(), 401, "Revoked session can't be refreshed");
+        let res = request
+            .post("/api/auth/refresh")
+            .json(&serde_json::json!({"refresh_token": users[0].refresh_token}))
+            .await;
+        assert_eq!(res.status_code(), 200, "Other sessions are kept");
+    })
+    .await;
+}
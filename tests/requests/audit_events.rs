@@ -0,0 +1,156 @@
+use bit_by_design::{app::App, models::audit_events::Event};
+use loco_rs::testing::prelude::*;
+use serial_test::serial;
+
+use super::prepare_data;
+
+#[tokio::test]
+#[serial]
+async fn config_update_is_audited() {
+    request::<App, _, _>(|request, ctx| async move {
+        let admin = prepare_data::init_user_login(&request, &ctx).await;
+        prepare_data::make_admin(&ctx, admin.user.id).await;
+        let competition = prepare_data::ensure_competition(&ctx).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&admin.token);
+
+        let params = serde_json::json!({
+            "submission_start": "2025-01-01T00:00:00+00:00",
+            "submission_end": "2025-01-15T00:00:00+00:00",
+            "voting_start": "2025-01-16T00:00:00+00:00",
+            "voting_end": "2025-01-30T00:00:00+00:00"
+        });
+        let res = request
+            .put("/api/config")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&params)
+            .await;
+        assert_eq!(res.status_code(), 200);
+
+        let res = request
+            .get("/api/admin/audit_events?action=competition.update_timings")
+            .add_header(auth_key, auth_value)
+            .await;
+        assert_eq!(res.status_code(), 200);
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        let events = body["results"].as_array().unwrap();
+        assert_eq!(events.len(), 1, "Should record one event");
+        let event = &events[0];
+        assert_eq!(event["actor_id"], admin.user.id);
+        assert_eq!(event["target_type"], "competition");
+        assert_eq!(event["target_id"], competition.id);
+        assert_eq!(event["competition_id"], competition.id);
+        assert!(event["before"]["submission_start"].is_null());
+        assert!(event["after"]["submission_start"]
+            .as_str()
+            .unwrap()
+            .starts_with("2025-01-01T00:00:00"));
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn can_filter_and_page_audit_events() {
+    request::<App, _, _>(|request, ctx| async move {
+        let admin = prepare_data::init_user_login(&request, &ctx).await;
+        prepare_data::make_admin(&ctx, admin.user.id).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&admin.token);
+
+        for id in 1..=3 {
+            Event::new("score.update", "score", Some(id))
+                .by(admin.user.id)
+                .record(&ctx.db)
+                .await
+                .unwrap();
+        }
+        Event::new("vote_assignment.delete", "vote_assignment", Some(1))
+            .record(&ctx.db)
+            .await
+            .unwrap();
+
+        let res = request
+            .get("/api/admin/audit_events?action=score&page=1&page_size=2")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        assert_eq!(res.status_code(), 200);
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        let events = body["results"].as_array().unwrap();
+        assert_eq!(events.len(), 2, "Should return a page of events");
+        assert_eq!(events[0]["target_id"], 3, "Most recent first");
+        assert_eq!(body["pagination"]["total_items"], 3);
+        assert_eq!(body["pagination"]["total_pages"], 2);
+
+        let res = request
+            .get("/api/admin/audit_events?target_type=vote_assignment&target_id=1")
+            .add_header(auth_key, auth_value)
+            .await;
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        let events = body["results"].as_array().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0]["action"], "vote_assignment.delete");
+        assert!(events[0]["actor_id"].is_null());
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn admin_edit_of_submission_is_audited() {
+    request::<App, _, _>(|request, ctx| async move {
+        let admin = prepare_data::init_user_login(&request, &ctx).await;
+        prepare_data::make_admin(&ctx, admin.user.id).await;
+        prepare_data::ensure_competition(&ctx).await;
+        let author =
+            prepare_data::init_user_login_with_email(&request, &ctx, "author@loco.com", "author")
+                .await;
+        let submission = prepare_data::create_submission_for_user(&ctx, author.user.id).await;
+        prepare_data::set_submission_period_active(&ctx).await;
+
+        let params = prepare_data::create_submission_params().await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&author.token);
+        let res = request
+            .put(&format!("/api/submissions/{}", submission.id))
+            .add_header(auth_key, auth_value)
+            .json(&params)
+            .await;
+        assert_eq!(res.status_code(), 200);
+
+        let (auth_key, auth_value) = prepare_data::auth_header(&admin.token);
+        let res = request
+            .put(&format!("/api/submissions/{}", submission.id))
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&params)
+            .await;
+        assert_eq!(res.status_code(), 200);
+
+        let res = request
+            .get("/api/admin/audit_events?target_type=submission")
+            .add_header(auth_key, auth_value)
+            .await;
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        let events = body["results"].as_array().unwrap();
+        assert_eq!(events.len(), 1, "Only the admin edit should be audited");
+        assert_eq!(events[0]["actor_id"], admin.user.id);
+        assert_eq!(events[0]["target_id"], submission.id);
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn non_admin_cannot_list_audit_events() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::init_user_login(&request, &ctx).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user.token);
+
+        let res = request
+            .get("/api/admin/audit_events")
+            .add_header(auth_key, auth_value)
+            .await;
+        assert_eq!(res.status_code(), 403);
+
+        let res = request.get("/api/admin/audit_events").await;
+        assert_eq!(res.status_code(), 401);
+    })
+    .await;
+}
@@ -1,6 +1,11 @@
-use bit_by_design::{app::App, models::users};
+use axum::http::StatusCode;
+use bit_by_design::{
+    app::App,
+    models::{invite_codes, refresh_tokens, users},
+};
+use chrono::{Local, TimeDelta};
 use insta::{assert_debug_snapshot, with_settings};
-use loco_rs::testing::prelude::*;
+use loco_rs::{prelude::*, TestServer};
 use serial_test::serial;
 
 use super::prepare_data;
@@ -37,8 +42,10 @@ async fn can_register() {
         );
         let saved_user = users::Model::find_by_email(&ctx.db, email).await;
 
+        let mut filters = cleanup_user_model();
+        filters.push((r#"\$argon2id\$[^"]+"#, "OTP_HASH"));
         with_settings!({
-            filters => cleanup_user_model()
+            filters => filters
         }, {
             assert_debug_snapshot!(saved_user);
         });
@@ -107,8 +114,8 @@ async fn can_get_current_user() {
 #[serial]
 async fn can_reject_invalid_email() {
     configure_insta!();
-    request::<App, _, _>(|request, _ctx| async move {
-        let invalid_email = "user1@temp-mail.com";
+    request::<App, _, _>(|request, ctx| async move {
+        let invalid_email = "user1@temp-mail";
         let payload = serde_json::json!({
             "email": invalid_email,
         });
@@ -118,6 +125,511 @@ async fn can_reject_invalid_email() {
             400,
             "Expected request with invalid email '{invalid_email}' to be blocked, but it was allowed."
         );
+
+        // Any domain can log in, `allowed_email_domains` only opens registration
+        let user = prepare_data::create_user(&ctx, "user1@temp-mail.com", "User 1").await;
+        let response = request
+            .post("/api/auth/magic-link")
+            .json(&serde_json::json!({"email": user.email}))
+            .await;
+        assert_eq!(response.status_code(), 200);
+    })
+    .await;
+}
+
+async fn login(request: &TestServer, email: &str, otp: &str) -> StatusCode {
+    request
+        .post("/api/auth/login")
+        .json(&serde_json::json!({"email": email, "otp": otp}))
+        .await
+        .status_code()
+}
+
+#[tokio::test]
+#[serial]
+async fn otp_is_stored_hashed_and_used_once() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::create_user(&ctx, "otp@test.com", "Otp").await;
+        let (user, otp) = user.into_active_model().create_otp(&ctx.db).await.unwrap();
+        assert_ne!(
+            user.otp.as_deref(),
+            Some(otp.as_str()),
+            "Should not store plaintext"
+        );
+
+        let status = login(&request, &user.email, &otp).await;
+        assert_eq!(status, 200, "Should log in with the OTP");
+
+        let status = login(&request, &user.email, &otp).await;
+        assert_eq!(status, 401, "Should not accept the OTP twice");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn expired_otp_is_rejected() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::create_user(&ctx, "otp@test.com", "Otp").await;
+        let (user, otp) = user.into_active_model().create_otp(&ctx.db).await.unwrap();
+        let mut user = user.into_active_model();
+        user.otp_sent_at = Set(Some(
+            DateTimeWithTimeZone::from(Local::now()) - TimeDelta::minutes(11),
+        ));
+        let user = user.update(&ctx.db).await.unwrap();
+
+        let status = login(&request, &user.email, &otp).await;
+        assert_eq!(status, 401, "Should reject an expired OTP");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn failed_attempts_lock_out_and_invalidate_otp() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::create_user(&ctx, "otp@test.com", "Otp").await;
+        let (user, otp) = user.into_active_model().create_otp(&ctx.db).await.unwrap();
+
+        for attempt in 1..5 {
+            let status = login(&request, &user.email, "wrong").await;
+            assert_eq!(status, 401);
+            let stored = users::Model::find_by_email(&ctx.db, &user.email)
+                .await
+                .unwrap();
+            assert_eq!(stored.otp_failed_attempts, attempt);
+        }
+        let status = login(&request, &user.email, "wrong").await;
+        assert_eq!(status, 401);
+
+        let stored = users::Model::find_by_email(&ctx.db, &user.email)
+            .await
+            .unwrap();
+        assert!(stored.otp.is_none(), "Should invalidate the OTP");
+        assert!(stored.otp_locked_until.is_some(), "Should lock out");
+
+        let status = login(&request, &user.email, &otp).await;
+        assert_eq!(status, 429, "Should reject even the right OTP");
+        let res = request
+            .post("/api/auth/send-otp")
+            .json(&serde_json::json!({"email": user.email}))
+            .await;
+        assert_eq!(res.status_code(), 429, "Should not send OTPs while locked");
+        assert_eq!(ctx.mailer.as_ref().unwrap().deliveries().count, 0);
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn can_log_in_after_lockout_ends() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::create_user(&ctx, "otp@test.com", "Otp").await;
+        let mut user = user.into_active_model();
+        user.otp_locked_until = Set(Some(
+            DateTimeWithTimeZone::from(Local::now()) - TimeDelta::minutes(1),
+        ));
+        let user = user.update(&ctx.db).await.unwrap();
+
+        let (user, otp) = user.into_active_model().create_otp(&ctx.db).await.unwrap();
+        let status = login(&request, &user.email, &otp).await;
+        assert_eq!(status, 200, "Lockout should be over");
+
+        let stored = users::Model::find_by_email(&ctx.db, &user.email)
+            .await
+            .unwrap();
+        assert!(stored.otp_locked_until.is_none());
+        assert_eq!(stored.otp_failed_attempts, 0);
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn successful_login_resets_failed_attempts() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::create_user(&ctx, "otp@test.com", "Otp").await;
+        let (user, otp) = user.into_active_model().create_otp(&ctx.db).await.unwrap();
+
+        login(&request, &user.email, "wrong").await;
+        let status = login(&request, &user.email, &otp).await;
+        assert_eq!(status, 200);
+
+        let stored = users::Model::find_by_email(&ctx.db, &user.email)
+            .await
+            .unwrap();
+        assert_eq!(stored.otp_failed_attempts, 0);
+    })
+    .await;
+}
+
+async fn verify_magic_link(request: &TestServer, token: &str) -> StatusCode {
+    request
+        .post("/api/auth/magic-link/verify")
+        .json(&serde_json::json!({"token": token}))
+        .await
+        .status_code()
+}
+
+#[tokio::test]
+#[serial]
+async fn can_request_magic_link() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::create_user(&ctx, "magic@test.com", "Magic").await;
+
+        let res = request
+            .post("/api/auth/magic-link")
+            .json(&serde_json::json!({"email": user.email}))
+            .await;
+        assert_eq!(res.status_code(), 200);
+        assert_eq!(ctx.mailer.as_ref().unwrap().deliveries().count, 1);
+
+        let stored = users::Model::find_by_email(&ctx.db, &user.email)
+            .await
+            .unwrap();
+        let token = stored.magic_link_token.expect("Should store the token");
+        assert_eq!(token.len(), 64, "Only a SHA-256 of the token is stored");
+        assert!(stored.magic_link_expiration.is_some());
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn magic_link_for_unknown_email_is_rejected() {
+    request::<App, _, _>(|request, ctx| async move {
+        let res = request
+            .post("/api/auth/magic-link")
+            .json(&serde_json::json!({"email": "nobody@test.com"}))
+            .await;
+        assert_eq!(res.status_code(), 401);
+        assert_eq!(ctx.mailer.as_ref().unwrap().deliveries().count, 0);
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn magic_link_logs_in_once() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::create_user(&ctx, "magic@test.com", "Magic").await;
+        let (user, token) = user
+            .into_active_model()
+            .create_magic_link(&ctx.db)
+            .await
+            .unwrap();
+
+        let res = request
+            .post("/api/auth/magic-link/verify")
+            .json(&serde_json::json!({"token": token}))
+            .await;
+        assert_eq!(res.status_code(), 200, "Should log in with the link");
+        let body: serde_json::Value = res.json();
+        assert_eq!(body["pid"], user.pid.to_string());
+        assert!(body["token"].as_str().is_some_and(|jwt| !jwt.is_empty()));
+
+        let status = verify_magic_link(&request, &token).await;
+        assert_eq!(status, 401, "Should not accept the link twice");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn magic_link_is_used_once_by_concurrent_logins() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::create_user(&ctx, "magic@test.com", "Magic").await;
+        let (_, token) = user
+            .into_active_model()
+            .create_magic_link(&ctx.db)
+            .await
+            .unwrap();
+
+        let (first, second) = tokio::join!(
+            verify_magic_link(&request, &token),
+            verify_magic_link(&request, &token)
+        );
+        let mut statuses = [first.as_u16(), second.as_u16()];
+        statuses.sort_unstable();
+        assert_eq!(statuses, [200, 401], "Only one login gets a session");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn expired_magic_link_is_rejected() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::create_user(&ctx, "magic@test.com", "Magic").await;
+        let (user, token) = user
+            .into_active_model()
+            .create_magic_link(&ctx.db)
+            .await
+            .unwrap();
+        let mut user = user.into_active_model();
+        user.magic_link_expiration = Set(Some(
+            DateTimeWithTimeZone::from(Local::now()) - TimeDelta::minutes(1),
+        ));
+        user.update(&ctx.db).await.unwrap();
+
+        let status = verify_magic_link(&request, &token).await;
+        assert_eq!(status, 401, "Should reject an expired link");
+        let status = verify_magic_link(&request, "").await;
+        assert_eq!(status, 401, "Should reject an empty token");
+    })
+    .await;
+}
+
+async fn refresh(request: &TestServer, refresh_token: &str) -> StatusCode {
+    request
+        .post("/api/auth/refresh")
+        .json(&serde_json::json!({"refresh_token": refresh_token}))
+        .await
+        .status_code()
+}
+
+#[tokio::test]
+#[serial]
+async fn refresh_token_rotates() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::init_user_login(&request, &ctx).await;
+
+        let res = request
+            .post("/api/auth/refresh")
+            .json(&serde_json::json!({"refresh_token": user.refresh_token}))
+            .await;
+        assert_eq!(res.status_code(), 200, "Should refresh the session");
+        let body: serde_json::Value = res.json();
+        assert_eq!(body["pid"], user.user.pid.to_string());
+        let rotated = body["refresh_token"].as_str().unwrap().to_string();
+        assert_ne!(rotated, user.refresh_token, "Should rotate the token");
+
+        let (auth_key, auth_value) = prepare_data::auth_header(body["token"].as_str().unwrap());
+        let res = request
+            .get("/api/auth/current")
+            .add_header(auth_key, auth_value)
+            .await;
+        assert_eq!(res.status_code(), 200, "New JWT should work");
+
+        assert_eq!(refresh(&request, &rotated).await, 200);
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn reused_refresh_token_revokes_every_session() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::init_user_login(&request, &ctx).await;
+        let res = request
+            .post("/api/auth/refresh")
+            .json(&serde_json::json!({"refresh_token": user.refresh_token}))
+            .await;
+        let body: serde_json::Value = res.json();
+        let rotated = body["refresh_token"].as_str().unwrap().to_string();
+
+        let status = refresh(&request, &user.refresh_token).await;
+        assert_eq!(status, 401, "Should not accept a rotated token");
+        let status = refresh(&request, &rotated).await;
+        assert_eq!(status, 401, "Reuse should end the session it leaked from");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn expired_refresh_token_is_rejected() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::create_user(&ctx, "refresh@test.com", "Refresh").await;
+        let (_, token) = refresh_tokens::ActiveModel::issue(&ctx.db, user.id, TimeDelta::zero())
+            .await
+            .unwrap();
+
+        assert_eq!(refresh(&request, &token).await, 401);
+        assert_eq!(refresh(&request, "unknown").await, 401);
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn logout_revokes_refresh_token() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::init_user_login(&request, &ctx).await;
+        let other =
+            prepare_data::init_user_login_with_email(&request, &ctx, "other@test.com", "Other")
+                .await;
+
+        let res = request
+            .post("/api/auth/logout")
+            .json(&serde_json::json!({"refresh_token": user.refresh_token}))
+            .await;
+        assert_eq!(res.status_code(), 200);
+        let stored = refresh_tokens::Model::find_by_token(&ctx.db, &user.refresh_token)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(stored.revoked_at.is_some(), "Should revoke the token");
+        assert_ne!(
+            stored.token_hash, user.refresh_token,
+            "Should not store plaintext"
+        );
+
+        assert_eq!(refresh(&request, &user.refresh_token).await, 401);
+        assert_eq!(refresh(&request, &other.refresh_token).await, 200);
+
+        let res = request
+            .post("/api/auth/logout")
+            .json(&serde_json::json!({"refresh_token": user.refresh_token}))
+            .await;
+        assert_eq!(res.status_code(), 200, "Logging out twice is fine");
+    })
+    .await;
+}
+
+async fn invite_code(ctx: &AppContext) -> invite_codes::Model {
+    let admin = prepare_data::create_user(ctx, "admin@test.com", "Admin").await;
+    invite_codes::ActiveModel::generate(&ctx.db, admin.id, None)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+#[serial]
+async fn can_register_with_invite_code() {
+    request::<App, _, _>(|request, ctx| async move {
+        let code = invite_code(&ctx).await;
+
+        let res = request
+            .post("/api/auth/register")
+            .json(&serde_json::json!({
+                "email": "invited@elsewhere.test",
+                "name": "Invited",
+                "secret": code.code.to_lowercase()
+            }))
+            .await;
+        assert_eq!(res.status_code(), 200, "Should register with the code");
+        assert_eq!(ctx.mailer.as_ref().unwrap().deliveries().count, 1);
+
+        let user = users::Model::find_by_email(&ctx.db, "invited@elsewhere.test")
+            .await
+            .unwrap();
+        let code = invite_codes::Entity::find_by_id(code.id)
+            .one(&ctx.db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(code.used_by_id, Some(user.id));
+        assert!(code.used_at.is_some());
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn invite_code_can_only_be_used_once() {
+    request::<App, _, _>(|request, ctx| async move {
+        let code = invite_code(&ctx).await;
+        for (email, status) in [
+            ("first@elsewhere.test", 200),
+            ("second@elsewhere.test", 400),
+        ] {
+            let res = request
+                .post("/api/auth/register")
+                .json(&serde_json::json!({"email": email, "name": "Someone", "secret": code.code}))
+                .await;
+            assert_eq!(res.status_code(), status);
+        }
+        assert!(
+            users::Model::find_by_email(&ctx.db, "second@elsewhere.test")
+                .await
+                .is_err(),
+            "Should not register with a used code"
+        );
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn cannot_register_without_code_or_allowed_domain() {
+    request::<App, _, _>(|request, ctx| async move {
+        let res = request
+            .post("/api/auth/register")
+            .json(&serde_json::json!({"email": "someone@elsewhere.test", "name": "Someone"}))
+            .await;
+
+        assert_eq!(res.status_code(), 403);
+        assert_eq!(ctx.mailer.as_ref().unwrap().deliveries().count, 0);
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn cannot_register_twice() {
+    request::<App, _, _>(|request, ctx| async move {
+        prepare_data::create_user(&ctx, "taken@loco.com", "Taken").await;
+
+        let res = request
+            .post("/api/auth/register")
+            .json(&serde_json::json!({"email": "taken@loco.com", "name": "Again"}))
+            .await;
+
+        assert_eq!(res.status_code(), 400);
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(body["errors"]["email"][0]["code"], "taken");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn emails_ignore_case() {
+    request::<App, _, _>(|request, ctx| async move {
+        let res = request
+            .post("/api/auth/register")
+            .json(&serde_json::json!({"email": "New.User@Loco.com", "name": "New"}))
+            .await;
+        assert_eq!(res.status_code(), 200);
+        let user = users::Model::find_by_email(&ctx.db, "new.user@loco.com")
+            .await
+            .unwrap();
+        assert_eq!(user.email, "new.user@loco.com", "Stored in lowercase");
+
+        let res = request
+            .post("/api/auth/register")
+            .json(&serde_json::json!({"email": "new.user@loco.com", "name": "Again"}))
+            .await;
+        assert_eq!(res.status_code(), 400, "Registering again in lowercase");
+        let res = request
+            .post("/api/auth/register")
+            .json(&serde_json::json!({"email": "NEW.USER@LOCO.COM", "name": "Again"}))
+            .await;
+        assert_eq!(res.status_code(), 400, "Registering again in capitals");
+
+        let res = request
+            .post("/api/auth/send-otp")
+            .json(&serde_json::json!({"email": "NEW.USER@loco.com"}))
+            .await;
+        assert_eq!(res.status_code(), 200, "Logging in with other capitals");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn registering_validates_params() {
+    request::<App, _, _>(|request, _ctx| async move {
+        let res = request
+            .post("/api/auth/register")
+            .json(&serde_json::json!({"email": "not an email", "name": "A"}))
+            .await;
+
+        assert_eq!(res.status_code(), 400);
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert!(body["errors"]["email"].is_array());
+        assert!(body["errors"]["name"].is_array());
     })
     .await;
 }
@@ -0,0 +1,163 @@
+use bit_by_design::{
+    app::App,
+    models::{
+        _entities::{competitions, scores},
+        submissions::{self, Status},
+    },
+    tasks::gen_leaderboard::GenLeaderboard,
+};
+use loco_rs::prelude::*;
+use serial_test::serial;
+
+use super::prepare_data;
+
+/// Scores every criterion of the default rubric the same
+fn uniform(score: i32) -> Vec<(&'static str, i32)> {
+    [
+        "problem_fit",
+        "clarity",
+        "style_interpretation",
+        "originality",
+        "overall_quality",
+    ]
+    .into_iter()
+    .map(|key| (key, score))
+    .collect()
+}
+
+/// Voter 0 only uses the bottom of the scale, voter 1 only the top. Each rates
+/// three submissions nobody else rated. Returns the submission ids, rated by
+/// voter 0 first.
+async fn harsh_and_lenient_votes(
+    request: &loco_rs::TestServer,
+    ctx: &AppContext,
+) -> (Vec<prepare_data::LoggedInUser>, Vec<i32>) {
+    let users = prepare_data::create_test_users(request, ctx, 8).await;
+    let mut submissions = Vec::new();
+    for user in &users[2..] {
+        let submission = prepare_data::create_submission_for_user(ctx, user.user.id).await;
+        submissions.push(submission.id);
+    }
+    for (i, submission_id) in submissions.iter().enumerate() {
+        let voter = &users[i / 3];
+        let score = i32::try_from(i).unwrap();
+        prepare_data::create_vote_with_scores(ctx, voter.user.id, *submission_id, &uniform(score))
+            .await;
+    }
+    (users, submissions)
+}
+
+async fn final_score(ctx: &AppContext, submission_id: i32) -> i32 {
+    scores::Entity::find()
+        .filter(scores::Column::SubmissionId.eq(submission_id))
+        .one(&ctx.db)
+        .await
+        .unwrap()
+        .unwrap()
+        .final_score
+}
+
+#[tokio::test]
+#[serial]
+async fn admin_can_see_calibration_report() {
+    request::<App, _, _>(|request, ctx| async move {
+        let (users, _) = harsh_and_lenient_votes(&request, &ctx).await;
+        prepare_data::make_admin(&ctx, users[0].user.id).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&users[0].token);
+
+        let res = request
+            .get("/api/calibration")
+            .add_header(auth_key, auth_value)
+            .await;
+
+        assert_eq!(res.status_code(), 200, "Admin should see the report");
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(body["applied"], false);
+        let voters = body["voters"].as_array().unwrap();
+        assert_eq!(voters.len(), 2);
+        assert_eq!(voters[0]["voter_id"], users[1].user.id, "Lenient first");
+        assert_eq!(voters[0]["name"], "User 1");
+        assert_eq!(voters[0]["ratings"], 15);
+        assert!(voters[0]["offset"].as_f64().unwrap() > 0.0);
+        assert!(voters[1]["offset"].as_f64().unwrap() < 0.0);
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn calibration_report_leaves_out_withdrawn_submissions() {
+    request::<App, _, _>(|request, ctx| async move {
+        let (users, submissions) = harsh_and_lenient_votes(&request, &ctx).await;
+        prepare_data::make_admin(&ctx, users[0].user.id).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&users[0].token);
+
+        let withdrawn = submissions::Entity::find_by_id(submissions[5])
+            .one(&ctx.db)
+            .await
+            .unwrap()
+            .unwrap();
+        submissions::ActiveModel::remove(&ctx.db, withdrawn, Status::Withdrawn, None)
+            .await
+            .unwrap();
+
+        let res = request
+            .get("/api/calibration")
+            .add_header(auth_key, auth_value)
+            .await;
+        assert_eq!(res.status_code(), 200);
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        let voters = body["voters"].as_array().unwrap();
+        assert_eq!(voters[0]["voter_id"], users[1].user.id);
+        assert_eq!(
+            voters[0]["ratings"], 10,
+            "Ratings of the withdrawn submission should not count"
+        );
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn non_admin_cannot_see_calibration_report() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::init_user_login(&request, &ctx).await;
+        prepare_data::ensure_competition(&ctx).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user.token);
+
+        let res = request
+            .get("/api/calibration")
+            .add_header(auth_key, auth_value)
+            .await;
+
+        assert_eq!(res.status_code(), 403, "Should reject non-admin");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn calibration_corrects_harsh_voters_in_leaderboard() {
+    request::<App, _, _>(|request, ctx| async move {
+        let (_, submissions) = harsh_and_lenient_votes(&request, &ctx).await;
+        // The best of the harsh voter and the worst of the lenient voter
+        let (harsh_best, lenient_worst) = (submissions[2], submissions[3]);
+
+        let competition = prepare_data::ensure_competition(&ctx).await;
+        GenLeaderboard::generate(&ctx, &competition).await.unwrap();
+        assert!(
+            final_score(&ctx, harsh_best).await < final_score(&ctx, lenient_worst).await,
+            "Uncalibrated, the raw scores decide"
+        );
+
+        let mut item: competitions::ActiveModel = competition.into();
+        item.calibrate_voters = Set(true);
+        let competition = item.update(&ctx.db).await.unwrap();
+        GenLeaderboard::generate(&ctx, &competition).await.unwrap();
+        assert!(
+            final_score(&ctx, harsh_best).await > final_score(&ctx, lenient_worst).await,
+            "Calibrated, the harsh voter's favourite should win"
+        );
+    })
+    .await;
+}
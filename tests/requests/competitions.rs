@@ -0,0 +1,335 @@
+use bit_by_design::app::App;
+use loco_rs::testing::prelude::*;
+use serial_test::serial;
+
+use super::prepare_data;
+
+#[tokio::test]
+#[serial]
+async fn can_list_competitions() {
+    request::<App, _, _>(|request, ctx| async move {
+        // Setup: Two rounds
+        prepare_data::create_competition(&ctx, "Round 1", "round-1").await;
+        prepare_data::create_competition(&ctx, "Round 2", "round-2").await;
+
+        let res = request.get("/api/competitions").await;
+
+        assert_eq!(res.status_code(), 200, "Should list competitions");
+        let body: Vec<serde_json::Value> = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(body.len(), 2, "Should have two competitions");
+        assert_eq!(body[0]["slug"], "round-2", "Newest competition first");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn can_get_competition_by_slug_or_id() {
+    request::<App, _, _>(|request, ctx| async move {
+        let competition = prepare_data::create_competition(&ctx, "Round 1", "round-1").await;
+
+        let res = request.get("/api/competitions/round-1").await;
+        assert_eq!(res.status_code(), 200, "Should get competition by slug");
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(body["id"], competition.id);
+
+        let res = request
+            .get(&format!("/api/competitions/{}", competition.id))
+            .await;
+        assert_eq!(res.status_code(), 200, "Should get competition by id");
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(body["slug"], "round-1");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn returns_404_for_unknown_competition() {
+    request::<App, _, _>(|request, _ctx| async move {
+        let res = request.get("/api/competitions/does-not-exist").await;
+
+        assert_eq!(res.status_code(), 404, "Should return 404");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn admin_can_create_competition() {
+    request::<App, _, _>(|request, ctx| async move {
+        let admin = prepare_data::init_user_login(&request, &ctx).await;
+        prepare_data::make_admin(&ctx, admin.user.id).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&admin.token);
+
+        let params = serde_json::json!({ "name": "Spring Round 2026" });
+        let res = request
+            .post("/api/competitions")
+            .add_header(auth_key, auth_value)
+            .json(&params)
+            .await;
+
+        assert_eq!(res.status_code(), 200, "Admin should create competition");
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(body["slug"], "spring-round-2026", "Slug derived from name");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn rejects_duplicate_slug() {
+    request::<App, _, _>(|request, ctx| async move {
+        let admin = prepare_data::init_user_login(&request, &ctx).await;
+        prepare_data::make_admin(&ctx, admin.user.id).await;
+        prepare_data::create_competition(&ctx, "Round 1", "round-1").await;
+        prepare_data::create_competition(&ctx, "Round 2", "round-2").await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&admin.token);
+
+        let params = serde_json::json!({ "name": "Round 1" });
+        let res = request
+            .post("/api/competitions")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&params)
+            .await;
+        assert_eq!(res.status_code(), 400, "Derived slug is taken");
+
+        let params = serde_json::json!({ "name": "Round 2", "slug": "round-1" });
+        let res = request
+            .put("/api/competitions/round-2")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&params)
+            .await;
+        assert_eq!(res.status_code(), 400, "Slug of another competition");
+
+        let params = serde_json::json!({ "name": "Round 2" });
+        let res = request
+            .put("/api/competitions/round-2")
+            .add_header(auth_key, auth_value)
+            .json(&params)
+            .await;
+        assert_eq!(res.status_code(), 200, "Keeping its own slug is fine");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn renaming_keeps_timings_and_slug() {
+    request::<App, _, _>(|request, ctx| async move {
+        let admin = prepare_data::init_user_login(&request, &ctx).await;
+        prepare_data::make_admin(&ctx, admin.user.id).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&admin.token);
+
+        let params = serde_json::json!({
+            "name": "Round 1",
+            "submission_start": "2026-03-01T00:00:00Z",
+            "submission_end": "2026-03-08T00:00:00Z",
+            "voting_start": "2026-03-09T00:00:00Z",
+            "voting_end": "2026-03-16T00:00:00Z",
+        });
+        let res = request
+            .post("/api/competitions")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&params)
+            .await;
+        assert_eq!(res.status_code(), 200);
+        let created: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+
+        let params = serde_json::json!({ "name": "Round One" });
+        let res = request
+            .put("/api/competitions/round-1")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&params)
+            .await;
+        assert_eq!(res.status_code(), 200, "Should rename the competition");
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(body["name"], "Round One");
+        assert_eq!(body["slug"], "round-1", "The url stays stable");
+        for timing in [
+            "submission_start",
+            "submission_end",
+            "voting_start",
+            "voting_end",
+        ] {
+            assert_eq!(body[timing], created[timing], "{timing} is kept");
+        }
+
+        let params = serde_json::json!({ "name": "Round One", "voting_end": null });
+        let res = request
+            .put("/api/competitions/round-1")
+            .add_header(auth_key, auth_value)
+            .json(&params)
+            .await;
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert!(body["voting_end"].is_null(), "null unsets a timing");
+        assert_eq!(body["voting_start"], created["voting_start"]);
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn non_admin_cannot_create_competition() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::init_user_login(&request, &ctx).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user.token);
+
+        let params = serde_json::json!({ "name": "Spring Round 2026" });
+        let res = request
+            .post("/api/competitions")
+            .add_header(auth_key, auth_value)
+            .json(&params)
+            .await;
+
+        assert_eq!(res.status_code(), 403, "Should reject non-admin");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn past_round_stays_browsable() {
+    request::<App, _, _>(|request, ctx| async move {
+        // Setup: A vote in the first round, then a new round starts
+        let user1 =
+            prepare_data::init_user_login_with_email(&request, &ctx, "user1@test.com", "User 1")
+                .await;
+        let user2 =
+            prepare_data::init_user_login_with_email(&request, &ctx, "user2@test.com", "User 2")
+                .await;
+        prepare_data::create_competition(&ctx, "Round 1", "round-1").await;
+        let submission = prepare_data::create_submission_for_user(&ctx, user1.user.id).await;
+        prepare_data::create_vote(&ctx, user2.user.id, submission.id).await;
+        prepare_data::create_competition(&ctx, "Round 2", "round-2").await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user2.token);
+
+        // The current round has no votes yet
+        let res = request
+            .get("/api/votes/mine")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        assert_eq!(res.status_code(), 200);
+        let body: Vec<serde_json::Value> = serde_json::from_str(&res.text()).unwrap();
+        assert!(body.is_empty(), "Current round should have no votes");
+
+        // The past round still lists the vote
+        let res = request
+            .get("/api/competitions/round-1/votes/mine")
+            .add_header(auth_key, auth_value)
+            .await;
+        assert_eq!(res.status_code(), 200);
+        let body: Vec<serde_json::Value> = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(body.len(), 1, "Past round should keep its vote");
+        assert_eq!(body[0]["submission_id"], submission.id);
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn admin_can_choose_aggregator() {
+    request::<App, _, _>(|request, ctx| async move {
+        let admin = prepare_data::init_user_login(&request, &ctx).await;
+        prepare_data::make_admin(&ctx, admin.user.id).await;
+        prepare_data::create_competition(&ctx, "Round 1", "round-1").await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&admin.token);
+
+        let res = request.get("/api/competitions/round-1").await;
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(body["aggregator"], "median_bayesian", "Default aggregator");
+
+        let params = serde_json::json!({ "name": "Round 1", "aggregator": "bradley_terry" });
+        let res = request
+            .put("/api/competitions/round-1")
+            .add_header(auth_key, auth_value)
+            .json(&params)
+            .await;
+
+        assert_eq!(res.status_code(), 200, "Admin should change aggregator");
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(body["aggregator"], "bradley_terry");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn rejects_unknown_aggregator() {
+    request::<App, _, _>(|request, ctx| async move {
+        let admin = prepare_data::init_user_login(&request, &ctx).await;
+        prepare_data::make_admin(&ctx, admin.user.id).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&admin.token);
+
+        let params = serde_json::json!({ "name": "Round 1", "aggregator": "coin_flip" });
+        let res = request
+            .post("/api/competitions")
+            .add_header(auth_key, auth_value)
+            .json(&params)
+            .await;
+
+        assert_eq!(res.status_code(), 400, "Should reject unknown aggregator");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn admin_can_configure_reviewer_load() {
+    request::<App, _, _>(|request, ctx| async move {
+        let admin = prepare_data::init_user_login(&request, &ctx).await;
+        prepare_data::make_admin(&ctx, admin.user.id).await;
+        prepare_data::create_competition(&ctx, "Round 1", "round-1").await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&admin.token);
+
+        let res = request.get("/api/competitions/round-1").await;
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(body["reviewers_per_submission"], 6, "Default reviewers");
+        assert!(
+            body["max_reviews_per_user"].is_null(),
+            "Uncapped by default"
+        );
+
+        let params = serde_json::json!({
+            "name": "Round 1",
+            "reviewers_per_submission": 3,
+            "max_reviews_per_user": 4
+        });
+        let res = request
+            .put("/api/competitions/round-1")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&params)
+            .await;
+        assert_eq!(res.status_code(), 200, "Admin should configure reviewers");
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(body["reviewers_per_submission"], 3);
+        assert_eq!(body["max_reviews_per_user"], 4);
+
+        let params = serde_json::json!({ "name": "Round 1" });
+        let res = request
+            .put("/api/competitions/round-1")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&params)
+            .await;
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(body["max_reviews_per_user"], 4, "Missing keeps the cap");
+
+        let params = serde_json::json!({ "name": "Round 1", "max_reviews_per_user": null });
+        let res = request
+            .put("/api/competitions/round-1")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&params)
+            .await;
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert!(body["max_reviews_per_user"].is_null(), "Null lifts the cap");
+
+        let params = serde_json::json!({ "name": "Round 1", "reviewers_per_submission": 0 });
+        let res = request
+            .put("/api/competitions/round-1")
+            .add_header(auth_key, auth_value)
+            .json(&params)
+            .await;
+        assert_eq!(res.status_code(), 400, "Should reject zero reviewers");
+    })
+    .await;
+}
@@ -9,7 +9,7 @@ use super::prepare_data;
 async fn can_get_config() {
     request::<App, _, _>(|request, ctx| async move {
         // Setup: Ensure config exists
-        prepare_data::ensure_config(&ctx).await;
+        prepare_data::ensure_competition(&ctx).await;
 
         // Get config (no auth required)
         let res = request.get("/api/config").await;
@@ -45,7 +45,7 @@ async fn admin_can_update_config() {
         // Setup: Create admin user and config
         let admin = prepare_data::init_user_login(&request, &ctx).await;
         prepare_data::make_admin(&ctx, admin.user.id).await;
-        prepare_data::ensure_config(&ctx).await;
+        prepare_data::ensure_competition(&ctx).await;
         let (auth_key, auth_value) = prepare_data::auth_header(&admin.token);
 
         // Update config
@@ -79,7 +79,7 @@ async fn non_admin_cannot_update_config() {
     request::<App, _, _>(|request, ctx| async move {
         // Setup: Create regular user and config
         let user = prepare_data::init_user_login(&request, &ctx).await;
-        prepare_data::ensure_config(&ctx).await;
+        prepare_data::ensure_competition(&ctx).await;
         let (auth_key, auth_value) = prepare_data::auth_header(&user.token);
 
         // Try to update config
@@ -95,7 +95,7 @@ async fn non_admin_cannot_update_config() {
             .json(&params)
             .await;
 
-        assert_eq!(res.status_code(), 401, "Non-admin should not update config");
+        assert_eq!(res.status_code(), 403, "Non-admin should not update config");
     })
     .await;
 }
@@ -105,7 +105,7 @@ async fn non_admin_cannot_update_config() {
 async fn requires_auth_to_update_config() {
     request::<App, _, _>(|request, ctx| async move {
         // Setup: Create config
-        prepare_data::ensure_config(&ctx).await;
+        prepare_data::ensure_competition(&ctx).await;
 
         // Try to update config without auth
         let params = serde_json::json!({
@@ -124,3 +124,51 @@ async fn requires_auth_to_update_config() {
     })
     .await;
 }
+
+#[tokio::test]
+#[serial]
+async fn config_includes_phase() {
+    request::<App, _, _>(|request, ctx| async move {
+        prepare_data::set_submission_period_active(&ctx).await;
+
+        let res = request.get("/api/config").await;
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(body["phase"], "submissions_open");
+
+        prepare_data::set_submission_period_closed(&ctx).await;
+        let res = request.get("/api/config").await;
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(body["phase"], "submissions_closed");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn rejects_timings_out_of_order() {
+    request::<App, _, _>(|request, ctx| async move {
+        let admin = prepare_data::init_user_login(&request, &ctx).await;
+        prepare_data::make_admin(&ctx, admin.user.id).await;
+        prepare_data::ensure_competition(&ctx).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&admin.token);
+
+        let params = serde_json::json!({
+            "submission_start": "2025-01-01T00:00:00+00:00",
+            "submission_end": "2025-01-15T00:00:00+00:00",
+            "voting_start": "2025-01-10T00:00:00+00:00",
+            "voting_end": "2025-01-30T00:00:00+00:00"
+        });
+        let res = request
+            .put("/api/config")
+            .add_header(auth_key, auth_value)
+            .json(&params)
+            .await;
+
+        assert_eq!(
+            res.status_code(),
+            400,
+            "Voting cannot start before submissions close"
+        );
+    })
+    .await;
+}
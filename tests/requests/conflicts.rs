@@ -0,0 +1,145 @@
+use bit_by_design::{
+    app::App,
+    models::_entities::{submissions, vote_assignments},
+    tasks::assign_submissions::AssignSubmissions,
+};
+use loco_rs::prelude::*;
+use serial_test::serial;
+
+use super::prepare_data;
+
+#[tokio::test]
+#[serial]
+async fn can_declare_and_withdraw_conflict() {
+    request::<App, _, _>(|request, ctx| async move {
+        let users = prepare_data::create_test_users(&request, &ctx, 2).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&users[0].token);
+
+        let res = request
+            .post("/api/conflicts")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&serde_json::json!({ "email": "user1@test.com" }))
+            .await;
+        assert_eq!(res.status_code(), 200, "Should declare conflict");
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(body["other_user_id"], users[1].user.id);
+        assert_eq!(body["other_user_name"], "User 1");
+        let id = body["id"].as_i64().unwrap();
+
+        let res = request
+            .get("/api/conflicts/mine")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        let body: Vec<serde_json::Value> = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(body.len(), 1);
+
+        let res = request
+            .delete(&format!("/api/conflicts/{id}"))
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        assert_eq!(res.status_code(), 200, "Should withdraw conflict");
+
+        let res = request
+            .get("/api/conflicts/mine")
+            .add_header(auth_key, auth_value)
+            .await;
+        let body: Vec<serde_json::Value> = serde_json::from_str(&res.text()).unwrap();
+        assert!(body.is_empty());
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn cannot_declare_conflict_with_self_or_unknown_user() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::init_user_login(&request, &ctx).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user.token);
+
+        let res = request
+            .post("/api/conflicts")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&serde_json::json!({ "email": user.user.email }))
+            .await;
+        assert_eq!(res.status_code(), 400, "Should reject self");
+
+        let res = request
+            .post("/api/conflicts")
+            .add_header(auth_key, auth_value)
+            .json(&serde_json::json!({ "email": "nobody@test.com" }))
+            .await;
+        assert_eq!(res.status_code(), 404, "Should reject unknown user");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn cannot_withdraw_someone_elses_conflict() {
+    request::<App, _, _>(|request, ctx| async move {
+        let users = prepare_data::create_test_users(&request, &ctx, 3).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&users[0].token);
+        let res = request
+            .post("/api/conflicts")
+            .add_header(auth_key, auth_value)
+            .json(&serde_json::json!({ "email": "user1@test.com" }))
+            .await;
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        let id = body["id"].as_i64().unwrap();
+
+        let (auth_key, auth_value) = prepare_data::auth_header(&users[2].token);
+        let res = request
+            .delete(&format!("/api/conflicts/{id}"))
+            .add_header(auth_key, auth_value)
+            .await;
+        assert_eq!(res.status_code(), 404, "Should not see others' conflicts");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn assignment_respects_conflicts_and_includes_non_submitters() {
+    request::<App, _, _>(|request, ctx| async move {
+        // Users 0-5 submit, users 6 and 7 only vote
+        let users = prepare_data::create_test_users(&request, &ctx, 8).await;
+        for user in &users[..6] {
+            prepare_data::create_submission_for_user(&ctx, user.user.id).await;
+        }
+        let (auth_key, auth_value) = prepare_data::auth_header(&users[1].token);
+        request
+            .post("/api/conflicts")
+            .add_header(auth_key, auth_value)
+            .json(&serde_json::json!({ "email": "user0@test.com" }))
+            .await;
+
+        let competition = prepare_data::ensure_competition(&ctx).await;
+        let distribution = AssignSubmissions::assign(&ctx, &competition).await.unwrap();
+
+        // 6 submissions with 6 reviewers each, spread over 8 reviewers
+        assert!(distribution.is_complete(), "{distribution}");
+        assert_eq!(distribution.assignments, 36);
+        assert_eq!(distribution.load_range(), (4, 5));
+
+        let assignments = vote_assignments::Entity::find()
+            .find_also_related(submissions::Entity)
+            .all(&ctx.db)
+            .await
+            .unwrap();
+        assert_eq!(assignments.len(), 36);
+        for (assignment, submission) in &assignments {
+            let author = submission.as_ref().unwrap().user_id;
+            assert_ne!(assignment.user_id, author, "No self review");
+            let pair = (assignment.user_id, author);
+            assert_ne!(pair, (users[0].user.id, users[1].user.id), "Conflict");
+            assert_ne!(pair, (users[1].user.id, users[0].user.id), "Conflict");
+        }
+        assert!(
+            assignments
+                .iter()
+                .any(|(assignment, _)| assignment.user_id == users[7].user.id),
+            "Non-submitters should review"
+        );
+    })
+    .await;
+}
@@ -0,0 +1,258 @@
+use bit_by_design::{
+    app::App,
+    models::{_entities::scores, criterion_scores},
+    tasks::gen_leaderboard::GenLeaderboard,
+};
+use loco_rs::prelude::*;
+use serial_test::serial;
+
+use super::prepare_data;
+
+fn custom_rubric() -> serde_json::Value {
+    serde_json::json!([
+        {
+            "key": "accessibility",
+            "name": "Accessibility",
+            "description": "Can everyone use it?",
+            "min_score": 0,
+            "max_score": 8,
+            "weight": 3.0
+        },
+        {
+            "key": "motion",
+            "name": "Motion",
+            "description": null,
+            "min_score": 1,
+            "max_score": 5,
+            "weight": 1.0
+        }
+    ])
+}
+
+#[tokio::test]
+#[serial]
+async fn new_competition_has_default_rubric() {
+    request::<App, _, _>(|request, ctx| async move {
+        prepare_data::ensure_competition(&ctx).await;
+
+        let res = request.get("/api/criteria").await;
+
+        assert_eq!(res.status_code(), 200, "Should get rubric");
+        let body: Vec<serde_json::Value> = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(body.len(), 5, "Should have the five default criteria");
+        assert_eq!(body[0]["key"], "problem_fit");
+        assert_eq!(body[0]["max_score"], 5);
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn admin_can_replace_rubric() {
+    request::<App, _, _>(|request, ctx| async move {
+        let admin = prepare_data::init_user_login(&request, &ctx).await;
+        prepare_data::make_admin(&ctx, admin.user.id).await;
+        prepare_data::ensure_competition(&ctx).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&admin.token);
+
+        let res = request
+            .put("/api/criteria")
+            .add_header(auth_key, auth_value)
+            .json(&custom_rubric())
+            .await;
+
+        assert_eq!(res.status_code(), 200, "Admin should replace rubric");
+        let body: Vec<serde_json::Value> = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(body.len(), 2);
+        assert_eq!(body[0]["key"], "accessibility");
+        assert_eq!(body[1]["key"], "motion");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn non_admin_cannot_replace_rubric() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::init_user_login(&request, &ctx).await;
+        prepare_data::ensure_competition(&ctx).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user.token);
+
+        let res = request
+            .put("/api/criteria")
+            .add_header(auth_key, auth_value)
+            .json(&custom_rubric())
+            .await;
+
+        assert_eq!(res.status_code(), 403, "Should reject non-admin");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn rejects_invalid_rubric() {
+    request::<App, _, _>(|request, ctx| async move {
+        let admin = prepare_data::init_user_login(&request, &ctx).await;
+        prepare_data::make_admin(&ctx, admin.user.id).await;
+        prepare_data::ensure_competition(&ctx).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&admin.token);
+
+        let rubric = serde_json::json!([{
+            "key": "motion",
+            "name": "Motion",
+            "description": null,
+            "min_score": 5,
+            "max_score": 1,
+            "weight": 1.0
+        }]);
+        let res = request
+            .put("/api/criteria")
+            .add_header(auth_key, auth_value)
+            .json(&rubric)
+            .await;
+
+        assert_eq!(res.status_code(), 400, "Should reject an empty range");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn cannot_replace_rubric_once_voted() {
+    request::<App, _, _>(|request, ctx| async move {
+        let admin = prepare_data::init_user_login(&request, &ctx).await;
+        prepare_data::make_admin(&ctx, admin.user.id).await;
+        let submission = prepare_data::create_submission_for_user(&ctx, admin.user.id).await;
+        prepare_data::create_vote(&ctx, admin.user.id, submission.id).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&admin.token);
+
+        let res = request
+            .put("/api/criteria")
+            .add_header(auth_key, auth_value)
+            .json(&custom_rubric())
+            .await;
+
+        assert_eq!(res.status_code(), 400, "Should keep the rubric votes use");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn votes_are_validated_against_rubric() {
+    request::<App, _, _>(|request, ctx| async move {
+        let admin =
+            prepare_data::init_user_login_with_email(&request, &ctx, "admin@test.com", "Admin")
+                .await;
+        let voter =
+            prepare_data::init_user_login_with_email(&request, &ctx, "voter@test.com", "Voter")
+                .await;
+        prepare_data::make_admin(&ctx, admin.user.id).await;
+        let submission = prepare_data::create_submission_for_user(&ctx, admin.user.id).await;
+        prepare_data::create_vote_assignment(&ctx, voter.user.id, submission.id).await;
+        prepare_data::set_voting_period_active(&ctx).await;
+
+        let (auth_key, auth_value) = prepare_data::auth_header(&admin.token);
+        request
+            .put("/api/criteria")
+            .add_header(auth_key, auth_value)
+            .json(&custom_rubric())
+            .await;
+
+        let (auth_key, auth_value) = prepare_data::auth_header(&voter.token);
+
+        // The old criteria are no longer part of the rubric
+        let res = request
+            .post("/api/votes")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&prepare_data::create_vote_params(submission.id))
+            .await;
+        assert_eq!(res.status_code(), 400, "Should reject unknown criteria");
+
+        // Each criterion has its own range
+        let params = serde_json::json!({
+            "submission_id": submission.id,
+            "scores": { "accessibility": 8, "motion": 0 }
+        });
+        let res = request
+            .post("/api/votes")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&params)
+            .await;
+        assert_eq!(res.status_code(), 400, "Should reject motion below 1");
+
+        let params = serde_json::json!({
+            "submission_id": submission.id,
+            "scores": { "accessibility": 8, "motion": 1 }
+        });
+        let res = request
+            .post("/api/votes")
+            .add_header(auth_key, auth_value)
+            .json(&params)
+            .await;
+        assert_eq!(res.status_code(), 200, "Should accept a valid vote");
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(body["scores"]["accessibility"], 8);
+        assert_eq!(body["scores"]["motion"], 1);
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn leaderboard_is_weighted_by_rubric() {
+    request::<App, _, _>(|request, ctx| async move {
+        let admin =
+            prepare_data::init_user_login_with_email(&request, &ctx, "admin@test.com", "Admin")
+                .await;
+        let voter =
+            prepare_data::init_user_login_with_email(&request, &ctx, "voter@test.com", "Voter")
+                .await;
+        prepare_data::make_admin(&ctx, admin.user.id).await;
+        let competition = prepare_data::ensure_competition(&ctx).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&admin.token);
+        request
+            .put("/api/criteria")
+            .add_header(auth_key, auth_value)
+            .json(&custom_rubric())
+            .await;
+
+        let submission = prepare_data::create_submission_for_user(&ctx, voter.user.id).await;
+        prepare_data::create_vote_with_scores(
+            &ctx,
+            admin.user.id,
+            submission.id,
+            &[("accessibility", 8), ("motion", 5)],
+        )
+        .await;
+        prepare_data::create_vote_with_scores(
+            &ctx,
+            voter.user.id,
+            submission.id,
+            &[("accessibility", 4), ("motion", 5)],
+        )
+        .await;
+
+        GenLeaderboard::generate(&ctx, &competition).await.unwrap();
+
+        let score = scores::Entity::find()
+            .filter(scores::Column::SubmissionId.eq(submission.id))
+            .one(&ctx.db)
+            .await
+            .unwrap()
+            .unwrap();
+        // accessibility: median 6 of 0-8 -> 0.75, motion: median 5 of 1-5 -> 1.0
+        // weighted: (0.75 * 3 + 1.0 * 1) / 4 = 0.8125
+        assert_eq!(score.final_score, 8125);
+
+        let criteria = criterion_scores::Entity::find_by_scores(&ctx.db, vec![score.id])
+            .await
+            .unwrap()
+            .remove(&score.id)
+            .unwrap();
+        assert_eq!(criteria["accessibility"], 750);
+        assert_eq!(criteria["motion"], 1000);
+    })
+    .await;
+}
@@ -0,0 +1,173 @@
+use bit_by_design::app::App;
+use chrono::{Local, TimeDelta};
+use loco_rs::testing::prelude::*;
+use sea_orm::entity::prelude::DateTimeWithTimeZone;
+use serial_test::serial;
+
+use super::prepare_data;
+
+#[tokio::test]
+#[serial]
+async fn extension_lets_user_submit_after_deadline() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::init_user_login(&request, &ctx).await;
+        let admin =
+            prepare_data::init_user_login_with_email(&request, &ctx, "admin@test.com", "Admin")
+                .await;
+        prepare_data::make_admin(&ctx, admin.user.id).await;
+        prepare_data::set_submission_period_closed(&ctx).await;
+        let until = DateTimeWithTimeZone::from(Local::now()) + TimeDelta::hours(1);
+
+        let (auth_key, auth_value) = prepare_data::auth_header(&admin.token);
+        let res = request
+            .post("/api/extensions")
+            .add_header(auth_key, auth_value)
+            .json(&serde_json::json!({
+                "email": user.user.email,
+                "extended_until": until,
+                "reason": "Power outage"
+            }))
+            .await;
+        assert_eq!(res.status_code(), 200, "Admin should grant an extension");
+        let extension: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(extension["user_id"], user.user.id);
+        assert_eq!(extension["reason"], "Power outage");
+
+        let (auth_key, auth_value) = prepare_data::auth_header(&user.token);
+        let params = prepare_data::create_submission_params().await;
+        let res = request
+            .post("/api/submissions")
+            .add_header(auth_key, auth_value)
+            .json(&params)
+            .await;
+        assert_eq!(res.status_code(), 200, "Extension should allow submitting");
+        let submission: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(submission["late"], true);
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn extension_only_applies_to_its_user() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::init_user_login(&request, &ctx).await;
+        let other =
+            prepare_data::init_user_login_with_email(&request, &ctx, "other@test.com", "Other")
+                .await;
+        let admin =
+            prepare_data::init_user_login_with_email(&request, &ctx, "admin@test.com", "Admin")
+                .await;
+        prepare_data::make_admin(&ctx, admin.user.id).await;
+        prepare_data::set_submission_period_closed(&ctx).await;
+        let until = DateTimeWithTimeZone::from(Local::now()) + TimeDelta::hours(1);
+
+        let (auth_key, auth_value) = prepare_data::auth_header(&admin.token);
+        request
+            .post("/api/extensions")
+            .add_header(auth_key, auth_value)
+            .json(&serde_json::json!({"email": user.user.email, "extended_until": until}))
+            .await;
+
+        let (auth_key, auth_value) = prepare_data::auth_header(&other.token);
+        let params = prepare_data::create_submission_params().await;
+        let res = request
+            .post("/api/submissions")
+            .add_header(auth_key, auth_value)
+            .json(&params)
+            .await;
+        assert_eq!(
+            res.status_code(),
+            400,
+            "Others should still be held to the deadline"
+        );
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn extension_must_end_after_submission_period() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::init_user_login(&request, &ctx).await;
+        prepare_data::make_admin(&ctx, user.user.id).await;
+        prepare_data::set_submission_period_closed(&ctx).await;
+        let until = DateTimeWithTimeZone::from(Local::now()) - TimeDelta::hours(2);
+        let (auth_key, auth_value) = prepare_data::auth_header(&user.token);
+
+        let res = request
+            .post("/api/extensions")
+            .add_header(auth_key, auth_value)
+            .json(&serde_json::json!({"email": user.user.email, "extended_until": until}))
+            .await;
+
+        assert_eq!(res.status_code(), 400);
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(
+            body["errors"]["extended_until"][0]["code"],
+            "after_submission_end"
+        );
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn admin_can_list_and_revoke_extensions() {
+    request::<App, _, _>(|request, ctx| async move {
+        let admin = prepare_data::init_user_login(&request, &ctx).await;
+        prepare_data::make_admin(&ctx, admin.user.id).await;
+        prepare_data::set_submission_period_closed(&ctx).await;
+        let until = DateTimeWithTimeZone::from(Local::now()) + TimeDelta::hours(1);
+        let (auth_key, auth_value) = prepare_data::auth_header(&admin.token);
+
+        let res = request
+            .post("/api/extensions")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&serde_json::json!({"email": admin.user.email, "extended_until": until}))
+            .await;
+        let extension: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+
+        let res = request
+            .get("/api/extensions")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        let listing: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(listing.as_array().unwrap().len(), 1);
+        assert_eq!(listing[0]["user_email"], admin.user.email);
+
+        let res = request
+            .delete(&format!("/api/extensions/{}", extension["id"]))
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        assert_eq!(res.status_code(), 200);
+
+        let res = request
+            .get("/api/extensions")
+            .add_header(auth_key, auth_value)
+            .await;
+        let listing: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert!(listing.as_array().unwrap().is_empty());
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn non_admin_cannot_grant_extensions() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::init_user_login(&request, &ctx).await;
+        prepare_data::set_submission_period_closed(&ctx).await;
+        let until = DateTimeWithTimeZone::from(Local::now()) + TimeDelta::hours(1);
+        let (auth_key, auth_value) = prepare_data::auth_header(&user.token);
+
+        let res = request
+            .post("/api/extensions")
+            .add_header(auth_key, auth_value)
+            .json(&serde_json::json!({"email": user.user.email, "extended_until": until}))
+            .await;
+
+        assert_eq!(res.status_code(), 403);
+    })
+    .await;
+}
@@ -0,0 +1,88 @@
+use bit_by_design::{app::App, models::invite_codes};
+use loco_rs::testing::prelude::*;
+use serial_test::serial;
+
+use super::prepare_data;
+
+#[tokio::test]
+#[serial]
+async fn admin_can_generate_and_list_codes() {
+    request::<App, _, _>(|request, ctx| async move {
+        let admin = prepare_data::init_user_login(&request, &ctx).await;
+        prepare_data::make_admin(&ctx, admin.user.id).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&admin.token);
+
+        let res = request
+            .post("/api/admin/invite-codes")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&serde_json::json!({"count": 3, "note": "Design school"}))
+            .await;
+        assert_eq!(res.status_code(), 200);
+        let codes: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        let codes = codes.as_array().unwrap();
+        assert_eq!(codes.len(), 3);
+        assert_eq!(codes[0]["note"], "Design school");
+        assert_ne!(codes[0]["code"], codes[1]["code"]);
+
+        let res = request
+            .get("/api/admin/invite-codes")
+            .add_header(auth_key, auth_value)
+            .await;
+        let listing: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(listing.as_array().unwrap().len(), 3);
+        assert!(listing[0]["used_by_email"].is_null());
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn admin_can_revoke_unused_codes_only() {
+    request::<App, _, _>(|request, ctx| async move {
+        let admin = prepare_data::init_user_login(&request, &ctx).await;
+        prepare_data::make_admin(&ctx, admin.user.id).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&admin.token);
+        let unused = invite_codes::ActiveModel::generate(&ctx.db, admin.user.id, None)
+            .await
+            .unwrap();
+        let used = invite_codes::ActiveModel::generate(&ctx.db, admin.user.id, None)
+            .await
+            .unwrap();
+        assert!(used.redeem(&ctx.db, admin.user.id).await.unwrap());
+
+        let res = request
+            .delete(&format!("/api/admin/invite-codes/{}", used.id))
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        assert_eq!(res.status_code(), 400, "Used codes are kept");
+
+        let res = request
+            .delete(&format!("/api/admin/invite-codes/{}", unused.id))
+            .add_header(auth_key, auth_value)
+            .await;
+        assert_eq!(res.status_code(), 200);
+        assert!(invite_codes::Model::find_unused(&ctx.db, &unused.code)
+            .await
+            .unwrap()
+            .is_none());
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn non_admin_cannot_generate_codes() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::init_user_login(&request, &ctx).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user.token);
+
+        let res = request
+            .post("/api/admin/invite-codes")
+            .add_header(auth_key, auth_value)
+            .json(&serde_json::json!({}))
+            .await;
+
+        assert_eq!(res.status_code(), 403);
+    })
+    .await;
+}
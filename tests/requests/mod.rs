@@ -1,9 +1,20 @@
 mod auth;
 mod prepare_data;
 
+pub mod admin_competition;
+pub mod admin_users;
+pub mod audit_events;
+pub mod calibration;
+pub mod competitions;
 pub mod config;
+pub mod conflicts;
+pub mod criteria;
+pub mod extensions;
 pub mod integration;
+pub mod invite_codes;
+pub mod rate_limit;
 pub mod scores;
 pub mod submissions;
+pub mod teams;
 pub mod vote_assignments;
 pub mod votes;
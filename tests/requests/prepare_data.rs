@@ -1,20 +1,36 @@
+use std::collections::BTreeMap;
+
 use axum::http::{HeaderName, HeaderValue};
 use bit_by_design::{
     models::{
-        _entities::{admins, configs, submissions, vote_assignments, votes},
-        users,
+        _entities::{admins, competitions, submissions, vote_assignments, votes},
+        criteria, users,
     },
     views::auth::LoginResponse,
 };
 use chrono::{Local, TimeDelta};
 use loco_rs::{app::AppContext, prelude::*, TestServer};
 use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::QueryOrder;
 
 const USER_EMAIL: &str = "test@loco.com";
 
 pub struct LoggedInUser {
     pub user: users::Model,
     pub token: String,
+    pub refresh_token: String,
+}
+
+/// Creates a user directly in the database, without logging them in
+pub async fn create_user(ctx: &AppContext, email: &str, name: &str) -> users::Model {
+    users::ActiveModel {
+        email: Set(email.to_string()),
+        name: Set(name.to_string()),
+        ..Default::default()
+    }
+    .insert(&ctx.db)
+    .await
+    .unwrap()
 }
 
 /// Creates a user with the given email and logs them in
@@ -24,20 +40,14 @@ pub async fn init_user_login_with_email(
     email: &str,
     name: &str,
 ) -> LoggedInUser {
-    // First, create the user directly in the database
-    let user = users::ActiveModel {
-        email: Set(email.to_string()),
-        name: Set(name.to_string()),
-        ..Default::default()
-    };
-    let user = user.insert(&ctx.db).await.unwrap();
+    let user = create_user(ctx, email, name).await;
 
     // Generate OTP for the user
-    let user = user.into_active_model().create_otp(&ctx.db).await.unwrap();
+    let (user, otp) = user.into_active_model().create_otp(&ctx.db).await.unwrap();
 
     let login_payload = serde_json::json!({
         "email": user.email,
-        "otp": user.otp,
+        "otp": otp,
     });
 
     let response = request.post("/api/auth/login").json(&login_payload).await;
@@ -46,6 +56,7 @@ pub async fn init_user_login_with_email(
     LoggedInUser {
         user: users::Model::find_by_email(&ctx.db, email).await.unwrap(),
         token: login_response.token,
+        refresh_token: login_response.refresh_token,
     }
 }
 
@@ -85,23 +96,33 @@ pub async fn make_admin(ctx: &AppContext, user_id: i32) {
     admin.insert(&ctx.db).await.unwrap();
 }
 
-/// Creates a config entry if it doesn't exist
-pub async fn ensure_config(ctx: &AppContext) -> configs::Model {
-    let config = configs::Entity::find().one(&ctx.db).await.unwrap();
-    if let Some(config) = config {
-        config
+/// Creates a competition with the given name and slug
+pub async fn create_competition(ctx: &AppContext, name: &str, slug: &str) -> competitions::Model {
+    let competition = competitions::ActiveModel {
+        name: Set(name.to_string()),
+        slug: Set(slug.to_string()),
+        ..Default::default()
+    };
+    competition.insert(&ctx.db).await.unwrap()
+}
+
+/// Returns the current competition, creating one if none exists
+pub async fn ensure_competition(ctx: &AppContext) -> competitions::Model {
+    let competition = competitions::Entity::find()
+        .order_by_desc(competitions::Column::Id)
+        .one(&ctx.db)
+        .await
+        .unwrap();
+    if let Some(competition) = competition {
+        competition
     } else {
-        let config = configs::ActiveModel {
-            show_leaderboard: Set(false),
-            ..Default::default()
-        };
-        config.insert(&ctx.db).await.unwrap()
+        create_competition(ctx, "Bit by Design", "bit-by-design").await
     }
 }
 
 /// Sets the submission period to encompass "now"
 pub async fn set_submission_period_active(ctx: &AppContext) {
-    let config = ensure_config(ctx).await;
+    let config = ensure_competition(ctx).await;
     let mut config = config.into_active_model();
     let now = DateTimeWithTimeZone::from(Local::now());
     config.submission_start = Set(Some(now - TimeDelta::hours(1)));
@@ -111,7 +132,7 @@ pub async fn set_submission_period_active(ctx: &AppContext) {
 
 /// Sets the voting period to encompass "now"
 pub async fn set_voting_period_active(ctx: &AppContext) {
-    let config = ensure_config(ctx).await;
+    let config = ensure_competition(ctx).await;
     let mut config = config.into_active_model();
     let now = DateTimeWithTimeZone::from(Local::now());
     config.voting_start = Set(Some(now - TimeDelta::hours(1)));
@@ -121,7 +142,7 @@ pub async fn set_voting_period_active(ctx: &AppContext) {
 
 /// Sets the submission period to the past (closed)
 pub async fn set_submission_period_closed(ctx: &AppContext) {
-    let config = ensure_config(ctx).await;
+    let config = ensure_competition(ctx).await;
     let mut config = config.into_active_model();
     let now = DateTimeWithTimeZone::from(Local::now());
     config.submission_start = Set(Some(now - TimeDelta::hours(2)));
@@ -129,9 +150,17 @@ pub async fn set_submission_period_closed(ctx: &AppContext) {
     config.save(&ctx.db).await.unwrap();
 }
 
+/// Sets the grace period accepting submissions after the submission period
+pub async fn set_grace_period(ctx: &AppContext, minutes: i32) {
+    let config = ensure_competition(ctx).await;
+    let mut config = config.into_active_model();
+    config.grace_period_minutes = Set(minutes);
+    config.save(&ctx.db).await.unwrap();
+}
+
 /// Sets the voting period to the past (closed)
 pub async fn set_voting_period_closed(ctx: &AppContext) {
-    let config = ensure_config(ctx).await;
+    let config = ensure_competition(ctx).await;
     let mut config = config.into_active_model();
     let now = DateTimeWithTimeZone::from(Local::now());
     config.voting_start = Set(Some(now - TimeDelta::hours(2)));
@@ -139,18 +168,18 @@ pub async fn set_voting_period_closed(ctx: &AppContext) {
     config.save(&ctx.db).await.unwrap();
 }
 
-/// Sets show_leaderboard to true
+/// Publishes the leaderboard
 pub async fn enable_leaderboard(ctx: &AppContext) {
-    let config = ensure_config(ctx).await;
+    let config = ensure_competition(ctx).await;
     let mut config = config.into_active_model();
-    config.show_leaderboard = Set(true);
+    config.entered_phase = Set("published".to_string());
     config.save(&ctx.db).await.unwrap();
 }
 
 /// Creates a submission for a user
 pub async fn create_submission_params() -> serde_json::Value {
     serde_json::json!({
-        "figma_link": "https://figma.com/file/test",
+        "figma_link": "https://www.figma.com/design/TestFileKey00000000000",
         "design_image": "https://example.com/image.png",
         "target_user_and_goal": "Target users are designers who want to improve their workflow",
         "layout_explanation": "The layout uses a grid system for consistency",
@@ -162,23 +191,65 @@ pub async fn create_submission_params() -> serde_json::Value {
     })
 }
 
+/// Encodes a blank image of the given size as PNG
+pub fn png_image(width: u32, height: u32) -> Vec<u8> {
+    let mut out = std::io::Cursor::new(Vec::new());
+    image::DynamicImage::new_rgb8(width, height)
+        .write_to(&mut out, image::ImageFormat::Png)
+        .unwrap();
+    out.into_inner()
+}
+
+/// A multipart form with `bytes` as its `image` field, returning the form's
+/// content type and body
+pub fn multipart_image(content_type: &str, bytes: &[u8]) -> (String, Vec<u8>) {
+    const BOUNDARY: &str = "design-image-boundary";
+    let mut body = format!(
+        "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"image\"; \
+         filename=\"design\"\r\nContent-Type: {content_type}\r\n\r\n"
+    )
+    .into_bytes();
+    body.extend_from_slice(bytes);
+    body.extend_from_slice(format!("\r\n--{BOUNDARY}--\r\n").as_bytes());
+    (format!("multipart/form-data; boundary={BOUNDARY}"), body)
+}
+
+/// Uploads an 800x600 design image, returning its URL
+pub async fn upload_design_image(request: &TestServer, token: &str) -> String {
+    let png = png_image(800, 600);
+    let (content_type, body) = multipart_image("image/png", &png);
+    let (auth_key, auth_value) = auth_header(token);
+    let res = request
+        .post("/api/submissions/image")
+        .add_header(auth_key, auth_value)
+        .content_type(&content_type)
+        .bytes(body.into())
+        .await;
+    let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+    body["url"].as_str().unwrap().to_string()
+}
+
 /// Creates a vote params for a submission
 pub fn create_vote_params(submission_id: i32) -> serde_json::Value {
     serde_json::json!({
         "submission_id": submission_id,
-        "problem_fit_score": 4,
-        "clarity_score": 5,
-        "style_interpretation_score": 3,
-        "originality_score": 4,
-        "overall_quality_score": 4
+        "scores": {
+            "problem_fit": 4,
+            "clarity": 5,
+            "style_interpretation": 3,
+            "originality": 4,
+            "overall_quality": 4
+        }
     })
 }
 
 /// Directly creates a submission in the database for a user
 pub async fn create_submission_for_user(ctx: &AppContext, user_id: i32) -> submissions::Model {
+    let competition = ensure_competition(ctx).await;
     let submission = submissions::ActiveModel {
+        competition_id: Set(competition.id),
         user_id: Set(user_id),
-        figma_link: Set("https://figma.com/file/test".to_string()),
+        figma_link: Set("https://www.figma.com/design/TestFileKey00000000000".to_string()),
         design_image: Set("https://example.com/image.png".to_string()),
         target_user_and_goal: Set("Target users are designers".to_string()),
         layout_explanation: Set("Grid layout".to_string()),
@@ -198,7 +269,9 @@ pub async fn create_vote_assignment(
     user_id: i32,
     submission_id: i32,
 ) -> vote_assignments::Model {
+    let competition = ensure_competition(ctx).await;
     let assignment = vote_assignments::ActiveModel {
+        competition_id: Set(competition.id),
         user_id: Set(user_id),
         submission_id: Set(submission_id),
         ..Default::default()
@@ -208,15 +281,44 @@ pub async fn create_vote_assignment(
 
 /// Creates a vote
 pub async fn create_vote(ctx: &AppContext, user_id: i32, submission_id: i32) -> votes::Model {
+    create_vote_with_scores(
+        ctx,
+        user_id,
+        submission_id,
+        &[
+            ("problem_fit", 4),
+            ("clarity", 5),
+            ("style_interpretation", 3),
+            ("originality", 4),
+            ("overall_quality", 4),
+        ],
+    )
+    .await
+}
+
+/// Creates a vote with the given score per criterion key
+pub async fn create_vote_with_scores(
+    ctx: &AppContext,
+    user_id: i32,
+    submission_id: i32,
+    scores: &[(&str, i32)],
+) -> votes::Model {
+    let competition = ensure_competition(ctx).await;
     let vote = votes::ActiveModel {
+        competition_id: Set(competition.id),
         user_id: Set(user_id),
         submission_id: Set(submission_id),
-        problem_fit_score: Set(4),
-        clarity_score: Set(5),
-        style_interpretation_score: Set(3),
-        originality_score: Set(4),
-        overall_quality_score: Set(4),
         ..Default::default()
     };
-    vote.insert(&ctx.db).await.unwrap()
+    let vote = vote.insert(&ctx.db).await.unwrap();
+
+    let rubric = criteria::Model::for_competition(&ctx.db, competition.id)
+        .await
+        .unwrap();
+    let scores = scores
+        .iter()
+        .map(|(key, score)| ((*key).to_string(), *score))
+        .collect::<BTreeMap<_, _>>();
+    vote.set_scores(&ctx.db, &rubric, &scores).await.unwrap();
+    vote
 }
@@ -0,0 +1,88 @@
+use bit_by_design::app::App;
+use loco_rs::testing::prelude::*;
+use serial_test::serial;
+
+use super::prepare_data;
+
+#[tokio::test]
+#[serial]
+async fn limits_otps_sent_per_email() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::create_user(&ctx, "otp@test.com", "Otp").await;
+
+        for _ in 0..3 {
+            let res = request
+                .post("/api/auth/send-otp")
+                .json(&serde_json::json!({"email": user.email}))
+                .await;
+            assert_eq!(res.status_code(), 200);
+        }
+        // emails are compared case-insensitively
+        let res = request
+            .post("/api/auth/send-otp")
+            .json(&serde_json::json!({"email": "OTP@test.com"}))
+            .await;
+
+        assert_eq!(res.status_code(), 429, "Should limit further OTPs");
+        let retry_after: u64 = res.header("retry-after").to_str().unwrap().parse().unwrap();
+        assert!(retry_after > 0);
+        assert_eq!(ctx.mailer.as_ref().unwrap().deliveries().count, 3);
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn limits_otps_sent_per_ip() {
+    request::<App, _, _>(|request, _ctx| async move {
+        for i in 0..10 {
+            let res = request
+                .post("/api/auth/send-otp")
+                .json(&serde_json::json!({"email": format!("user{i}@test.com")}))
+                .await;
+            assert_ne!(res.status_code(), 429);
+        }
+        let res = request
+            .post("/api/auth/send-otp")
+            .json(&serde_json::json!({"email": "another@test.com"}))
+            .await;
+
+        assert_eq!(res.status_code(), 429, "Should limit the IP across emails");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn limits_login_attempts_per_email() {
+    request::<App, _, _>(|request, _ctx| async move {
+        let login = serde_json::json!({"email": "nobody@test.com", "otp": "wrong"});
+        for _ in 0..10 {
+            let res = request.post("/api/auth/login").json(&login).await;
+            assert_eq!(res.status_code(), 401);
+        }
+        let res = request.post("/api/auth/login").json(&login).await;
+
+        assert_eq!(res.status_code(), 429, "Should limit guessing");
+        assert!(res.maybe_header("retry-after").is_some());
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn other_routes_are_not_limited() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::init_user_login(&request, &ctx).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user.token);
+
+        for _ in 0..30 {
+            let res = request
+                .get("/api/auth/current")
+                .add_header(auth_key.clone(), auth_value.clone())
+                .await;
+            assert_eq!(res.status_code(), 200);
+        }
+    })
+    .await;
+}
@@ -14,12 +14,8 @@ async fn can_get_scores_when_leaderboard_enabled() {
 
         // Create a score directly
         let score = scores::ActiveModel {
+            competition_id: Set(submission.competition_id),
             submission_id: Set(submission.id),
-            problem_fit_score: Set(4),
-            visual_clarity_score: Set(5),
-            style_interpretation_score: Set(3),
-            originality_score: Set(4),
-            overall_quality_score: Set(4),
             final_score: Set(800),
             ..Default::default()
         };
@@ -52,19 +48,15 @@ async fn returns_404_when_leaderboard_disabled() {
 
         // Create a score directly
         let score = scores::ActiveModel {
+            competition_id: Set(submission.competition_id),
             submission_id: Set(submission.id),
-            problem_fit_score: Set(4),
-            visual_clarity_score: Set(5),
-            style_interpretation_score: Set(3),
-            originality_score: Set(4),
-            overall_quality_score: Set(4),
             final_score: Set(800),
             ..Default::default()
         };
         score.insert(&ctx.db).await.unwrap();
 
         // Ensure config exists but leaderboard is disabled (default)
-        prepare_data::ensure_config(&ctx).await;
+        prepare_data::ensure_competition(&ctx).await;
 
         // Get scores
         let res = request.get("/api/scores").await;
@@ -108,3 +100,66 @@ async fn returns_empty_array_when_no_scores() {
     })
     .await;
 }
+
+#[tokio::test]
+#[serial]
+async fn admin_can_manage_scores() {
+    request::<App, _, _>(|request, ctx| async move {
+        let admin = prepare_data::init_user_login(&request, &ctx).await;
+        prepare_data::make_admin(&ctx, admin.user.id).await;
+        let submission = prepare_data::create_submission_for_user(&ctx, admin.user.id).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&admin.token);
+
+        let params = serde_json::json!({ "submission_id": submission.id, "final_score": 700 });
+        let res = request
+            .post("/api/scores")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&params)
+            .await;
+        assert_eq!(res.status_code(), 200, "Admin should add a score");
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        let id = body["id"].as_i64().unwrap();
+
+        let params = serde_json::json!({ "submission_id": submission.id, "final_score": 900 });
+        let res = request
+            .put(&format!("/api/scores/{id}"))
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&params)
+            .await;
+        assert_eq!(res.status_code(), 200, "Admin should correct a score");
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(body["final_score"], 900);
+
+        let res = request
+            .delete(&format!("/api/scores/{id}"))
+            .add_header(auth_key, auth_value)
+            .await;
+        assert_eq!(res.status_code(), 200, "Admin should remove a score");
+        assert!(scores::Entity::find_by_id(i32::try_from(id).unwrap())
+            .one(&ctx.db)
+            .await
+            .unwrap()
+            .is_none());
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn non_admin_cannot_manage_scores() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::init_user_login(&request, &ctx).await;
+        let submission = prepare_data::create_submission_for_user(&ctx, user.user.id).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user.token);
+
+        let params = serde_json::json!({ "submission_id": submission.id, "final_score": 1000 });
+        let res = request
+            .post("/api/scores")
+            .add_header(auth_key, auth_value)
+            .json(&params)
+            .await;
+
+        assert_eq!(res.status_code(), 403, "Should reject non-admin");
+    })
+    .await;
+}
@@ -1,5 +1,13 @@
-use bit_by_design::app::App;
+use bit_by_design::{
+    app::App,
+    models::{
+        _entities::{scores, vote_assignments, votes},
+        submissions,
+    },
+    tasks::gen_leaderboard::GenLeaderboard,
+};
 use loco_rs::testing::prelude::*;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
 use serial_test::serial;
 
 use super::prepare_data;
@@ -29,7 +37,10 @@ async fn can_create_submission_during_submission_period() {
 
         // Verify response contains the submission data
         let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
-        assert_eq!(body["figma_link"], "https://figma.com/file/test");
+        assert_eq!(
+            body["figma_link"],
+            "https://www.figma.com/design/TestFileKey00000000000"
+        );
         assert_eq!(body["user_id"], user.user.id);
     })
     .await;
@@ -136,11 +147,12 @@ async fn can_update_own_submission() {
         // Setup: Create user with submission
         let user = prepare_data::init_user_login(&request, &ctx).await;
         let submission = prepare_data::create_submission_for_user(&ctx, user.user.id).await;
+        prepare_data::set_submission_period_active(&ctx).await;
         let (auth_key, auth_value) = prepare_data::auth_header(&user.token);
 
         // Update submission
         let updated_params = serde_json::json!({
-            "figma_link": "https://figma.com/file/updated",
+            "figma_link": "https://www.figma.com/design/UpdatedFileKey00000000",
             "design_image": "https://example.com/updated.png",
             "target_user_and_goal": "Updated target",
             "layout_explanation": "Updated layout",
@@ -159,7 +171,10 @@ async fn can_update_own_submission() {
 
         assert_eq!(res.status_code(), 200, "Should update submission");
         let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
-        assert_eq!(body["figma_link"], "https://figma.com/file/updated");
+        assert_eq!(
+            body["figma_link"],
+            "https://www.figma.com/design/UpdatedFileKey00000000"
+        );
     })
     .await;
 }
@@ -186,7 +201,7 @@ async fn cannot_update_others_submission() {
             .json(&updated_params)
             .await;
 
-        assert_eq!(res.status_code(), 401, "Should reject unauthorized update");
+        assert_eq!(res.status_code(), 403, "Should reject unauthorized update");
     })
     .await;
 }
@@ -285,7 +300,7 @@ async fn admin_can_update_any_submission() {
 
         // Admin updates any submission
         let updated_params = serde_json::json!({
-            "figma_link": "https://figma.com/file/admin-updated",
+            "figma_link": "https://www.figma.com/design/AdminUpdatedKey0000000",
             "design_image": "https://example.com/admin.png",
             "target_user_and_goal": "Admin updated",
             "layout_explanation": "Admin layout",
@@ -306,3 +321,824 @@ async fn admin_can_update_any_submission() {
     })
     .await;
 }
+
+#[tokio::test]
+#[serial]
+async fn cannot_update_own_submission_outside_submission_period() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::init_user_login(&request, &ctx).await;
+        let submission = prepare_data::create_submission_for_user(&ctx, user.user.id).await;
+        prepare_data::set_submission_period_closed(&ctx).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user.token);
+
+        let params = prepare_data::create_submission_params().await;
+        let res = request
+            .put(&format!("/api/submissions/{}", submission.id))
+            .add_header(auth_key, auth_value)
+            .json(&params)
+            .await;
+
+        assert_eq!(
+            res.status_code(),
+            400,
+            "Should reject edits after the submission period"
+        );
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn updates_keep_revisions() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::init_user_login(&request, &ctx).await;
+        let admin =
+            prepare_data::init_user_login_with_email(&request, &ctx, "admin@test.com", "Admin")
+                .await;
+        prepare_data::make_admin(&ctx, admin.user.id).await;
+        let submission = prepare_data::create_submission_for_user(&ctx, user.user.id).await;
+        prepare_data::set_submission_period_active(&ctx).await;
+
+        for (token, link) in [
+            (
+                &user.token,
+                "https://www.figma.com/design/SecondFileKey000000000",
+            ),
+            (
+                &admin.token,
+                "https://www.figma.com/design/ThirdFileKey0000000000",
+            ),
+        ] {
+            let mut params = prepare_data::create_submission_params().await;
+            params["figma_link"] = serde_json::json!(link);
+            let (auth_key, auth_value) = prepare_data::auth_header(token);
+            let res = request
+                .put(&format!("/api/submissions/{}", submission.id))
+                .add_header(auth_key, auth_value)
+                .json(&params)
+                .await;
+            assert_eq!(res.status_code(), 200);
+        }
+
+        let (auth_key, auth_value) = prepare_data::auth_header(&admin.token);
+        let res = request
+            .get(&format!("/api/submissions/{}/revisions", submission.id))
+            .add_header(auth_key, auth_value)
+            .await;
+        assert_eq!(res.status_code(), 200, "Admin should list revisions");
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        let revisions = body.as_array().unwrap();
+        assert_eq!(revisions.len(), 2, "Should keep each replaced version");
+        assert_eq!(revisions[0]["revision"], 1);
+        assert_eq!(revisions[0]["editor_id"], user.user.id);
+        assert_eq!(
+            revisions[0]["snapshot"]["figma_link"],
+            submission.figma_link
+        );
+        assert_eq!(revisions[1]["revision"], 2);
+        assert_eq!(revisions[1]["editor_id"], admin.user.id);
+        assert_eq!(
+            revisions[1]["snapshot"]["figma_link"],
+            "https://www.figma.com/design/SecondFileKey000000000"
+        );
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn non_admin_cannot_list_revisions() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::init_user_login(&request, &ctx).await;
+        let submission = prepare_data::create_submission_for_user(&ctx, user.user.id).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user.token);
+
+        let res = request
+            .get(&format!("/api/submissions/{}/revisions", submission.id))
+            .add_header(auth_key, auth_value)
+            .await;
+
+        assert_eq!(res.status_code(), 403, "Authors cannot list revisions");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn can_upload_design_image() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::init_user_login(&request, &ctx).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user.token);
+
+        let png = prepare_data::png_image(640, 480);
+        let (content_type, body) = prepare_data::multipart_image("image/png", &png);
+        let res = request
+            .post("/api/submissions/image")
+            .add_header(auth_key, auth_value)
+            .content_type(&content_type)
+            .bytes(body.into())
+            .await;
+        assert_eq!(res.status_code(), 200, "Should accept a PNG upload");
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(body["width"], 640);
+        assert_eq!(body["height"], 480);
+        let url = body["url"].as_str().unwrap();
+        assert!(url.starts_with("/api/submissions/image/"));
+
+        let res = request.get(url).await;
+        assert_eq!(res.status_code(), 200, "Should serve the stored image");
+        assert_eq!(res.header("content-type"), "image/png");
+        assert_eq!(res.as_bytes().as_ref(), png.as_slice());
+
+        let res = request.get(body["thumbnail_url"].as_str().unwrap()).await;
+        assert_eq!(res.status_code(), 200, "Should serve the thumbnail");
+        assert_eq!(res.header("content-type"), "image/jpeg");
+        let thumbnail = image::load_from_memory(res.as_bytes()).unwrap();
+        assert_eq!((thumbnail.width(), thumbnail.height()), (480, 360));
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn rejects_invalid_design_images() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::init_user_login(&request, &ctx).await;
+
+        let png = prepare_data::png_image(640, 480);
+        let tiny = prepare_data::png_image(64, 64);
+        for (content_type, bytes, reason) in [
+            ("text/plain", png.as_slice(), "unsupported content type"),
+            (
+                "image/jpeg",
+                png.as_slice(),
+                "content not matching its type",
+            ),
+            (
+                "image/png",
+                b"not an image".as_slice(),
+                "undecodable content",
+            ),
+            ("image/png", tiny.as_slice(), "too small images"),
+        ] {
+            let (form_type, body) = prepare_data::multipart_image(content_type, bytes);
+            let (auth_key, auth_value) = prepare_data::auth_header(&user.token);
+            let res = request
+                .post("/api/submissions/image")
+                .add_header(auth_key, auth_value)
+                .content_type(&form_type)
+                .bytes(body.into())
+                .await;
+            assert_eq!(res.status_code(), 400, "Should reject {reason}");
+        }
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn cannot_upload_design_image_without_login() {
+    request::<App, _, _>(|request, _ctx| async move {
+        let png = prepare_data::png_image(640, 480);
+        let (content_type, body) = prepare_data::multipart_image("image/png", &png);
+        let res = request
+            .post("/api/submissions/image")
+            .content_type(&content_type)
+            .bytes(body.into())
+            .await;
+
+        assert_eq!(res.status_code(), 401, "Should require login to upload");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn uploaded_design_images_get_previews() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::init_user_login(&request, &ctx).await;
+        prepare_data::set_submission_period_active(&ctx).await;
+        let image = prepare_data::upload_design_image(&request, &user.token).await;
+
+        let mut params = prepare_data::create_submission_params().await;
+        params["design_image"] = serde_json::json!(image);
+        let (auth_key, auth_value) = prepare_data::auth_header(&user.token);
+        let res = request
+            .post("/api/submissions")
+            .add_header(auth_key, auth_value)
+            .json(&params)
+            .await;
+        assert_eq!(res.status_code(), 200);
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+
+        let submission = submissions::Entity::find_by_id(body["id"].as_i64().unwrap() as i32)
+            .one(&ctx.db)
+            .await
+            .unwrap()
+            .unwrap();
+        let gallery = submission.gallery_images.expect("gallery thumbnails");
+        let widths: Vec<_> = gallery
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|thumbnail| thumbnail["width"].as_u64().unwrap())
+            .collect();
+        assert_eq!(widths, [320, 640, 800], "None wider than the image");
+        let res = request.get(gallery[1]["url"].as_str().unwrap()).await;
+        assert_eq!(res.status_code(), 200, "Should serve the gallery thumbnail");
+        let thumbnail = image::load_from_memory(res.as_bytes()).unwrap();
+        assert_eq!((thumbnail.width(), thumbnail.height()), (640, 480));
+
+        let placeholder = submission.placeholder_image.expect("placeholder");
+        assert!(placeholder.starts_with(&image[..image.rfind('/').unwrap()]));
+        let res = request.get(&placeholder).await;
+        assert_eq!(res.status_code(), 200, "Should serve the placeholder");
+        assert_eq!(res.header("content-type"), "image/jpeg");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn replacing_design_image_regenerates_previews() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::init_user_login(&request, &ctx).await;
+        let submission = prepare_data::create_submission_for_user(&ctx, user.user.id).await;
+        prepare_data::set_submission_period_active(&ctx).await;
+        assert!(submission.placeholder_image.is_none());
+
+        let image = prepare_data::upload_design_image(&request, &user.token).await;
+        let mut params = prepare_data::create_submission_params().await;
+        params["design_image"] = serde_json::json!(image);
+        let (auth_key, auth_value) = prepare_data::auth_header(&user.token);
+        let res = request
+            .put(&format!("/api/submissions/{}", submission.id))
+            .add_header(auth_key, auth_value)
+            .json(&params)
+            .await;
+        assert_eq!(res.status_code(), 200);
+
+        let updated = submissions::Entity::find_by_id(submission.id)
+            .one(&ctx.db)
+            .await
+            .unwrap()
+            .unwrap();
+        let placeholder = updated.placeholder_image.expect("placeholder");
+        assert!(placeholder.starts_with(&image[..image.rfind('/').unwrap()]));
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn figma_links_are_normalised() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::init_user_login(&request, &ctx).await;
+        prepare_data::set_submission_period_active(&ctx).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user.token);
+
+        let mut params = prepare_data::create_submission_params().await;
+        params["figma_link"] = serde_json::json!(
+            "https://figma.com/proto/TestFileKey00000000000/Flow?node-id=3-4&t=tracking"
+        );
+        let res = request
+            .post("/api/submissions")
+            .add_header(auth_key, auth_value)
+            .json(&params)
+            .await;
+
+        assert_eq!(res.status_code(), 200);
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(
+            body["figma_link"],
+            "https://www.figma.com/proto/TestFileKey00000000000?node-id=3-4"
+        );
+        assert_eq!(body["figma_file_key"], "TestFileKey00000000000");
+        assert_eq!(body["figma_node_id"], "3:4");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn rejects_invalid_figma_link() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::init_user_login(&request, &ctx).await;
+        prepare_data::set_submission_period_active(&ctx).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user.token);
+
+        let mut params = prepare_data::create_submission_params().await;
+        params["figma_link"] = serde_json::json!("https://example.com/my-design");
+        let res = request
+            .post("/api/submissions")
+            .add_header(auth_key, auth_value)
+            .json(&params)
+            .await;
+
+        assert_eq!(res.status_code(), 400, "Should reject non Figma links");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn rejects_figma_file_of_another_participant() {
+    request::<App, _, _>(|request, ctx| async move {
+        let users = prepare_data::create_test_users(&request, &ctx, 2).await;
+        prepare_data::set_submission_period_active(&ctx).await;
+
+        let mut statuses = Vec::new();
+        for (user, node) in users.iter().zip(["1-2", "5-6"]) {
+            let mut params = prepare_data::create_submission_params().await;
+            params["figma_link"] = serde_json::json!(format!(
+                "https://www.figma.com/design/TestFileKey00000000000?node-id={node}"
+            ));
+            let (auth_key, auth_value) = prepare_data::auth_header(&user.token);
+            let res = request
+                .post("/api/submissions")
+                .add_header(auth_key, auth_value)
+                .json(&params)
+                .await;
+            statuses.push(res.status_code().as_u16());
+        }
+
+        assert_eq!(
+            statuses,
+            [200, 400],
+            "Should reject a file another participant submitted"
+        );
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn reports_every_invalid_submission_field() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::init_user_login(&request, &ctx).await;
+        prepare_data::set_submission_period_active(&ctx).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user.token);
+
+        let mut params = prepare_data::create_submission_params().await;
+        params["figma_link"] = serde_json::json!("https://example.com/design");
+        params["design_image"] = serde_json::json!("not a url");
+        params["layout_explanation"] = serde_json::json!("");
+        params["key_trade_off"] = serde_json::json!("x".repeat(2001));
+        params["originality_confirmed"] = serde_json::json!(false);
+        let res = request
+            .post("/api/submissions")
+            .add_header(auth_key, auth_value)
+            .json(&params)
+            .await;
+
+        assert_eq!(res.status_code(), 400, "Should reject invalid fields");
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        let errors = body["errors"].as_object().unwrap();
+        let codes: Vec<_> = errors
+            .iter()
+            .map(|(field, errors)| (field.as_str(), errors[0]["code"].as_str().unwrap()))
+            .collect();
+        assert_eq!(
+            codes,
+            [
+                ("design_image", "url"),
+                ("figma_link", "figma_link"),
+                ("key_trade_off", "length"),
+                ("layout_explanation", "length"),
+                ("originality_confirmed", "must_be_true"),
+            ]
+        );
+        assert!(errors
+            .values()
+            .all(|errors| errors[0]["message"].is_string()));
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn can_save_incomplete_draft() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::init_user_login(&request, &ctx).await;
+        prepare_data::set_submission_period_active(&ctx).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user.token);
+
+        let res = request
+            .put("/api/submissions/draft")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&serde_json::json!({"layout_explanation": "Grid"}))
+            .await;
+        assert_eq!(res.status_code(), 200, "Should save an incomplete draft");
+        let draft: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(draft["status"], "draft");
+        assert_eq!(draft["layout_explanation"], "Grid");
+
+        let res = request
+            .put("/api/submissions/draft")
+            .add_header(auth_key, auth_value)
+            .json(&serde_json::json!({
+                "figma_link": "https://www.figma.com/design/TestFileKey00000000000/Title?t=abc",
+                "layout_explanation": "Grid layout",
+            }))
+            .await;
+        assert_eq!(res.status_code(), 200, "Should update the draft");
+        let updated: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(updated["id"], draft["id"], "Should keep a single draft");
+        assert_eq!(
+            updated["figma_link"],
+            "https://www.figma.com/design/TestFileKey00000000000"
+        );
+
+        let competition = prepare_data::ensure_competition(&ctx).await;
+        let finalised = submissions::Entity::find_finalised(competition.id)
+            .all(&ctx.db)
+            .await
+            .unwrap();
+        assert!(finalised.is_empty(), "Drafts should not be reviewed");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn rejects_malformed_draft_fields() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::init_user_login(&request, &ctx).await;
+        prepare_data::set_submission_period_active(&ctx).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user.token);
+
+        let res = request
+            .put("/api/submissions/draft")
+            .add_header(auth_key, auth_value)
+            .json(&serde_json::json!({"figma_link": "https://example.com/design"}))
+            .await;
+
+        assert_eq!(res.status_code(), 400, "Should reject a malformed link");
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(body["errors"]["figma_link"][0]["code"], "figma_link");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn cannot_save_draft_over_final_submission() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::init_user_login(&request, &ctx).await;
+        prepare_data::set_submission_period_active(&ctx).await;
+        prepare_data::create_submission_for_user(&ctx, user.user.id).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user.token);
+
+        let res = request
+            .put("/api/submissions/draft")
+            .add_header(auth_key, auth_value)
+            .json(&serde_json::json!({}))
+            .await;
+
+        assert_eq!(
+            res.status_code(),
+            400,
+            "Should not reopen a final submission"
+        );
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn finalising_validates_the_draft() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::init_user_login(&request, &ctx).await;
+        prepare_data::set_submission_period_active(&ctx).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user.token);
+
+        let mut params = prepare_data::create_submission_params().await;
+        params["key_trade_off"] = serde_json::json!("");
+        let res = request
+            .put("/api/submissions/draft")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&params)
+            .await;
+        let draft: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        let finalise = format!("/api/submissions/{}/finalise", draft["id"]);
+
+        let res = request
+            .post(&finalise)
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        assert_eq!(res.status_code(), 400, "Should reject an incomplete draft");
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        let fields: Vec<_> = body["errors"].as_object().unwrap().keys().collect();
+        assert_eq!(fields, ["key_trade_off"]);
+
+        params["key_trade_off"] = serde_json::json!("Simplicity over features");
+        request
+            .put("/api/submissions/draft")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&params)
+            .await;
+        let res = request
+            .post(&finalise)
+            .add_header(auth_key, auth_value)
+            .await;
+        assert_eq!(res.status_code(), 200, "Should finalise a complete draft");
+        let submission: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(submission["status"], "final");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn creating_submission_finalises_draft() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::init_user_login(&request, &ctx).await;
+        prepare_data::set_submission_period_active(&ctx).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user.token);
+
+        let res = request
+            .put("/api/submissions/draft")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&serde_json::json!({"layout_explanation": "Grid"}))
+            .await;
+        let draft: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+
+        let params = prepare_data::create_submission_params().await;
+        let res = request
+            .post("/api/submissions")
+            .add_header(auth_key, auth_value)
+            .json(&params)
+            .await;
+        assert_eq!(res.status_code(), 200, "Should finalise the draft");
+        let submission: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(submission["id"], draft["id"]);
+        assert_eq!(submission["status"], "final");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn can_withdraw_own_submission() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::init_user_login(&request, &ctx).await;
+        prepare_data::set_submission_period_active(&ctx).await;
+        let submission = prepare_data::create_submission_for_user(&ctx, user.user.id).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user.token);
+
+        let res = request
+            .post(&format!("/api/submissions/{}/withdraw", submission.id))
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        assert_eq!(res.status_code(), 200, "Should withdraw own submission");
+        let withdrawn: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(withdrawn["status"], "withdrawn");
+        assert!(withdrawn["removed_at"].is_string());
+        assert_eq!(ctx.mailer.as_ref().unwrap().deliveries().count, 1);
+
+        let params = prepare_data::create_submission_params().await;
+        let res = request
+            .post("/api/submissions")
+            .add_header(auth_key, auth_value)
+            .json(&params)
+            .await;
+        assert_eq!(res.status_code(), 200, "Should be able to submit again");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn cannot_withdraw_outside_submission_period() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::init_user_login(&request, &ctx).await;
+        let submission = prepare_data::create_submission_for_user(&ctx, user.user.id).await;
+        prepare_data::set_submission_period_closed(&ctx).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user.token);
+
+        let res = request
+            .post(&format!("/api/submissions/{}/withdraw", submission.id))
+            .add_header(auth_key, auth_value)
+            .await;
+
+        assert_eq!(res.status_code(), 400, "Should only withdraw while open");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn admin_can_disqualify_submission() {
+    request::<App, _, _>(|request, ctx| async move {
+        let author = prepare_data::init_user_login(&request, &ctx).await;
+        let admin =
+            prepare_data::init_user_login_with_email(&request, &ctx, "admin@test.com", "Admin")
+                .await;
+        prepare_data::make_admin(&ctx, admin.user.id).await;
+        let submission = prepare_data::create_submission_for_user(&ctx, author.user.id).await;
+        prepare_data::create_vote_assignment(&ctx, admin.user.id, submission.id).await;
+        let vote = prepare_data::create_vote(&ctx, admin.user.id, submission.id).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&admin.token);
+
+        let res = request
+            .post(&format!("/api/submissions/{}/disqualify", submission.id))
+            .add_header(auth_key, auth_value)
+            .json(&serde_json::json!({"reason": "Copied from another entry"}))
+            .await;
+        assert_eq!(res.status_code(), 200, "Admin should disqualify");
+        let disqualified: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(disqualified["status"], "disqualified");
+        assert_eq!(disqualified["removal_reason"], "Copied from another entry");
+        assert_eq!(ctx.mailer.as_ref().unwrap().deliveries().count, 1);
+
+        assert!(
+            votes::Entity::find_by_id(vote.id)
+                .one(&ctx.db)
+                .await
+                .unwrap()
+                .is_some(),
+            "Votes should be kept"
+        );
+        let assignments = vote_assignments::Entity::find()
+            .filter(vote_assignments::Column::SubmissionId.eq(submission.id))
+            .all(&ctx.db)
+            .await
+            .unwrap();
+        assert!(assignments.is_empty(), "Nobody should review it anymore");
+
+        let competition = prepare_data::ensure_competition(&ctx).await;
+        GenLeaderboard::generate(&ctx, &competition).await.unwrap();
+        let ranked = scores::Entity::find()
+            .filter(scores::Column::SubmissionId.eq(submission.id))
+            .all(&ctx.db)
+            .await
+            .unwrap();
+        assert!(ranked.is_empty(), "Should not be ranked");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn disqualifying_requires_a_reason() {
+    request::<App, _, _>(|request, ctx| async move {
+        let admin = prepare_data::init_user_login(&request, &ctx).await;
+        prepare_data::make_admin(&ctx, admin.user.id).await;
+        let submission = prepare_data::create_submission_for_user(&ctx, admin.user.id).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&admin.token);
+
+        let res = request
+            .post(&format!("/api/submissions/{}/disqualify", submission.id))
+            .add_header(auth_key, auth_value)
+            .json(&serde_json::json!({"reason": ""}))
+            .await;
+
+        assert_eq!(res.status_code(), 400, "Should require a reason");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn non_admin_cannot_disqualify() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::init_user_login(&request, &ctx).await;
+        let submission = prepare_data::create_submission_for_user(&ctx, user.user.id).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user.token);
+
+        let res = request
+            .post(&format!("/api/submissions/{}/disqualify", submission.id))
+            .add_header(auth_key, auth_value)
+            .json(&serde_json::json!({"reason": "Not mine to judge"}))
+            .await;
+
+        assert_eq!(res.status_code(), 403, "Only admins can disqualify");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn submission_within_grace_period_is_flagged_late() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::init_user_login(&request, &ctx).await;
+        prepare_data::set_submission_period_closed(&ctx).await;
+        prepare_data::set_grace_period(&ctx, 90).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user.token);
+
+        let params = prepare_data::create_submission_params().await;
+        let res = request
+            .post("/api/submissions")
+            .add_header(auth_key, auth_value)
+            .json(&params)
+            .await;
+
+        assert_eq!(
+            res.status_code(),
+            200,
+            "Should accept within the grace period"
+        );
+        let submission: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(submission["late"], true, "Should be flagged late");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn cannot_submit_after_grace_period() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::init_user_login(&request, &ctx).await;
+        prepare_data::set_submission_period_closed(&ctx).await;
+        prepare_data::set_grace_period(&ctx, 30).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user.token);
+
+        let params = prepare_data::create_submission_params().await;
+        let res = request
+            .post("/api/submissions")
+            .add_header(auth_key, auth_value)
+            .json(&params)
+            .await;
+
+        assert_eq!(
+            res.status_code(),
+            400,
+            "Should reject after the grace period"
+        );
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn submission_within_period_is_not_late() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::init_user_login(&request, &ctx).await;
+        prepare_data::set_submission_period_active(&ctx).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user.token);
+
+        let params = prepare_data::create_submission_params().await;
+        let res = request
+            .post("/api/submissions")
+            .add_header(auth_key, auth_value)
+            .json(&params)
+            .await;
+
+        assert_eq!(res.status_code(), 200);
+        let submission: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(submission["late"], false);
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn admin_listing_shows_late_submissions() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::init_user_login(&request, &ctx).await;
+        let admin =
+            prepare_data::init_user_login_with_email(&request, &ctx, "admin@test.com", "Admin")
+                .await;
+        prepare_data::make_admin(&ctx, admin.user.id).await;
+        prepare_data::set_submission_period_closed(&ctx).await;
+        prepare_data::set_grace_period(&ctx, 90).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user.token);
+        let params = prepare_data::create_submission_params().await;
+        request
+            .post("/api/submissions")
+            .add_header(auth_key, auth_value)
+            .json(&params)
+            .await;
+
+        let (auth_key, auth_value) = prepare_data::auth_header(&admin.token);
+        let res = request
+            .get("/api/submissions")
+            .add_header(auth_key, auth_value)
+            .await;
+
+        assert_eq!(res.status_code(), 200);
+        let listing: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        let listing = listing.as_array().unwrap();
+        assert_eq!(listing.len(), 1);
+        assert_eq!(listing[0]["late"], true);
+        assert_eq!(listing[0]["user_id"], user.user.id);
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn non_admin_cannot_list_submissions() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = prepare_data::init_user_login(&request, &ctx).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user.token);
+
+        let res = request
+            .get("/api/submissions")
+            .add_header(auth_key, auth_value)
+            .await;
+
+        assert_eq!(res.status_code(), 403, "Only admins can list submissions");
+    })
+    .await;
+}
@@ -0,0 +1,327 @@
+use bit_by_design::{
+    app::App,
+    models::_entities::{deadline_extensions, scores, submissions, team_invites, vote_assignments},
+    tasks::assign_submissions::AssignSubmissions,
+};
+use chrono::{Local, TimeDelta};
+use loco_rs::{prelude::*, TestServer};
+use serial_test::serial;
+
+use super::prepare_data::{self, LoggedInUser};
+
+/// Creates a team of `owner` and has every one of `members` join it through an invite.
+async fn create_team(
+    request: &TestServer,
+    ctx: &AppContext,
+    owner: &LoggedInUser,
+    members: &[&LoggedInUser],
+) -> i32 {
+    let (auth_key, auth_value) = prepare_data::auth_header(&owner.token);
+    let res = request
+        .post("/api/teams")
+        .add_header(auth_key.clone(), auth_value.clone())
+        .json(&serde_json::json!({"name": "Pixel Pushers"}))
+        .await;
+    assert_eq!(res.status_code(), 200, "Should create the team");
+    let team: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+    let team_id = team["id"].as_i64().unwrap();
+
+    for member in members {
+        let res = request
+            .post(&format!("/api/teams/{team_id}/invites"))
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&serde_json::json!({"email": member.user.email}))
+            .await;
+        assert_eq!(
+            res.status_code(),
+            200,
+            "Should invite {}",
+            member.user.email
+        );
+        let invite = team_invites::Entity::find_by_id(
+            serde_json::from_str::<serde_json::Value>(&res.text()).unwrap()["id"]
+                .as_i64()
+                .unwrap() as i32,
+        )
+        .one(&ctx.db)
+        .await
+        .unwrap()
+        .unwrap();
+
+        let (member_key, member_value) = prepare_data::auth_header(&member.token);
+        let res = request
+            .post(&format!("/api/teams/invites/{}/accept", invite.token))
+            .add_header(member_key, member_value)
+            .await;
+        assert_eq!(res.status_code(), 200, "Should join the team");
+    }
+    team_id as i32
+}
+
+#[tokio::test]
+#[serial]
+async fn can_form_team_through_invites() {
+    request::<App, _, _>(|request, ctx| async move {
+        let owner = prepare_data::init_user_login(&request, &ctx).await;
+        let member =
+            prepare_data::init_user_login_with_email(&request, &ctx, "member@test.com", "Member")
+                .await;
+        prepare_data::set_submission_period_active(&ctx).await;
+
+        create_team(&request, &ctx, &owner, &[&member]).await;
+        assert_eq!(
+            ctx.mailer.as_ref().unwrap().deliveries().count,
+            1,
+            "The invitee should get an email"
+        );
+
+        let (auth_key, auth_value) = prepare_data::auth_header(&member.token);
+        let res = request
+            .get("/api/teams/mine")
+            .add_header(auth_key, auth_value)
+            .await;
+        assert_eq!(res.status_code(), 200);
+        let team: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(team["name"], "Pixel Pushers");
+        let members: Vec<_> = team["members"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|member| member["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(members, ["loco", "Member"]);
+        assert!(team["invites"].as_array().unwrap().is_empty());
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn extension_lets_user_form_team_after_deadline() {
+    request::<App, _, _>(|request, ctx| async move {
+        let owner = prepare_data::init_user_login(&request, &ctx).await;
+        let other =
+            prepare_data::init_user_login_with_email(&request, &ctx, "other@test.com", "Other")
+                .await;
+        prepare_data::set_submission_period_closed(&ctx).await;
+        let competition = prepare_data::ensure_competition(&ctx).await;
+        deadline_extensions::ActiveModel {
+            extended_until: Set(DateTimeWithTimeZone::from(Local::now()) + TimeDelta::hours(1)),
+            competition_id: Set(competition.id),
+            user_id: Set(owner.user.id),
+            granted_by_id: Set(other.user.id),
+            ..Default::default()
+        }
+        .insert(&ctx.db)
+        .await
+        .unwrap();
+
+        let (auth_key, auth_value) = prepare_data::auth_header(&other.token);
+        let res = request
+            .post("/api/teams")
+            .add_header(auth_key, auth_value)
+            .json(&serde_json::json!({"name": "Too Late"}))
+            .await;
+        assert_eq!(res.status_code(), 400, "Submissions are closed");
+
+        create_team(&request, &ctx, &owner, &[]).await;
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn cannot_accept_invite_for_another_email() {
+    request::<App, _, _>(|request, ctx| async move {
+        let owner = prepare_data::init_user_login(&request, &ctx).await;
+        let other =
+            prepare_data::init_user_login_with_email(&request, &ctx, "other@test.com", "Other")
+                .await;
+        prepare_data::set_submission_period_active(&ctx).await;
+        let team_id = create_team(&request, &ctx, &owner, &[]).await;
+
+        let (auth_key, auth_value) = prepare_data::auth_header(&owner.token);
+        request
+            .post(&format!("/api/teams/{team_id}/invites"))
+            .add_header(auth_key, auth_value)
+            .json(&serde_json::json!({"email": "invited@test.com"}))
+            .await;
+        let invite = team_invites::Entity::find()
+            .one(&ctx.db)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let (auth_key, auth_value) = prepare_data::auth_header(&other.token);
+        let res = request
+            .post(&format!("/api/teams/invites/{}/accept", invite.token))
+            .add_header(auth_key, auth_value)
+            .await;
+        assert_eq!(res.status_code(), 403, "The invite is for someone else");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn cannot_join_team_after_submitting_alone() {
+    request::<App, _, _>(|request, ctx| async move {
+        let owner = prepare_data::init_user_login(&request, &ctx).await;
+        let member =
+            prepare_data::init_user_login_with_email(&request, &ctx, "member@test.com", "Member")
+                .await;
+        prepare_data::set_submission_period_active(&ctx).await;
+        prepare_data::create_submission_for_user(&ctx, member.user.id).await;
+        let team_id = create_team(&request, &ctx, &owner, &[]).await;
+
+        let (auth_key, auth_value) = prepare_data::auth_header(&owner.token);
+        request
+            .post(&format!("/api/teams/{team_id}/invites"))
+            .add_header(auth_key, auth_value)
+            .json(&serde_json::json!({"email": member.user.email}))
+            .await;
+        let invite = team_invites::Entity::find()
+            .one(&ctx.db)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let (auth_key, auth_value) = prepare_data::auth_header(&member.token);
+        let res = request
+            .post(&format!("/api/teams/invites/{}/accept", invite.token))
+            .add_header(auth_key, auth_value)
+            .await;
+        assert_eq!(res.status_code(), 400, "Should withdraw first");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn members_share_the_team_submission() {
+    request::<App, _, _>(|request, ctx| async move {
+        let owner = prepare_data::init_user_login(&request, &ctx).await;
+        let member =
+            prepare_data::init_user_login_with_email(&request, &ctx, "member@test.com", "Member")
+                .await;
+        prepare_data::set_submission_period_active(&ctx).await;
+        let team_id = create_team(&request, &ctx, &owner, &[&member]).await;
+
+        let params = prepare_data::create_submission_params().await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&owner.token);
+        let res = request
+            .post("/api/submissions")
+            .add_header(auth_key, auth_value)
+            .json(&params)
+            .await;
+        assert_eq!(res.status_code(), 200, "Should submit for the team");
+        let submission: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(submission["team_id"], team_id);
+
+        let (auth_key, auth_value) = prepare_data::auth_header(&member.token);
+        let res = request
+            .post("/api/submissions")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&params)
+            .await;
+        assert_eq!(res.status_code(), 400, "The team already submitted");
+
+        let res = request
+            .get("/api/submissions/mine")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        let mine: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(mine["id"], submission["id"]);
+
+        let mut update = params.clone();
+        update["key_trade_off"] = serde_json::json!("Speed over polish");
+        let res = request
+            .put(&format!("/api/submissions/{}", submission["id"]))
+            .add_header(auth_key, auth_value)
+            .json(&update)
+            .await;
+        assert_eq!(res.status_code(), 200, "Members can edit the submission");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn members_do_not_review_their_team_submission() {
+    request::<App, _, _>(|request, ctx| async move {
+        let owner = prepare_data::init_user_login(&request, &ctx).await;
+        let member =
+            prepare_data::init_user_login_with_email(&request, &ctx, "member@test.com", "Member")
+                .await;
+        let others = prepare_data::create_test_users(&request, &ctx, 3).await;
+        prepare_data::set_submission_period_active(&ctx).await;
+        create_team(&request, &ctx, &owner, &[&member]).await;
+
+        let (auth_key, auth_value) = prepare_data::auth_header(&owner.token);
+        let res = request
+            .post("/api/submissions")
+            .add_header(auth_key, auth_value)
+            .json(&prepare_data::create_submission_params().await)
+            .await;
+        let team_submission: submissions::Model = serde_json::from_str(&res.text()).unwrap();
+        for other in &others {
+            prepare_data::create_submission_for_user(&ctx, other.user.id).await;
+        }
+
+        let competition = prepare_data::ensure_competition(&ctx).await;
+        AssignSubmissions::assign(&ctx, &competition).await.unwrap();
+
+        let reviewers: Vec<i32> = vote_assignments::Entity::find()
+            .filter(vote_assignments::Column::SubmissionId.eq(team_submission.id))
+            .all(&ctx.db)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|assignment| assignment.user_id)
+            .collect();
+        assert!(
+            !reviewers.is_empty(),
+            "The team submission should be reviewed"
+        );
+        assert!(
+            !reviewers.contains(&owner.user.id) && !reviewers.contains(&member.user.id),
+            "Members should not review their own team's submission"
+        );
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn leaderboard_lists_every_member() {
+    request::<App, _, _>(|request, ctx| async move {
+        let owner = prepare_data::init_user_login(&request, &ctx).await;
+        let member =
+            prepare_data::init_user_login_with_email(&request, &ctx, "member@test.com", "Member")
+                .await;
+        prepare_data::set_submission_period_active(&ctx).await;
+        let submission = prepare_data::create_submission_for_user(&ctx, owner.user.id).await;
+        create_team(&request, &ctx, &owner, &[&member]).await;
+
+        scores::ActiveModel {
+            competition_id: Set(submission.competition_id),
+            submission_id: Set(submission.id),
+            final_score: Set(800),
+            ..Default::default()
+        }
+        .insert(&ctx.db)
+        .await
+        .unwrap();
+        prepare_data::enable_leaderboard(&ctx).await;
+
+        let res = request.get("/api/scores").await;
+        let body: Vec<serde_json::Value> = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(body[0]["team_name"], "Pixel Pushers");
+        assert_eq!(
+            body[0]["member_names"],
+            serde_json::json!(["loco", "Member"])
+        );
+        assert_eq!(body[0]["user_name"], "loco, Member");
+    })
+    .await;
+}
@@ -1,5 +1,9 @@
-use bit_by_design::app::App;
-use loco_rs::testing::prelude::*;
+use bit_by_design::{
+    app::App, assignment::Constraints, models::_entities::vote_assignments,
+    tasks::rebalance_assignments::RebalanceAssignments,
+};
+use chrono::{Duration, Utc};
+use loco_rs::prelude::*;
 use serial_test::serial;
 
 use super::prepare_data;
@@ -101,3 +105,171 @@ async fn requires_authentication() {
     })
     .await;
 }
+
+#[tokio::test]
+#[serial]
+async fn rebalance_tops_up_submissions_of_inactive_reviewers() {
+    request::<App, _, _>(|request, ctx| async move {
+        // User 0 submitted; user 1 voted on it, user 2 never did
+        let users = prepare_data::create_test_users(&request, &ctx, 5).await;
+        let submission = prepare_data::create_submission_for_user(&ctx, users[0].user.id).await;
+        let voted =
+            prepare_data::create_vote_assignment(&ctx, users[1].user.id, submission.id).await;
+        prepare_data::create_vote(&ctx, users[1].user.id, submission.id).await;
+        prepare_data::create_vote_assignment(&ctx, users[2].user.id, submission.id).await;
+        prepare_data::set_voting_period_active(&ctx).await;
+        let competition = prepare_data::ensure_competition(&ctx).await;
+        let constraints = Constraints::new(2);
+
+        // Not stale yet
+        let rebalance = RebalanceAssignments::rebalance_with(
+            &ctx,
+            &competition,
+            &constraints,
+            Duration::hours(48),
+            Utc::now(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(rebalance.stale, 0);
+        assert!(rebalance.added.is_empty(), "Nothing to top up yet");
+
+        let rebalance = RebalanceAssignments::rebalance_with(
+            &ctx,
+            &competition,
+            &constraints,
+            Duration::zero(),
+            Utc::now(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(rebalance.stale, 1);
+        assert_eq!(rebalance.inactive_reviewers, vec![users[2].user.id]);
+        assert_eq!(
+            rebalance.added.len(),
+            1,
+            "One reviewer to make up for user 2"
+        );
+        let added = rebalance.added[0];
+        assert_eq!(added.submission_id, submission.id);
+        assert!(
+            [users[3].user.id, users[4].user.id].contains(&added.reviewer_id),
+            "Only active, unassigned reviewers are added"
+        );
+        assert!(rebalance.understaffed.is_empty());
+
+        let assignments = vote_assignments::Entity::find()
+            .filter(vote_assignments::Column::SubmissionId.eq(submission.id))
+            .all(&ctx.db)
+            .await
+            .unwrap();
+        assert_eq!(assignments.len(), 3, "Existing assignments are kept");
+        assert!(
+            assignments.contains(&voted),
+            "Voted assignment is untouched"
+        );
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn rebalance_does_nothing_outside_voting_period() {
+    request::<App, _, _>(|request, ctx| async move {
+        let users = prepare_data::create_test_users(&request, &ctx, 3).await;
+        let submission = prepare_data::create_submission_for_user(&ctx, users[0].user.id).await;
+        prepare_data::create_vote_assignment(&ctx, users[1].user.id, submission.id).await;
+        prepare_data::set_voting_period_closed(&ctx).await;
+        let competition = prepare_data::ensure_competition(&ctx).await;
+
+        let rebalance = RebalanceAssignments::rebalance_with(
+            &ctx,
+            &competition,
+            &Constraints::new(2),
+            Duration::zero(),
+            Utc::now(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(rebalance.stale, 0);
+        assert!(rebalance.added.is_empty(), "Voting is over");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn admin_can_manage_assignments() {
+    request::<App, _, _>(|request, ctx| async move {
+        let users = prepare_data::create_test_users(&request, &ctx, 3).await;
+        prepare_data::make_admin(&ctx, users[0].user.id).await;
+        let submission = prepare_data::create_submission_for_user(&ctx, users[1].user.id).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&users[0].token);
+
+        let params = serde_json::json!({
+            "user_id": users[2].user.id,
+            "submission_id": submission.id
+        });
+        let res = request
+            .post("/api/vote_assignments")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&params)
+            .await;
+        assert_eq!(res.status_code(), 200, "Admin should assign a reviewer");
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(body["competition_id"], submission.competition_id);
+        let id = body["id"].as_i64().unwrap();
+
+        let params = serde_json::json!({
+            "user_id": users[0].user.id,
+            "submission_id": submission.id
+        });
+        let res = request
+            .put(&format!("/api/vote_assignments/{id}"))
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&params)
+            .await;
+        assert_eq!(res.status_code(), 200, "Admin should reassign");
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        assert_eq!(body["user_id"], users[0].user.id);
+
+        let res = request
+            .delete(&format!("/api/vote_assignments/{id}"))
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        assert_eq!(res.status_code(), 200, "Admin should remove assignment");
+
+        let res = request
+            .get(&format!("/api/vote_assignments/{id}"))
+            .add_header(auth_key, auth_value)
+            .await;
+        assert_eq!(res.status_code(), 404, "Assignment should be gone");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn non_admin_cannot_manage_assignments() {
+    request::<App, _, _>(|request, ctx| async move {
+        let users = prepare_data::create_test_users(&request, &ctx, 2).await;
+        let submission = prepare_data::create_submission_for_user(&ctx, users[1].user.id).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&users[0].token);
+
+        let params = serde_json::json!({
+            "user_id": users[0].user.id,
+            "submission_id": submission.id
+        });
+        let res = request
+            .post("/api/vote_assignments")
+            .add_header(auth_key, auth_value)
+            .json(&params)
+            .await;
+        assert_eq!(res.status_code(), 403, "Should reject non-admin");
+
+        let res = request.post("/api/vote_assignments").json(&params).await;
+        assert_eq!(res.status_code(), 401, "Should require authentication");
+    })
+    .await;
+}
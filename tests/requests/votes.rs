@@ -1,5 +1,9 @@
-use bit_by_design::app::App;
+use bit_by_design::{
+    app::App,
+    models::_entities::{criteria, vote_scores, votes},
+};
 use loco_rs::testing::prelude::*;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
 use serial_test::serial;
 
 use super::prepare_data;
@@ -32,7 +36,7 @@ async fn can_create_vote_during_voting_period() {
 
         let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
         assert_eq!(body["submission_id"], submission.id);
-        assert_eq!(body["problem_fit_score"], 4);
+        assert_eq!(body["scores"]["problem_fit"], 4);
     })
     .await;
 }
@@ -96,6 +100,63 @@ async fn cannot_vote_twice_on_same_submission() {
     .await;
 }
 
+#[tokio::test]
+#[serial]
+async fn can_update_own_vote() {
+    request::<App, _, _>(|request, ctx| async move {
+        // Setup: Create users, submissions, existing vote
+        let user1 =
+            prepare_data::init_user_login_with_email(&request, &ctx, "user1@test.com", "User 1")
+                .await;
+        let user2 =
+            prepare_data::init_user_login_with_email(&request, &ctx, "user2@test.com", "User 2")
+                .await;
+        let submission = prepare_data::create_submission_for_user(&ctx, user1.user.id).await;
+        prepare_data::create_vote_assignment(&ctx, user2.user.id, submission.id).await;
+        let vote = prepare_data::create_vote(&ctx, user2.user.id, submission.id).await;
+        prepare_data::set_voting_period_active(&ctx).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user2.token);
+
+        // Change the vote, twice, with different scores
+        let mut params = prepare_data::create_vote_params(submission.id);
+        for score in [1, 2] {
+            params["scores"]["clarity"] = score.into();
+            let res = request
+                .put(&format!("/api/votes/{}", vote.id))
+                .add_header(auth_key.clone(), auth_value.clone())
+                .json(&params)
+                .await;
+            assert_eq!(res.status_code(), 200, "Should update the vote");
+        }
+
+        let votes = votes::Entity::find()
+            .filter(votes::Column::UserId.eq(user2.user.id))
+            .all(&ctx.db)
+            .await
+            .unwrap();
+        assert_eq!(votes.len(), 1, "Should not duplicate the vote");
+        assert_eq!(votes[0].id, vote.id);
+
+        let scores = vote_scores::Entity::find()
+            .filter(vote_scores::Column::VoteId.eq(vote.id))
+            .find_also_related(criteria::Entity)
+            .all(&ctx.db)
+            .await
+            .unwrap();
+        assert_eq!(
+            scores.len(),
+            5,
+            "Should replace the scores, not add to them"
+        );
+        let clarity = scores
+            .iter()
+            .find(|(_, criterion)| criterion.as_ref().unwrap().key == "clarity")
+            .unwrap();
+        assert_eq!(clarity.0.score, 2, "Should store the latest score");
+    })
+    .await;
+}
+
 #[tokio::test]
 #[serial]
 async fn rejects_invalid_score_too_high() {
@@ -115,11 +176,13 @@ async fn rejects_invalid_score_too_high() {
         // Create vote with score > 5
         let params = serde_json::json!({
             "submission_id": submission.id,
-            "problem_fit_score": 6,  // Invalid - too high
-            "clarity_score": 5,
-            "style_interpretation_score": 3,
-            "originality_score": 4,
-            "overall_quality_score": 4
+            "scores": {
+                "problem_fit": 6,  // Invalid - too high
+                "clarity": 5,
+                "style_interpretation": 3,
+                "originality": 4,
+                "overall_quality": 4
+            }
         });
         let res = request
             .post("/api/votes")
@@ -151,11 +214,13 @@ async fn rejects_invalid_score_negative() {
         // Create vote with negative score
         let params = serde_json::json!({
             "submission_id": submission.id,
-            "problem_fit_score": -1,  // Invalid - negative
-            "clarity_score": 5,
-            "style_interpretation_score": 3,
-            "originality_score": 4,
-            "overall_quality_score": 4
+            "scores": {
+                "problem_fit": -1,  // Invalid - negative
+                "clarity": 5,
+                "style_interpretation": 3,
+                "originality": 4,
+                "overall_quality": 4
+            }
         });
         let res = request
             .post("/api/votes")
@@ -168,6 +233,59 @@ async fn rejects_invalid_score_negative() {
     .await;
 }
 
+#[tokio::test]
+#[serial]
+async fn reports_every_invalid_score() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user1 =
+            prepare_data::init_user_login_with_email(&request, &ctx, "user1@test.com", "User 1")
+                .await;
+        let user2 =
+            prepare_data::init_user_login_with_email(&request, &ctx, "user2@test.com", "User 2")
+                .await;
+        let submission = prepare_data::create_submission_for_user(&ctx, user1.user.id).await;
+        prepare_data::create_vote_assignment(&ctx, user2.user.id, submission.id).await;
+        prepare_data::set_voting_period_active(&ctx).await;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user2.token);
+
+        let params = serde_json::json!({
+            "submission_id": submission.id,
+            "scores": {
+                "problem_fit": 6,
+                "clarity": -1,
+                "style_interpretation": 3,
+                "originality": 4,
+                "polish": 4
+            }
+        });
+        let res = request
+            .post("/api/votes")
+            .add_header(auth_key, auth_value)
+            .json(&params)
+            .await;
+
+        assert_eq!(res.status_code(), 400);
+        let body: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+        let errors = body["errors"].as_object().unwrap();
+        let mut fields: Vec<_> = errors.keys().map(String::as_str).collect();
+        fields.sort_unstable();
+        assert_eq!(
+            fields,
+            [
+                "scores.clarity",
+                "scores.overall_quality",
+                "scores.polish",
+                "scores.problem_fit"
+            ]
+        );
+        assert_eq!(errors["scores.problem_fit"][0]["code"], "range");
+        assert_eq!(errors["scores.problem_fit"][0]["params"]["max"], 5);
+        assert_eq!(errors["scores.overall_quality"][0]["code"], "required");
+        assert_eq!(errors["scores.polish"][0]["code"], "unknown_criterion");
+    })
+    .await;
+}
+
 #[tokio::test]
 #[serial]
 async fn accepts_boundary_scores() {
@@ -187,11 +305,13 @@ async fn accepts_boundary_scores() {
         // Create vote with boundary scores (0 and 5)
         let params = serde_json::json!({
             "submission_id": submission.id,
-            "problem_fit_score": 0,  // Valid minimum
-            "clarity_score": 5,      // Valid maximum
-            "style_interpretation_score": 0,
-            "originality_score": 5,
-            "overall_quality_score": 3
+            "scores": {
+                "problem_fit": 0,  // Valid minimum
+                "clarity": 5,      // Valid maximum
+                "style_interpretation": 0,
+                "originality": 5,
+                "overall_quality": 3
+            }
         });
         let res = request
             .post("/api/votes")
@@ -1,5 +1,9 @@
-use bit_by_design::app::App;
-use loco_rs::{task, testing::prelude::*};
+use bit_by_design::{
+    app::App,
+    models::competitions::{self, Phase},
+};
+use chrono::{TimeDelta, Utc};
+use loco_rs::{prelude::*, task};
 
 use loco_rs::boot::run_task;
 use serial_test::serial;
@@ -17,3 +21,40 @@ async fn test_can_run_assign_submissions() {
     .await
     .is_ok());
 }
+
+#[tokio::test]
+#[serial]
+async fn running_assign_submissions_moves_the_phase() {
+    let boot = boot_test::<App>().await.unwrap();
+    let ctx = &boot.app_context;
+    let now = DateTimeWithTimeZone::from(Utc::now());
+    let competition = competitions::ActiveModel {
+        name: Set("Task Round".to_string()),
+        slug: Set("task-round".to_string()),
+        submission_start: Set(Some(now - TimeDelta::hours(2))),
+        submission_end: Set(Some(now - TimeDelta::hours(1))),
+        ..Default::default()
+    }
+    .insert(&ctx.db)
+    .await
+    .unwrap();
+
+    let vars =
+        task::Vars::from_cli_args(vec![("competition".to_string(), competition.slug.clone())]);
+    run_task::<App>(ctx, Some(&"assign_submissions".to_string()), &vars)
+        .await
+        .unwrap();
+
+    let competition = competitions::Entity::find_by_id(competition.id)
+        .one(&ctx.db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(competition.entered(), Phase::Assigned);
+    assert!(
+        run_task::<App>(ctx, Some(&"assign_submissions".to_string()), &vars)
+            .await
+            .is_ok(),
+        "Can be run again"
+    );
+}
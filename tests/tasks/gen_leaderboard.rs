@@ -1,5 +1,9 @@
-use bit_by_design::app::App;
-use loco_rs::{task, testing::prelude::*};
+use bit_by_design::{
+    app::App,
+    models::competitions::{self, Phase},
+};
+use chrono::{TimeDelta, Utc};
+use loco_rs::{prelude::*, task};
 
 use loco_rs::boot::run_task;
 use serial_test::serial;
@@ -17,3 +21,43 @@ async fn test_can_run_gen_leaderboard() {
     .await
     .is_ok());
 }
+
+#[tokio::test]
+#[serial]
+async fn running_gen_leaderboard_moves_the_phase() {
+    let boot = boot_test::<App>().await.unwrap();
+    let ctx = &boot.app_context;
+    let now = DateTimeWithTimeZone::from(Utc::now());
+    let competition = competitions::ActiveModel {
+        name: Set("Task Round".to_string()),
+        slug: Set("task-round".to_string()),
+        submission_start: Set(Some(now - TimeDelta::hours(4))),
+        submission_end: Set(Some(now - TimeDelta::hours(3))),
+        voting_start: Set(Some(now - TimeDelta::hours(2))),
+        voting_end: Set(Some(now - TimeDelta::hours(1))),
+        entered_phase: Set("assigned".to_string()),
+        ..Default::default()
+    }
+    .insert(&ctx.db)
+    .await
+    .unwrap();
+
+    let vars =
+        task::Vars::from_cli_args(vec![("competition".to_string(), competition.slug.clone())]);
+    run_task::<App>(ctx, Some(&"gen_leaderboard".to_string()), &vars)
+        .await
+        .unwrap();
+
+    let competition = competitions::Entity::find_by_id(competition.id)
+        .one(&ctx.db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(competition.entered(), Phase::Scored);
+    assert!(
+        run_task::<App>(ctx, Some(&"gen_leaderboard".to_string()), &vars)
+            .await
+            .is_ok(),
+        "Can be run again"
+    );
+}
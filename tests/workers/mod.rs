@@ -1 +1 @@
-
+mod previews;
@@ -0,0 +1,17 @@
+use bit_by_design::{
+    app::App,
+    workers::previews::{PreviewWorker, PreviewWorkerArgs},
+};
+use loco_rs::{bgworker::BackgroundWorker, testing::prelude::*};
+use serial_test::serial;
+
+#[tokio::test]
+#[serial]
+async fn skips_missing_submissions() {
+    let boot = boot_test::<App>().await.unwrap();
+
+    assert!(PreviewWorker::build(&boot.app_context)
+        .perform(PreviewWorkerArgs { submission_id: 1 })
+        .await
+        .is_ok());
+}